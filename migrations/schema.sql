@@ -50,48 +50,187 @@ CREATE TABLE IF NOT EXISTS budget_members (
 CREATE TABLE IF NOT EXISTS categories (
     id CHAR(36) PRIMARY KEY,
     budget_id CHAR(36) NOT NULL,
+    parent_id CHAR(36) NULL,
     name VARCHAR(255) NOT NULL,
     kind VARCHAR(20) NOT NULL,
+    is_hidden BOOLEAN NOT NULL DEFAULT FALSE,
     color VARCHAR(7) NULL,
     icon VARCHAR(50) NULL,
+    rollover_policy VARCHAR(20) NOT NULL DEFAULT 'none',
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
     FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (parent_id) REFERENCES categories(id) ON DELETE RESTRICT,
     INDEX idx_categories_budget (budget_id),
+    INDEX idx_categories_parent (parent_id),
     INDEX idx_categories_kind (kind)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
+-- Scheduled transfer templates (executed into budget_transfers by the scheduler)
+CREATE TABLE IF NOT EXISTS scheduled_transfers (
+    id CHAR(36) PRIMARY KEY,
+    from_budget_id CHAR(36) NOT NULL,
+    to_budget_id CHAR(36) NOT NULL,
+    from_category_id CHAR(36) NOT NULL,
+    to_category_id CHAR(36) NOT NULL,
+    amount_minor BIGINT NOT NULL, -- in the source budget currency
+    exchange_rate DOUBLE NULL, -- fixed rate; NULL looks up the rate on each date
+    fee_minor BIGINT NOT NULL DEFAULT 0,
+    fee_category_id CHAR(36) NULL,
+    note TEXT NULL,
+    frequency VARCHAR(10) NOT NULL,
+    interval_count INT NOT NULL DEFAULT 1,
+    by_month_day INT NULL,
+    start_date DATE NOT NULL,
+    end_date DATE NULL,
+    occurrence_count INT NULL,
+    generated_through DATE NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by CHAR(36) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (from_budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (to_budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_category_id) REFERENCES categories(id) ON DELETE RESTRICT,
+    FOREIGN KEY (to_category_id) REFERENCES categories(id) ON DELETE RESTRICT,
+    FOREIGN KEY (fee_category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_scheduled_transfers_from (from_budget_id),
+    INDEX idx_scheduled_transfers_to (to_budget_id),
+    INDEX idx_scheduled_transfers_due (active, generated_through)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
 -- Budget transfers table
 CREATE TABLE IF NOT EXISTS budget_transfers (
     id CHAR(36) PRIMARY KEY,
     from_budget_id CHAR(36) NOT NULL,
     to_budget_id CHAR(36) NOT NULL,
-    amount_minor BIGINT NOT NULL,
+    amount_minor BIGINT NOT NULL, -- sent, in the source budget currency
     currency_code CHAR(3) NOT NULL,
+    to_amount_minor BIGINT NOT NULL, -- received, in the destination budget currency
+    to_currency_code CHAR(3) NOT NULL,
+    exchange_rate DOUBLE NULL, -- NULL when both budgets share a currency
+    fee_minor BIGINT NOT NULL DEFAULT 0, -- charged to the source budget on top of amount_minor
     transfer_date DATE NOT NULL,
     note TEXT NULL,
     created_by CHAR(36) NOT NULL,
     created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NULL,
+    deleted_at DATETIME NULL,
+    scheduled_transfer_id CHAR(36) NULL,
+    scheduled_date DATE NULL,
     FOREIGN KEY (from_budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
     FOREIGN KEY (to_budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
     FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (scheduled_transfer_id) REFERENCES scheduled_transfers(id) ON DELETE SET NULL,
+    UNIQUE KEY unique_scheduled_occurrence (scheduled_transfer_id, scheduled_date),
     INDEX idx_transfers_from_budget (from_budget_id),
     INDEX idx_transfers_to_budget (to_budget_id),
     INDEX idx_transfers_date (transfer_date)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
+-- Category limits table (planned amount per category, effective from a month onwards)
+CREATE TABLE IF NOT EXISTS category_limits (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    period_start DATE NOT NULL,
+    planned_minor BIGINT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_category_period (category_id, period_start),
+    INDEX idx_category_limits_budget (budget_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Category month snapshots (cached envelope results for closed months)
+CREATE TABLE IF NOT EXISTS category_month_snapshots (
+    budget_id CHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    month_start DATE NOT NULL,
+    planned_minor BIGINT NOT NULL,
+    spent_minor BIGINT NOT NULL,
+    rollover_minor BIGINT NOT NULL,
+    available_minor BIGINT NOT NULL,
+    computed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (category_id, month_start),
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    INDEX idx_snapshots_budget_month (budget_id, month_start)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Recurring entry templates (materialized into entries by the scheduler)
+CREATE TABLE IF NOT EXISTS recurring_entries (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    amount_minor BIGINT NOT NULL,
+    currency_code CHAR(3) NOT NULL,
+    description TEXT,
+    counterparty VARCHAR(255),
+    frequency VARCHAR(10) NOT NULL,
+    interval_count INT NOT NULL DEFAULT 1,
+    by_month_day INT NULL,
+    start_date DATE NOT NULL,
+    end_date DATE NULL,
+    occurrence_count INT NULL,
+    generated_through DATE NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by CHAR(36) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE RESTRICT,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_recurring_budget (budget_id),
+    INDEX idx_recurring_due (active, generated_through)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Payee directory; every payee's own name is also stored as an alias so
+-- free-text counterparties resolve through payee_aliases alone
+CREATE TABLE IF NOT EXISTS payees (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    default_category_id CHAR(36) NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (default_category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    INDEX idx_payees_budget (budget_id, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS payee_aliases (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    payee_id CHAR(36) NOT NULL,
+    alias VARCHAR(255) NOT NULL,
+    normalized VARCHAR(255) NOT NULL, -- lowercased, whitespace collapsed
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (payee_id) REFERENCES payees(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_payee_alias (budget_id, normalized)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
 -- Entries table
 CREATE TABLE IF NOT EXISTS entries (
     id CHAR(36) PRIMARY KEY,
     budget_id CHAR(36) NOT NULL,
     category_id CHAR(36) NOT NULL,
     kind VARCHAR(20) NOT NULL,
-    amount_minor BIGINT NOT NULL,
+    amount_minor BIGINT NOT NULL, -- in currency_code
     currency_code CHAR(3) NOT NULL,
+    base_amount_minor BIGINT NOT NULL, -- amount_minor converted to the budget currency
+    exchange_rate DOUBLE NULL, -- units of budget currency per unit of currency_code; NULL when they are the same
     entry_date DATE NOT NULL,
     description TEXT,
     counterparty VARCHAR(255),
+    payee_id CHAR(36) NULL,
     transfer_id CHAR(36) NULL,
+    transfer_leg VARCHAR(8) NULL, -- 'from', 'to' or 'fee' within the transfer
+    recurring_id CHAR(36) NULL,
+    recurring_date DATE NULL,
     comment_count INT DEFAULT 0,
     attachment_count INT DEFAULT 0,
     created_by CHAR(36) NOT NULL,
@@ -103,15 +242,37 @@ CREATE TABLE IF NOT EXISTS entries (
     FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE RESTRICT,
     FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
     FOREIGN KEY (transfer_id) REFERENCES budget_transfers(id) ON DELETE SET NULL,
+    FOREIGN KEY (recurring_id) REFERENCES recurring_entries(id) ON DELETE SET NULL,
+    FOREIGN KEY (payee_id) REFERENCES payees(id) ON DELETE SET NULL,
+    UNIQUE KEY unique_recurring_occurrence (recurring_id, recurring_date),
     INDEX idx_entries_budget (budget_id),
     INDEX idx_entries_category (category_id),
     INDEX idx_entries_date (entry_date),
     INDEX idx_entries_kind (kind),
     INDEX idx_entries_created_by (created_by),
+    INDEX idx_entries_payee (payee_id),
     INDEX idx_entries_transfer (transfer_id),
     INDEX idx_entries_deleted (deleted_at)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
+-- Entry splits table (line items of one entry, each with its own category; amounts sum to the entry)
+CREATE TABLE IF NOT EXISTS entry_splits (
+    id CHAR(36) PRIMARY KEY,
+    entry_id CHAR(36) NOT NULL,
+    budget_id CHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    amount_minor BIGINT NOT NULL,
+    base_amount_minor BIGINT NOT NULL, -- share of the entry's base_amount_minor
+    note VARCHAR(255) NULL,
+    position INT NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE RESTRICT,
+    INDEX idx_splits_entry (entry_id),
+    INDEX idx_splits_category (category_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
 -- Entry comments table
 CREATE TABLE IF NOT EXISTS entry_comments (
     id CHAR(36) PRIMARY KEY,
@@ -196,3 +357,201 @@ CREATE TABLE IF NOT EXISTS password_resets (
     INDEX idx_password_resets_token (token_hash),
     INDEX idx_password_resets_expires (expires_at)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Import dedupe keys (bank transaction ids already imported into a budget)
+CREATE TABLE IF NOT EXISTS import_dedupe_keys (
+    budget_id CHAR(36) NOT NULL,
+    dedupe_key VARCHAR(255) NOT NULL,
+    entry_id CHAR(36) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (budget_id, dedupe_key),
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    INDEX idx_dedupe_entry (entry_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Tags (per-budget labels that cut across categories)
+CREATE TABLE IF NOT EXISTS tags (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    color VARCHAR(7) NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    UNIQUE KEY uq_tags_budget_name (budget_id, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Entry <-> tag links
+CREATE TABLE IF NOT EXISTS entry_tags (
+    entry_id CHAR(36) NOT NULL,
+    tag_id CHAR(36) NOT NULL,
+    PRIMARY KEY (entry_id, tag_id),
+    FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE,
+    INDEX idx_entry_tags_tag (tag_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- User-defined category templates for seeding new budgets
+CREATE TABLE IF NOT EXISTS category_templates (
+    id CHAR(36) PRIMARY KEY,
+    owner_id CHAR(36) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    budget_type VARCHAR(20) NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (owner_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_category_templates_owner (owner_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS category_template_items (
+    id CHAR(36) PRIMARY KEY,
+    template_id CHAR(36) NOT NULL,
+    parent_id CHAR(36) NULL, -- another item of the same template
+    name VARCHAR(255) NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    color VARCHAR(7) NULL,
+    icon VARCHAR(50) NULL,
+    rollover_policy VARCHAR(20) NOT NULL DEFAULT 'none',
+    position INT NOT NULL DEFAULT 0,
+    FOREIGN KEY (template_id) REFERENCES category_templates(id) ON DELETE CASCADE,
+    INDEX idx_template_items_template (template_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Auto-categorization rules, applied in priority order (lowest first)
+CREATE TABLE IF NOT EXISTS entry_rules (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    priority INT NOT NULL DEFAULT 100,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    counterparty_pattern VARCHAR(255) NULL,
+    counterparty_match VARCHAR(20) NOT NULL DEFAULT 'contains',
+    description_regex VARCHAR(500) NULL,
+    min_amount_minor BIGINT NULL,
+    max_amount_minor BIGINT NULL,
+    kind VARCHAR(20) NULL,
+    set_category_id CHAR(36) NULL,
+    set_counterparty VARCHAR(255) NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (set_category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    INDEX idx_entry_rules_budget (budget_id, priority)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS entry_rule_tags (
+    rule_id CHAR(36) NOT NULL,
+    tag_id CHAR(36) NOT NULL,
+    PRIMARY KEY (rule_id, tag_id),
+    FOREIGN KEY (rule_id) REFERENCES entry_rules(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Exchange rates of a budget: one unit of base_currency is worth `rate` units of quote_currency
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    rate_date DATE NOT NULL,
+    base_currency CHAR(3) NOT NULL,
+    quote_currency CHAR(3) NOT NULL,
+    rate DOUBLE NOT NULL,
+    source VARCHAR(20) NOT NULL DEFAULT 'manual', -- manual, csv, ecb
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_exchange_rate (budget_id, base_currency, quote_currency, rate_date),
+    INDEX idx_exchange_rates_quote (budget_id, quote_currency, rate_date)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Savings goals of saving budgets; progress is computed from entries
+CREATE TABLE IF NOT EXISTS savings_goals (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    target_amount_minor BIGINT NOT NULL, -- in the budget currency
+    target_date DATE NULL,
+    start_date DATE NULL, -- entries before it do not count; NULL counts the whole history
+    note TEXT NULL,
+    created_by CHAR(36) NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NULL,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_savings_goals_budget (budget_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Categories a goal counts (with their subcategories); none means the whole budget
+CREATE TABLE IF NOT EXISTS savings_goal_categories (
+    goal_id CHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    PRIMARY KEY (goal_id, category_id),
+    FOREIGN KEY (goal_id) REFERENCES savings_goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Debts tracked in debt budgets; expense entries in the payment category pay them down
+CREATE TABLE IF NOT EXISTS debts (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    principal_minor BIGINT NOT NULL, -- balance on opened_on, in the budget currency
+    apr DOUBLE NOT NULL DEFAULT 0, -- annual percentage rate, e.g. 19.99
+    minimum_payment_minor BIGINT NOT NULL DEFAULT 0,
+    due_day TINYINT NOT NULL DEFAULT 1, -- clamped to the last day of shorter months
+    category_id CHAR(36) NULL,
+    opened_on DATE NULL, -- payments before it do not count; NULL counts the whole history
+    note TEXT NULL,
+    created_by CHAR(36) NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NULL,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_debts_budget (budget_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Securities held in invest budgets, one row per symbol
+CREATE TABLE IF NOT EXISTS holdings (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    symbol VARCHAR(32) NOT NULL,
+    name VARCHAR(255) NULL,
+    cost_method VARCHAR(8) NOT NULL DEFAULT 'fifo', -- fifo, average
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NULL,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_holding_symbol (budget_id, symbol)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Buys, sells and dividends of a holding; entry_id is the cash side, an entry in the same budget
+CREATE TABLE IF NOT EXISTS investment_transactions (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    holding_id CHAR(36) NOT NULL,
+    kind VARCHAR(10) NOT NULL, -- buy, sell, dividend
+    trade_date DATE NOT NULL,
+    quantity DOUBLE NOT NULL DEFAULT 0, -- 0 for dividends
+    price_minor BIGINT NOT NULL DEFAULT 0, -- per unit, in the budget currency
+    fee_minor BIGINT NOT NULL DEFAULT 0,
+    amount_minor BIGINT NOT NULL, -- cash paid for buys, received for sells and dividends, fees included
+    entry_id CHAR(36) NULL,
+    note TEXT NULL,
+    created_by CHAR(36) NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    FOREIGN KEY (holding_id) REFERENCES holdings(id) ON DELETE CASCADE,
+    FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_investment_transactions_holding (holding_id, trade_date),
+    INDEX idx_investment_transactions_entry (entry_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Market prices of symbols held in invest budgets
+CREATE TABLE IF NOT EXISTS price_snapshots (
+    id CHAR(36) PRIMARY KEY,
+    budget_id CHAR(36) NOT NULL,
+    symbol VARCHAR(32) NOT NULL,
+    price_date DATE NOT NULL,
+    price_minor BIGINT NOT NULL, -- per unit, in the budget currency
+    source VARCHAR(20) NOT NULL DEFAULT 'manual', -- manual, csv
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_price_snapshot (budget_id, symbol, price_date)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
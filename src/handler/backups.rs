@@ -0,0 +1,28 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, http::header, response::{IntoResponse, Response}, Json, Extension};
+use crate::manager::models::backup::{BackupQuery, RestoreReport, RestoreReq};
+use crate::manager::biz::backups::BackupService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/backup?format=json|zip
+/// Download a versioned archive of the budget; `zip` also bundles attachment files
+pub async fn backup(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(query): Query<BackupQuery>) -> Result<Response, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Owner).await?;
+    let (content_type, extension, body) = match query.format.as_deref().unwrap_or("json") {
+        "json" => {
+            let archive = BackupService::archive(&state.pool, &budget_id).await?;
+            ("application/json", "json", serde_json::to_vec_pretty(&archive).map_err(|_| AppError::Internal)?)
+        }
+        "zip" => ("application/zip", "zip", BackupService::archive_zip(&state.pool, &budget_id).await?),
+        other => return Err(AppError::BadRequest(format!("Invalid backup format: {}", other))),
+    };
+    let disposition = format!("attachment; filename=\"budget-{}-backup.{}\"", budget_id, extension);
+    Ok(([(header::CONTENT_TYPE, content_type.to_string()), (header::CONTENT_DISPOSITION, disposition)], body).into_response())
+}
+
+/// POST /api/budgets/restore
+/// Recreate a budget from a backup archive under new ids, owned by the caller
+pub async fn restore(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Json(req): Json<RestoreReq>) -> Result<Json<RestoreReport>, AppError> {
+    Ok(Json(BackupService::restore(&state.pool, &claims.sub, req).await?))
+}
@@ -1,18 +1,34 @@
 use std::sync::Arc;
 use axum::{extract::{Query, Path, State}, Json, Extension};
 use serde::Deserialize;
-use crate::manager::models::{category::{Category, CreateCategoryReq, UpdateCategoryReq}};
+use crate::manager::models::{category::{Category, CategoryNode, CategorySummaryRow, CreateCategoryReq, MergeCategoryReq, MergeCategoryReport, UpdateCategoryReq}};
+use crate::handler::summaries::SummaryFilter;
 use crate::manager::biz::categories::CategoryService;
 use crate::utils::error::error::AppError;
 use super::AppState;
 
 #[derive(Deserialize)]
-pub struct CategoryFilter { kind: Option<String> }
+pub struct CategoryFilter { kind: Option<String>, include_hidden: Option<bool> }
+
+#[derive(Deserialize)]
+pub struct DeleteCategoryQuery { reparent_children: Option<bool> }
 
 pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(filter): Query<CategoryFilter>) -> Result<Json<Vec<Category>>, AppError> {
     // Ensure user has at least viewer access to this budget
     crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
-    Ok(Json(CategoryService::list(&state.pool, &budget_id, filter.kind).await?))
+    Ok(Json(CategoryService::list(&state.pool, &budget_id, filter.kind, filter.include_hidden.unwrap_or(false)).await?))
+}
+
+pub async fn tree(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(filter): Query<CategoryFilter>) -> Result<Json<Vec<CategoryNode>>, AppError> {
+    // Ensure user has at least viewer access to this budget
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(CategoryService::tree(&state.pool, &budget_id, filter.kind, filter.include_hidden.unwrap_or(false)).await?))
+}
+
+pub async fn summary(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(filter): Query<SummaryFilter>) -> Result<Json<Vec<CategorySummaryRow>>, AppError> {
+    // Ensure user has at least viewer access to this budget
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(CategoryService::summary(&state.pool, &budget_id, filter.from, filter.to).await?))
 }
 
 pub async fn create(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<CreateCategoryReq>) -> Result<Json<Category>, AppError> {
@@ -33,9 +49,27 @@ pub async fn update(State(state): State<Arc<AppState>>, Extension(claims): Exten
     Ok(Json(CategoryService::update(&state.pool, &budget_id, &category_id, req).await?))
 }
 
-pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, category_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, category_id)): Path<(String, String)>, Query(query): Query<DeleteCategoryQuery>) -> Result<Json<serde_json::Value>, AppError> {
     // Ensure user has at least manager access to delete categories
     crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
-    CategoryService::delete(&state.pool, &budget_id, &category_id).await?;
+    CategoryService::delete(&state.pool, &budget_id, &category_id, query.reparent_children.unwrap_or(false)).await?;
     Ok(Json(serde_json::json!({"message": "Category deleted successfully"})))
-}
\ No newline at end of file
+}
+
+pub async fn merge(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, category_id)): Path<(String, String)>, Json(req): Json<MergeCategoryReq>) -> Result<Json<MergeCategoryReport>, AppError> {
+    // Ensure user has at least manager access to merge categories
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(CategoryService::merge(&state.pool, &budget_id, &category_id, req).await?))
+}
+
+pub async fn archive(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, category_id)): Path<(String, String)>) -> Result<Json<Category>, AppError> {
+    // Ensure user has at least manager access to archive categories
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(CategoryService::set_hidden(&state.pool, &budget_id, &category_id, true).await?))
+}
+
+pub async fn unarchive(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, category_id)): Path<(String, String)>) -> Result<Json<Category>, AppError> {
+    // Ensure user has at least manager access to unarchive categories
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(CategoryService::set_hidden(&state.pool, &budget_id, &category_id, false).await?))
+}
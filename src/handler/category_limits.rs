@@ -0,0 +1,29 @@
+use std::sync::Arc;
+use axum::{extract::{Path, State}, Json, Extension};
+use crate::manager::models::category_limit::{CategoryLimit, CreateCategoryLimitReq, UpdateCategoryLimitReq};
+use crate::manager::biz::category_limits::CategoryLimitService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, category_id)): Path<(String, String)>) -> Result<Json<Vec<CategoryLimit>>, AppError> {
+    // Ensure user has at least viewer access to this budget
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(CategoryLimitService::list(&state.pool, &budget_id, &category_id).await?))
+}
+
+pub async fn create(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, category_id)): Path<(String, String)>, Json(req): Json<CreateCategoryLimitReq>) -> Result<Json<CategoryLimit>, AppError> {
+    // Planning amounts is a category management task
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(CategoryLimitService::create(&state.pool, &budget_id, &category_id, req).await?))
+}
+
+pub async fn update(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, category_id, limit_id)): Path<(String, String, String)>, Json(req): Json<UpdateCategoryLimitReq>) -> Result<Json<CategoryLimit>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(CategoryLimitService::update(&state.pool, &budget_id, &category_id, &limit_id, req).await?))
+}
+
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, category_id, limit_id)): Path<(String, String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    CategoryLimitService::delete(&state.pool, &budget_id, &category_id, &limit_id).await?;
+    Ok(Json(serde_json::json!({"message": "Category limit deleted successfully"})))
+}
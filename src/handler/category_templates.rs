@@ -0,0 +1,23 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use crate::manager::models::category_template::{CategoryTemplate, SaveTemplateReq, TemplateFilter};
+use crate::manager::biz::category_templates::CategoryTemplateService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/category-templates?budget_type=&locale=
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Query(filter): Query<TemplateFilter>) -> Result<Json<Vec<CategoryTemplate>>, AppError> {
+    Ok(Json(CategoryTemplateService::list(&state.pool, &claims.sub, filter).await?))
+}
+
+/// POST /api/budgets/:budget_id/category-templates
+pub async fn save_from_budget(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<SaveTemplateReq>) -> Result<Json<CategoryTemplate>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(CategoryTemplateService::save_from_budget(&state.pool, &budget_id, &claims.sub, req).await?))
+}
+
+/// DELETE /api/category-templates/:template_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(template_id): Path<String>) -> Result<Json<serde_json::Value>, AppError> {
+    CategoryTemplateService::delete(&state.pool, &claims.sub, &template_id).await?;
+    Ok(Json(serde_json::json!({"message": "Category template deleted successfully"})))
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use crate::manager::models::debt::{CreateDebtReq, Debt, PayoffPlan, PayoffPlanQuery, UpdateDebtReq};
+use crate::manager::biz::debts::DebtService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/debts
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>) -> Result<Json<Vec<Debt>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(DebtService::list(&state.pool, &budget_id).await?))
+}
+
+/// POST /api/budgets/:budget_id/debts
+pub async fn create(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<CreateDebtReq>) -> Result<Json<Debt>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(DebtService::create(&state.pool, &budget_id, &claims.sub, req).await?))
+}
+
+/// GET /api/budgets/:budget_id/debts/plan?extra_monthly_minor=&strategy=
+pub async fn plan(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(query): Query<PayoffPlanQuery>) -> Result<Json<PayoffPlan>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(DebtService::plan(&state.pool, &budget_id, query).await?))
+}
+
+/// GET /api/budgets/:budget_id/debts/:debt_id
+pub async fn get(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, debt_id)): Path<(String, String)>) -> Result<Json<Debt>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(DebtService::get(&state.pool, &budget_id, &debt_id).await?))
+}
+
+/// PATCH /api/budgets/:budget_id/debts/:debt_id
+pub async fn update(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, debt_id)): Path<(String, String)>, Json(req): Json<UpdateDebtReq>) -> Result<Json<Debt>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(DebtService::update(&state.pool, &budget_id, &debt_id, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/debts/:debt_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, debt_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    DebtService::delete(&state.pool, &budget_id, &debt_id).await?;
+    Ok(Json(serde_json::json!({"message": "Debt deleted successfully"})))
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 use axum::{extract::{Query, Path, State}, Json, Extension};
 use serde::{Deserialize, Serialize};
-use crate::manager::models::{entry::{Entry, CreateEntryReq}};
+use crate::manager::models::{entry::{Entry, CreateEntryReq, EntrySplitReq, RecategorizeReq, RecategorizeReport}, tag::TagFilter};
 use crate::manager::biz::entries::EntryService;
 use crate::utils::error::error::AppError;
 use super::AppState;
@@ -14,6 +14,8 @@ pub struct EntryFilter {
     category_id: Option<String>,
     member_id: Option<String>,
     search: Option<String>,
+    tags: Option<String>, // comma-separated tag ids
+    tag_match: Option<String>, // "any" (default), "all"
     sort_by: Option<String>, // "date" (default), "amount"
     sort_order: Option<String>, // "desc" (default), "asc"
     page: Option<u32>,
@@ -25,9 +27,17 @@ pub struct UpdateEntryReq {
     pub category_id: Option<String>,
     pub kind: Option<String>,
     pub amount_minor: Option<i64>,
+    pub currency_code: Option<String>,
+    pub exchange_rate: Option<f64>, // overrides the budget's rate table for a foreign currency
     pub entry_date: Option<chrono::NaiveDate>,
     pub description: Option<String>,
     pub counterparty: Option<String>,
+    pub splits: Option<Vec<EntrySplitReq>>, // replaces the line items; an empty list removes the split
+    pub tag_ids: Option<Vec<String>>, // replaces the tags; an empty list removes them
+    #[serde(skip)]
+    pub payee_id: Option<String>, // resolved from counterparty
+    #[serde(skip)]
+    pub base_amount_minor: Option<i64>, // set with exchange_rate when the conversion is redone
 }
 
 #[derive(Serialize)]
@@ -38,6 +48,7 @@ pub struct DeleteEntryResp {
 pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(filter): Query<EntryFilter>) -> Result<Json<Vec<Entry>>, AppError> {
     // Ensure user has at least viewer access to this budget
     crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    let tags = TagFilter::parse(filter.tags.as_deref(), filter.tag_match.as_deref())?;
     Ok(Json(EntryService::list(
         &state.pool,
         &budget_id,
@@ -47,6 +58,7 @@ pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extensi
         filter.category_id,
         filter.member_id,
         filter.search,
+        tags,
         filter.sort_by,
         filter.sort_order,
         filter.page,
@@ -70,4 +82,10 @@ pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Exten
     Ok(Json(DeleteEntryResp {
         message: "Entry deleted successfully".to_string(),
     }))
-}
\ No newline at end of file
+}
+
+/// POST /api/budgets/:budget_id/entries/recategorize
+pub async fn recategorize(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<RecategorizeReq>) -> Result<Json<RecategorizeReport>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(EntryService::recategorize(&state.pool, &budget_id, req, &claims.sub).await?))
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use crate::manager::models::exchange_rate::{ExchangeRate, ExchangeRateFilter, ImportExchangeRatesReport, ImportExchangeRatesReq, SaveExchangeRateReq};
+use crate::manager::biz::exchange_rates::ExchangeRateService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/exchange-rates?base=&quote=&from=&to=
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(filter): Query<ExchangeRateFilter>) -> Result<Json<Vec<ExchangeRate>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(ExchangeRateService::list(&state.pool, &budget_id, filter).await?))
+}
+
+/// POST /api/budgets/:budget_id/exchange-rates
+pub async fn save(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<SaveExchangeRateReq>) -> Result<Json<ExchangeRate>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(ExchangeRateService::save(&state.pool, &budget_id, req).await?))
+}
+
+/// POST /api/budgets/:budget_id/exchange-rates/import
+pub async fn import(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<ImportExchangeRatesReq>) -> Result<Json<ImportExchangeRatesReport>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(ExchangeRateService::import(&state.pool, &budget_id, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/exchange-rates/:rate_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, rate_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    ExchangeRateService::delete(&state.pool, &budget_id, &rate_id).await?;
+    Ok(Json(serde_json::json!({"message": "Exchange rate deleted successfully"})))
+}
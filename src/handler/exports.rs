@@ -0,0 +1,35 @@
+use std::sync::Arc;
+use axum::{body::Body, extract::{Path, Query, State}, http::header, response::{IntoResponse, Response}, Extension};
+use crate::manager::models::export::{ExportQuery, JournalQuery};
+use crate::manager::biz::exports::ExportService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/export?format=csv|json|xlsx
+/// Download every non-deleted entry matching the entry list filters
+pub async fn export(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(query): Query<ExportQuery>) -> Result<Response, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    let (format, stream) = ExportService::export(&state.pool, &budget_id, query).await?;
+    let disposition = format!("attachment; filename=\"entries-{}.{}\"", budget_id, format.extension());
+    Ok((
+        [(header::CONTENT_TYPE, format.content_type().to_string()), (header::CONTENT_DISPOSITION, disposition)],
+        Body::from_stream(stream),
+    ).into_response())
+}
+
+/// GET /api/budgets/:budget_id/export/journal?format=hledger|beancount
+/// Render one budget as a plain-text double-entry journal
+pub async fn budget_journal(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(query): Query<JournalQuery>) -> Result<Response, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    let (format, journal) = ExportService::journal(&state.pool, &claims.sub, Some(&budget_id), query).await?;
+    let disposition = format!("attachment; filename=\"budget-{}.{}\"", budget_id, format.extension());
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string()), (header::CONTENT_DISPOSITION, disposition)], journal).into_response())
+}
+
+/// GET /api/export/journal?format=hledger|beancount
+/// Render every budget the user is a member of as one journal
+pub async fn journal(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Query(query): Query<JournalQuery>) -> Result<Response, AppError> {
+    let (format, journal) = ExportService::journal(&state.pool, &claims.sub, None, query).await?;
+    let disposition = format!("attachment; filename=\"philand.{}\"", format.extension());
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string()), (header::CONTENT_DISPOSITION, disposition)], journal).into_response())
+}
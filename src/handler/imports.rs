@@ -0,0 +1,34 @@
+use std::sync::Arc;
+use axum::{extract::{Path, State}, Json, Extension};
+use crate::manager::models::import::{CsvImportReq, ImportReport, StatementImportReq};
+use crate::manager::biz::imports::ImportService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// POST /api/budgets/:budget_id/imports/csv
+/// Import entries from a base64 encoded CSV file; `dry_run` only validates
+pub async fn import_csv(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<CsvImportReq>) -> Result<Json<ImportReport>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    Ok(Json(ImportService::import_csv(&state.pool, &budget_id, &claims.sub, req).await?))
+}
+
+/// POST /api/budgets/:budget_id/imports/ofx
+/// Import an OFX or QFX statement; transactions already imported (same FITID) are skipped
+pub async fn import_ofx(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<StatementImportReq>) -> Result<Json<ImportReport>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    Ok(Json(ImportService::import_ofx(&state.pool, &budget_id, &claims.sub, req).await?))
+}
+
+/// POST /api/budgets/:budget_id/imports/qif
+/// Import a QIF bank or cash register export
+pub async fn import_qif(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<StatementImportReq>) -> Result<Json<ImportReport>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    Ok(Json(ImportService::import_qif(&state.pool, &budget_id, &claims.sub, req).await?))
+}
+
+/// POST /api/budgets/:budget_id/imports/camt053
+/// Import an ISO 20022 camt.053 statement; entries already imported are skipped
+pub async fn import_camt053(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<StatementImportReq>) -> Result<Json<ImportReport>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    Ok(Json(ImportService::import_camt053(&state.pool, &budget_id, &claims.sub, req).await?))
+}
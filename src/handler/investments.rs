@@ -0,0 +1,62 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use crate::manager::models::investment::{CreateHoldingReq, CreateInvestmentTransactionReq, Holding, InvestmentTransaction, PortfolioQuery, PortfolioSummary, UpdateHoldingReq};
+use crate::manager::biz::investments::InvestmentService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/holdings
+pub async fn list_holdings(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>) -> Result<Json<Vec<Holding>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(InvestmentService::list_holdings(&state.pool, &budget_id).await?))
+}
+
+/// POST /api/budgets/:budget_id/holdings
+pub async fn create_holding(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<CreateHoldingReq>) -> Result<Json<Holding>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(InvestmentService::create_holding(&state.pool, &budget_id, req).await?))
+}
+
+/// GET /api/budgets/:budget_id/holdings/:holding_id
+pub async fn get_holding(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, holding_id)): Path<(String, String)>) -> Result<Json<Holding>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(InvestmentService::get_holding(&state.pool, &budget_id, &holding_id).await?))
+}
+
+/// PATCH /api/budgets/:budget_id/holdings/:holding_id
+pub async fn update_holding(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, holding_id)): Path<(String, String)>, Json(req): Json<UpdateHoldingReq>) -> Result<Json<Holding>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(InvestmentService::update_holding(&state.pool, &budget_id, &holding_id, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/holdings/:holding_id
+pub async fn delete_holding(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, holding_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    InvestmentService::delete_holding(&state.pool, &budget_id, &holding_id, &claims.sub).await?;
+    Ok(Json(serde_json::json!({"message": "Holding deleted successfully"})))
+}
+
+/// GET /api/budgets/:budget_id/holdings/:holding_id/transactions
+pub async fn list_transactions(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, holding_id)): Path<(String, String)>) -> Result<Json<Vec<InvestmentTransaction>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(InvestmentService::list_transactions(&state.pool, &budget_id, &holding_id).await?))
+}
+
+/// POST /api/budgets/:budget_id/holdings/:holding_id/transactions
+pub async fn create_transaction(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, holding_id)): Path<(String, String)>, Json(req): Json<CreateInvestmentTransactionReq>) -> Result<Json<InvestmentTransaction>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    Ok(Json(InvestmentService::create_transaction(&state.pool, &budget_id, &holding_id, &claims.sub, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/holdings/:holding_id/transactions/:transaction_id
+pub async fn delete_transaction(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, holding_id, transaction_id)): Path<(String, String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    InvestmentService::delete_transaction(&state.pool, &budget_id, &holding_id, &transaction_id, &claims.sub).await?;
+    Ok(Json(serde_json::json!({"message": "Transaction deleted successfully"})))
+}
+
+/// GET /api/budgets/:budget_id/portfolio?as_of=
+pub async fn portfolio(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(query): Query<PortfolioQuery>) -> Result<Json<PortfolioSummary>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(InvestmentService::portfolio(&state.pool, &budget_id, query).await?))
+}
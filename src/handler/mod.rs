@@ -6,6 +6,7 @@ pub mod health;
 pub mod users;
 pub mod budgets;
 pub mod categories;
+pub mod debts;
 pub mod entries;
 pub mod summaries;
 pub mod auth;
@@ -16,6 +17,20 @@ pub mod cleanup;
 pub mod comments;
 pub mod notifications;
 pub mod transfers;
+pub mod category_limits;
+pub mod recurring;
+pub mod imports;
+pub mod exports;
+pub mod backups;
+pub mod tags;
+pub mod category_templates;
+pub mod rules;
+pub mod savings_goals;
+pub mod payees;
+pub mod prices;
+pub mod exchange_rates;
+pub mod investments;
+pub mod scheduled_transfers;
 
 #[derive(Clone)]
 pub struct AppState { 
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use crate::manager::models::payee::{AddAliasReq, CreatePayeeReq, MergePayeeReport, MergePayeeReq, Payee, PayeeSearch, PayeeSuggestion, PayeeSummaryRow, UpdatePayeeReq};
+use crate::manager::biz::payees::PayeeService;
+use crate::handler::summaries::SummaryFilter;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/payees
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>) -> Result<Json<Vec<Payee>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(PayeeService::list(&state.pool, &budget_id).await?))
+}
+
+/// POST /api/budgets/:budget_id/payees
+pub async fn create(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<CreatePayeeReq>) -> Result<Json<Payee>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    Ok(Json(PayeeService::create(&state.pool, &budget_id, req).await?))
+}
+
+/// GET /api/budgets/:budget_id/payees/autocomplete?q=&limit=
+pub async fn autocomplete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(search): Query<PayeeSearch>) -> Result<Json<Vec<PayeeSuggestion>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(PayeeService::autocomplete(&state.pool, &budget_id, search).await?))
+}
+
+/// GET /api/budgets/:budget_id/payees/:payee_id
+pub async fn get(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, payee_id)): Path<(String, String)>) -> Result<Json<Payee>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(PayeeService::get(&state.pool, &budget_id, &payee_id).await?))
+}
+
+/// PATCH /api/budgets/:budget_id/payees/:payee_id
+pub async fn update(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, payee_id)): Path<(String, String)>, Json(req): Json<UpdatePayeeReq>) -> Result<Json<Payee>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(PayeeService::update(&state.pool, &budget_id, &payee_id, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/payees/:payee_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, payee_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    PayeeService::delete(&state.pool, &budget_id, &payee_id).await?;
+    Ok(Json(serde_json::json!({"message": "Payee deleted successfully"})))
+}
+
+/// POST /api/budgets/:budget_id/payees/:payee_id/aliases
+pub async fn add_alias(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, payee_id)): Path<(String, String)>, Json(req): Json<AddAliasReq>) -> Result<Json<Payee>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(PayeeService::add_alias(&state.pool, &budget_id, &payee_id, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/payees/:payee_id/aliases/:alias_id
+pub async fn delete_alias(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, payee_id, alias_id)): Path<(String, String, String)>) -> Result<Json<Payee>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(PayeeService::delete_alias(&state.pool, &budget_id, &payee_id, &alias_id).await?))
+}
+
+/// POST /api/budgets/:budget_id/payees/:payee_id/merge
+pub async fn merge(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, payee_id)): Path<(String, String)>, Json(req): Json<MergePayeeReq>) -> Result<Json<MergePayeeReport>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(PayeeService::merge(&state.pool, &budget_id, &payee_id, req).await?))
+}
+
+/// GET /api/budgets/:budget_id/summary/payees?from=&to=
+pub async fn summary(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(filter): Query<SummaryFilter>) -> Result<Json<Vec<PayeeSummaryRow>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(PayeeService::summary(&state.pool, &budget_id, filter.from, filter.to).await?))
+}
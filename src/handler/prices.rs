@@ -0,0 +1,31 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use crate::manager::models::investment::{ImportPricesReport, ImportPricesReq, PriceFilter, PriceSnapshot, SavePriceReq};
+use crate::manager::biz::prices::PriceService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/prices?symbol=&from=&to=
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(filter): Query<PriceFilter>) -> Result<Json<Vec<PriceSnapshot>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(PriceService::list(&state.pool, &budget_id, filter).await?))
+}
+
+/// POST /api/budgets/:budget_id/prices
+pub async fn save(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<SavePriceReq>) -> Result<Json<PriceSnapshot>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(PriceService::save(&state.pool, &budget_id, req).await?))
+}
+
+/// POST /api/budgets/:budget_id/prices/import
+pub async fn import(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<ImportPricesReq>) -> Result<Json<ImportPricesReport>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(PriceService::import(&state.pool, &budget_id, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/prices/:price_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, price_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    PriceService::delete(&state.pool, &budget_id, &price_id).await?;
+    Ok(Json(serde_json::json!({"message": "Price deleted successfully"})))
+}
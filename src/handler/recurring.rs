@@ -0,0 +1,42 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use serde::Deserialize;
+use crate::manager::models::recurring::{CreateRecurringEntryReq, RecurringEntry, RecurringPreview, UpdateRecurringEntryReq};
+use crate::manager::biz::recurring::RecurringService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+#[derive(Deserialize)]
+pub struct PreviewFilter { count: Option<u32> }
+
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>) -> Result<Json<Vec<RecurringEntry>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(RecurringService::list(&state.pool, &budget_id).await?))
+}
+
+pub async fn create(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(mut req): Json<CreateRecurringEntryReq>) -> Result<Json<RecurringEntry>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    req.created_by = claims.sub.clone();
+    Ok(Json(RecurringService::create(&state.pool, &budget_id, req).await?))
+}
+
+pub async fn get(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, recurring_id)): Path<(String, String)>) -> Result<Json<RecurringEntry>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(RecurringService::get(&state.pool, &budget_id, &recurring_id).await?))
+}
+
+pub async fn update(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, recurring_id)): Path<(String, String)>, Json(req): Json<UpdateRecurringEntryReq>) -> Result<Json<RecurringEntry>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    Ok(Json(RecurringService::update(&state.pool, &budget_id, &recurring_id, req).await?))
+}
+
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, recurring_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Contributor).await?;
+    RecurringService::delete(&state.pool, &budget_id, &recurring_id).await?;
+    Ok(Json(serde_json::json!({"message": "Recurring entry deleted successfully"})))
+}
+
+pub async fn preview(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, recurring_id)): Path<(String, String)>, Query(filter): Query<PreviewFilter>) -> Result<Json<RecurringPreview>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(RecurringService::preview(&state.pool, &budget_id, &recurring_id, filter.count).await?))
+}
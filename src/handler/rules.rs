@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use axum::{extract::{Path, State}, Json, Extension};
+use crate::manager::models::rule::{ApplyRulesReport, ApplyRulesReq, Rule, SaveRuleReq};
+use crate::manager::biz::rules::RuleService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/rules
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>) -> Result<Json<Vec<Rule>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(RuleService::list(&state.pool, &budget_id).await?))
+}
+
+/// POST /api/budgets/:budget_id/rules
+pub async fn create(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<SaveRuleReq>) -> Result<Json<Rule>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(RuleService::create(&state.pool, &budget_id, req).await?))
+}
+
+/// GET /api/budgets/:budget_id/rules/:rule_id
+pub async fn get(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, rule_id)): Path<(String, String)>) -> Result<Json<Rule>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(RuleService::get(&state.pool, &budget_id, &rule_id).await?))
+}
+
+/// PUT /api/budgets/:budget_id/rules/:rule_id
+pub async fn replace(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, rule_id)): Path<(String, String)>, Json(req): Json<SaveRuleReq>) -> Result<Json<Rule>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(RuleService::replace(&state.pool, &budget_id, &rule_id, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/rules/:rule_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, rule_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    RuleService::delete(&state.pool, &budget_id, &rule_id).await?;
+    Ok(Json(serde_json::json!({"message": "Rule deleted successfully"})))
+}
+
+/// POST /api/budgets/:budget_id/rules/apply
+pub async fn apply(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<ApplyRulesReq>) -> Result<Json<ApplyRulesReport>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(RuleService::apply(&state.pool, &budget_id, req, &claims.sub).await?))
+}
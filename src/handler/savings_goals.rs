@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use crate::manager::models::savings_goal::{CreateSavingsGoalReq, GoalProgressQuery, SavingsGoal, SavingsGoalProgress, UpdateSavingsGoalReq};
+use crate::manager::biz::savings_goals::SavingsGoalService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/goals
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>) -> Result<Json<Vec<SavingsGoal>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(SavingsGoalService::list(&state.pool, &budget_id).await?))
+}
+
+/// POST /api/budgets/:budget_id/goals
+pub async fn create(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<CreateSavingsGoalReq>) -> Result<Json<SavingsGoal>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(SavingsGoalService::create(&state.pool, &budget_id, &claims.sub, req).await?))
+}
+
+/// GET /api/budgets/:budget_id/goals/:goal_id
+pub async fn get(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, goal_id)): Path<(String, String)>) -> Result<Json<SavingsGoal>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(SavingsGoalService::get(&state.pool, &budget_id, &goal_id).await?))
+}
+
+/// PATCH /api/budgets/:budget_id/goals/:goal_id
+pub async fn update(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, goal_id)): Path<(String, String)>, Json(req): Json<UpdateSavingsGoalReq>) -> Result<Json<SavingsGoal>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(SavingsGoalService::update(&state.pool, &budget_id, &goal_id, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/goals/:goal_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, goal_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    SavingsGoalService::delete(&state.pool, &budget_id, &goal_id).await?;
+    Ok(Json(serde_json::json!({"message": "Savings goal deleted successfully"})))
+}
+
+/// GET /api/budgets/:budget_id/goals/:goal_id/progress?months=
+pub async fn progress(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, goal_id)): Path<(String, String)>, Query(query): Query<GoalProgressQuery>) -> Result<Json<SavingsGoalProgress>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(SavingsGoalService::progress(&state.pool, &budget_id, &goal_id, query.months).await?))
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use serde::Deserialize;
+use crate::manager::models::scheduled_transfer::{CreateScheduledTransferReq, ScheduledTransfer, ScheduledTransferPreview, UpdateScheduledTransferReq};
+use crate::manager::biz::scheduled_transfers::ScheduledTransferService;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+#[derive(Deserialize)]
+pub struct PreviewFilter { count: Option<u32> }
+
+/// GET /api/budgets/:budget_id/scheduled-transfers
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>) -> Result<Json<Vec<ScheduledTransfer>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(ScheduledTransferService::list(&state.pool, &budget_id).await?))
+}
+
+/// POST /api/scheduled-transfers
+pub async fn create(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Json(req): Json<CreateScheduledTransferReq>) -> Result<Json<ScheduledTransfer>, AppError> {
+    Ok(Json(ScheduledTransferService::create(&state.pool, &claims.sub, req).await?))
+}
+
+/// GET /api/scheduled-transfers/:scheduled_id
+pub async fn get(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(scheduled_id): Path<String>) -> Result<Json<ScheduledTransfer>, AppError> {
+    Ok(Json(ScheduledTransferService::get(&state.pool, &claims.sub, &scheduled_id).await?))
+}
+
+/// PATCH /api/scheduled-transfers/:scheduled_id
+pub async fn update(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(scheduled_id): Path<String>, Json(req): Json<UpdateScheduledTransferReq>) -> Result<Json<ScheduledTransfer>, AppError> {
+    Ok(Json(ScheduledTransferService::update(&state.pool, &claims.sub, &scheduled_id, req).await?))
+}
+
+/// DELETE /api/scheduled-transfers/:scheduled_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(scheduled_id): Path<String>) -> Result<Json<serde_json::Value>, AppError> {
+    ScheduledTransferService::delete(&state.pool, &claims.sub, &scheduled_id).await?;
+    Ok(Json(serde_json::json!({"message": "Scheduled transfer deleted successfully"})))
+}
+
+/// GET /api/scheduled-transfers/:scheduled_id/preview?count=
+pub async fn preview(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(scheduled_id): Path<String>, Query(filter): Query<PreviewFilter>) -> Result<Json<ScheduledTransferPreview>, AppError> {
+    Ok(Json(ScheduledTransferService::preview(&state.pool, &claims.sub, &scheduled_id, filter.count).await?))
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+use axum::{extract::{Path, Query, State}, Json, Extension};
+use crate::manager::models::tag::{CreateTagReq, Tag, TagSummaryRow, UpdateTagReq};
+use crate::manager::biz::tags::TagService;
+use crate::handler::summaries::SummaryFilter;
+use crate::utils::error::error::AppError;
+use super::AppState;
+
+/// GET /api/budgets/:budget_id/tags
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>) -> Result<Json<Vec<Tag>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(TagService::list(&state.pool, &budget_id).await?))
+}
+
+/// POST /api/budgets/:budget_id/tags
+pub async fn create(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Json(req): Json<CreateTagReq>) -> Result<Json<Tag>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(TagService::create(&state.pool, &budget_id, req).await?))
+}
+
+/// PATCH /api/budgets/:budget_id/tags/:tag_id
+pub async fn update(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, tag_id)): Path<(String, String)>, Json(req): Json<UpdateTagReq>) -> Result<Json<Tag>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    Ok(Json(TagService::update(&state.pool, &budget_id, &tag_id, req).await?))
+}
+
+/// DELETE /api/budgets/:budget_id/tags/:tag_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, tag_id)): Path<(String, String)>) -> Result<Json<serde_json::Value>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Manager).await?;
+    TagService::delete(&state.pool, &budget_id, &tag_id).await?;
+    Ok(Json(serde_json::json!({"message": "Tag deleted successfully"})))
+}
+
+/// GET /api/budgets/:budget_id/summary/tags?from=&to=
+pub async fn summary(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>, Query(filter): Query<SummaryFilter>) -> Result<Json<Vec<TagSummaryRow>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(TagService::summary(&state.pool, &budget_id, filter.from, filter.to).await?))
+}
@@ -1,8 +1,8 @@
 use std::sync::Arc;
-use axum::{extract::State, Json, Extension};
+use axum::{extract::{Path, State}, Json, Extension};
 use serde::Deserialize;
 use crate::manager::biz::transfers::TransferService;
-use crate::manager::models::transfer::{CreateTransferReq, TransferWithEntries};
+use crate::manager::models::transfer::{BudgetTransfer, CreateTransferReq, TransferWithEntries, UpdateTransferReq};
 use crate::utils::error::error::AppError;
 use super::AppState;
 
@@ -16,6 +16,9 @@ pub struct CreateTransferHttpReq {
     pub note: Option<String>,
     pub from_category_id: String,
     pub to_category_id: String,
+    pub exchange_rate: Option<f64>,
+    pub fee_minor: Option<i64>,
+    pub fee_category_id: Option<String>,
 }
 
 pub async fn create_transfer(
@@ -32,6 +35,10 @@ pub async fn create_transfer(
         note: req.note,
         from_category_id: req.from_category_id,
         to_category_id: req.to_category_id,
+        exchange_rate: req.exchange_rate,
+        fee_minor: req.fee_minor,
+        fee_category_id: req.fee_category_id,
+        scheduled_transfer_id: None,
     };
 
     let result = TransferService::create_transfer(
@@ -42,3 +49,26 @@ pub async fn create_transfer(
 
     Ok(Json(result))
 }
+
+/// GET /api/budgets/:budget_id/transfers
+pub async fn list(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(budget_id): Path<String>) -> Result<Json<Vec<BudgetTransfer>>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(TransferService::list(&state.pool, &budget_id).await?))
+}
+
+/// GET /api/budgets/:budget_id/transfers/:transfer_id
+pub async fn get(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path((budget_id, transfer_id)): Path<(String, String)>) -> Result<Json<TransferWithEntries>, AppError> {
+    crate::manager::biz::authz::ensure_role(&state.pool, &budget_id, &claims.sub, crate::manager::models::role::Role::Viewer).await?;
+    Ok(Json(TransferService::get(&state.pool, &budget_id, &transfer_id).await?))
+}
+
+/// PATCH /api/transfers/:transfer_id
+pub async fn update(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(transfer_id): Path<String>, Json(req): Json<UpdateTransferReq>) -> Result<Json<TransferWithEntries>, AppError> {
+    Ok(Json(TransferService::update(&state.pool, &claims.sub, &transfer_id, req).await?))
+}
+
+/// DELETE /api/transfers/:transfer_id
+pub async fn delete(State(state): State<Arc<AppState>>, Extension(claims): Extension<crate::handler::auth::Claims>, Path(transfer_id): Path<String>) -> Result<Json<serde_json::Value>, AppError> {
+    TransferService::delete(&state.pool, &claims.sub, &transfer_id).await?;
+    Ok(Json(serde_json::json!({"message": "Transfer deleted successfully"})))
+}
@@ -8,7 +8,7 @@ use axum::{
     http::Request,
     middleware::Next,
     response::IntoResponse,
-    extract::{State, ConnectInfo},
+    extract::{State, ConnectInfo, DefaultBodyLimit},
 };
 use tower_http::cors::{CorsLayer, AllowOrigin, AllowHeaders};
 use dotenvy::dotenv;
@@ -85,6 +85,42 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
+    // Start recurring entries job (runs every hour)
+    let recurring_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60 * 60)); // 1 hour
+        loop {
+            interval.tick().await;
+            let today = chrono::Utc::now().date_naive();
+            match manager::biz::recurring::RecurringService::materialize_due(&recurring_pool, today).await {
+                Ok(stats) => {
+                    info!("{}", stats);
+                }
+                Err(e) => {
+                    tracing::error!("Recurring entries job failed: {}", e);
+                }
+            }
+        }
+    });
+
+    // Start scheduled transfers job (runs every hour)
+    let transfers_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60 * 60)); // 1 hour
+        loop {
+            interval.tick().await;
+            let today = chrono::Utc::now().date_naive();
+            match manager::biz::scheduled_transfers::ScheduledTransferService::execute_due(&transfers_pool, today).await {
+                Ok(stats) => {
+                    info!("{}", stats);
+                }
+                Err(e) => {
+                    tracing::error!("Scheduled transfers job failed: {}", e);
+                }
+            }
+        }
+    });
+
     let cors_layer = {
         let origins_vec = get_config().get_cors_origins();
         if !origins_vec.is_empty() {
@@ -116,14 +152,71 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/budgets/{id}", get(handler::budgets::get).patch(handler::budgets::update).delete(handler::budgets::delete))
         .route("/api/budgets/{id}/balance", get(handler::budgets::get_balance))
         .route("/api/budgets/{id}/categories", get(handler::categories::list).post(handler::categories::create))
+        .route("/api/budgets/{id}/categories/tree", get(handler::categories::tree))
         .route("/api/budgets/{id}/categories/{category_id}", get(handler::categories::get_by_id).patch(handler::categories::update).delete(handler::categories::delete))
+        .route("/api/budgets/{id}/categories/{category_id}/merge", post(handler::categories::merge))
+        .route("/api/budgets/{id}/categories/{category_id}/archive", post(handler::categories::archive))
+        .route("/api/budgets/{id}/categories/{category_id}/unarchive", post(handler::categories::unarchive))
+        .route("/api/budgets/{id}/categories/{category_id}/limits", get(handler::category_limits::list).post(handler::category_limits::create))
+        .route("/api/budgets/{id}/categories/{category_id}/limits/{limit_id}", patch(handler::category_limits::update).delete(handler::category_limits::delete))
+        .route("/api/budgets/{id}/category-templates", post(handler::category_templates::save_from_budget))
+        .route("/api/category-templates", get(handler::category_templates::list))
+        .route("/api/category-templates/{template_id}", delete(handler::category_templates::delete))
+        .route("/api/budgets/{id}/tags", get(handler::tags::list).post(handler::tags::create))
+        .route("/api/budgets/{id}/tags/{tag_id}", patch(handler::tags::update).delete(handler::tags::delete))
+        .route("/api/budgets/{id}/rules", get(handler::rules::list).post(handler::rules::create))
+        .route("/api/budgets/{id}/rules/apply", post(handler::rules::apply))
+        .route("/api/budgets/{id}/rules/{rule_id}", get(handler::rules::get).put(handler::rules::replace).delete(handler::rules::delete))
+        .route("/api/budgets/{id}/payees", get(handler::payees::list).post(handler::payees::create))
+        .route("/api/budgets/{id}/payees/autocomplete", get(handler::payees::autocomplete))
+        .route("/api/budgets/{id}/payees/{payee_id}", get(handler::payees::get).patch(handler::payees::update).delete(handler::payees::delete))
+        .route("/api/budgets/{id}/payees/{payee_id}/aliases", post(handler::payees::add_alias))
+        .route("/api/budgets/{id}/payees/{payee_id}/aliases/{alias_id}", delete(handler::payees::delete_alias))
+        .route("/api/budgets/{id}/payees/{payee_id}/merge", post(handler::payees::merge))
+        .route("/api/budgets/{id}/exchange-rates", get(handler::exchange_rates::list).post(handler::exchange_rates::save))
+        .route("/api/budgets/{id}/exchange-rates/import", post(handler::exchange_rates::import).layer(DefaultBodyLimit::max(20 * 1024 * 1024)))
+        .route("/api/budgets/{id}/exchange-rates/{rate_id}", delete(handler::exchange_rates::delete))
+        .route("/api/budgets/{id}/transfers", get(handler::transfers::list))
+        .route("/api/budgets/{id}/transfers/{transfer_id}", get(handler::transfers::get))
+        .route("/api/budgets/{id}/goals", get(handler::savings_goals::list).post(handler::savings_goals::create))
+        .route("/api/budgets/{id}/goals/{goal_id}", get(handler::savings_goals::get).patch(handler::savings_goals::update).delete(handler::savings_goals::delete))
+        .route("/api/budgets/{id}/goals/{goal_id}/progress", get(handler::savings_goals::progress))
+        .route("/api/budgets/{id}/debts", get(handler::debts::list).post(handler::debts::create))
+        .route("/api/budgets/{id}/debts/plan", get(handler::debts::plan))
+        .route("/api/budgets/{id}/debts/{debt_id}", get(handler::debts::get).patch(handler::debts::update).delete(handler::debts::delete))
+        .route("/api/budgets/{id}/holdings", get(handler::investments::list_holdings).post(handler::investments::create_holding))
+        .route("/api/budgets/{id}/holdings/{holding_id}", get(handler::investments::get_holding).patch(handler::investments::update_holding).delete(handler::investments::delete_holding))
+        .route("/api/budgets/{id}/holdings/{holding_id}/transactions", get(handler::investments::list_transactions).post(handler::investments::create_transaction))
+        .route("/api/budgets/{id}/holdings/{holding_id}/transactions/{transaction_id}", delete(handler::investments::delete_transaction))
+        .route("/api/budgets/{id}/portfolio", get(handler::investments::portfolio))
+        .route("/api/budgets/{id}/prices", get(handler::prices::list).post(handler::prices::save))
+        .route("/api/budgets/{id}/prices/import", post(handler::prices::import).layer(DefaultBodyLimit::max(20 * 1024 * 1024)))
+        .route("/api/budgets/{id}/prices/{price_id}", delete(handler::prices::delete))
+        .route("/api/budgets/{id}/scheduled-transfers", get(handler::scheduled_transfers::list))
         .route("/api/budgets/{id}/entries", get(handler::entries::list).post(handler::entries::create))
+        .route("/api/budgets/{id}/entries/recategorize", post(handler::entries::recategorize))
         .route("/api/budgets/{id}/entries/{entry_id}", patch(handler::entries::update).delete(handler::entries::delete))
         .route("/api/budgets/{id}/entries/{entry_id}/comments", get(handler::comments::list_comments).post(handler::comments::create_comment))
         .route("/api/budgets/{id}/entries/{entry_id}/comments/{comment_id}", patch(handler::comments::update_comment).delete(handler::comments::delete_comment))
         .route("/api/budgets/{id}/entries/{entry_id}/attachments", post(handler::comments::upload_attachment))
         .route("/api/budgets/{id}/entries/{entry_id}/attachments/{attachment_id}", delete(handler::comments::delete_attachment))
+        .route("/api/budgets/{id}/imports/csv", post(handler::imports::import_csv))
+        .route("/api/budgets/{id}/imports/ofx", post(handler::imports::import_ofx))
+        .route("/api/budgets/{id}/imports/qif", post(handler::imports::import_qif))
+        .route("/api/budgets/{id}/imports/camt053", post(handler::imports::import_camt053))
+        .route("/api/budgets/{id}/recurring", get(handler::recurring::list).post(handler::recurring::create))
+        .route("/api/budgets/{id}/recurring/{recurring_id}", get(handler::recurring::get).patch(handler::recurring::update).delete(handler::recurring::delete))
+        .route("/api/budgets/{id}/recurring/{recurring_id}/preview", get(handler::recurring::preview))
         .route("/api/budgets/{id}/summary/monthly", get(handler::summaries::monthly))
+        .route("/api/budgets/{id}/summary/budget-vs-actual", get(handler::summaries::budget_vs_actual))
+        .route("/api/budgets/{id}/summary/categories", get(handler::categories::summary))
+        .route("/api/budgets/{id}/summary/tags", get(handler::tags::summary))
+        .route("/api/budgets/{id}/summary/payees", get(handler::payees::summary))
+        .route("/api/budgets/{id}/export", get(handler::exports::export))
+        .route("/api/budgets/{id}/export/journal", get(handler::exports::budget_journal))
+        .route("/api/export/journal", get(handler::exports::journal))
+        .route("/api/budgets/{id}/backup", get(handler::backups::backup))
+        .route("/api/budgets/restore", post(handler::backups::restore).layer(DefaultBodyLimit::max(200 * 1024 * 1024)))
         .route("/api/budgets/{id}/members", get(handler::members::list).post(handler::members::upsert))
         .route("/api/budgets/{id}/members/{user_id}", patch(handler::members::update).delete(handler::members::delete))
         .route("/api/notifications", get(handler::notifications::list_notifications))
@@ -131,6 +224,10 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/notifications/mark-read", post(handler::notifications::mark_as_read))
         .route("/api/notifications/mark-all-read", post(handler::notifications::mark_all_as_read))
         .route("/api/transfers", post(handler::transfers::create_transfer))
+        .route("/api/transfers/{transfer_id}", patch(handler::transfers::update).delete(handler::transfers::delete))
+        .route("/api/scheduled-transfers", post(handler::scheduled_transfers::create))
+        .route("/api/scheduled-transfers/{scheduled_id}", get(handler::scheduled_transfers::get).patch(handler::scheduled_transfers::update).delete(handler::scheduled_transfers::delete))
+        .route("/api/scheduled-transfers/{scheduled_id}/preview", get(handler::scheduled_transfers::preview))
         .route("/api/admin/cleanup", post(handler::cleanup::manual_cleanup))
         .route_layer(axum::middleware::from_fn(handler::auth::auth_middleware));
 
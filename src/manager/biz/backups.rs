@@ -0,0 +1,323 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read, Write};
+use base64::{engine::general_purpose, Engine as _};
+use crate::manager::models::backup::{BudgetArchive, RestoreConflicts, RestorePlan, RestoreReport, RestoreReq, ARCHIVE_VERSION};
+use crate::manager::models::{budget::BudgetType, category::RolloverPolicy, investment::{CostMethod, TradeKind}, recurrence::Frequency, role::Role};
+use crate::manager::repository::backups::BackupRepo;
+use crate::utils::{database::database::DbPool, error::error::AppError, s3_storage::get_s3_client};
+
+const ARCHIVE_JSON: &str = "budget.json";
+
+pub struct BackupService;
+
+impl BackupService {
+    pub async fn archive(pool: &DbPool, budget_id: &str) -> Result<BudgetArchive, AppError> {
+        Ok(BudgetArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: chrono::Utc::now().naive_utc(),
+            budget: BackupRepo::budget(pool, budget_id).await?,
+            members: BackupRepo::members(pool, budget_id).await?,
+            categories: BackupRepo::categories(pool, budget_id).await?,
+            category_limits: BackupRepo::category_limits(pool, budget_id).await?,
+            payees: BackupRepo::payees(pool, budget_id).await?,
+            payee_aliases: BackupRepo::payee_aliases(pool, budget_id).await?,
+            recurring_entries: BackupRepo::recurring_entries(pool, budget_id).await?,
+            entries: BackupRepo::entries(pool, budget_id).await?,
+            entry_splits: BackupRepo::entry_splits(pool, budget_id).await?,
+            tags: BackupRepo::tags(pool, budget_id).await?,
+            entry_tags: BackupRepo::entry_tags(pool, budget_id).await?,
+            transfers: BackupRepo::transfers(pool, budget_id).await?,
+            comments: BackupRepo::comments(pool, budget_id).await?,
+            mentions: BackupRepo::mentions(pool, budget_id).await?,
+            attachments: BackupRepo::attachments(pool, budget_id).await?,
+            import_keys: BackupRepo::import_keys(pool, budget_id).await?,
+            rules: BackupRepo::rules(pool, budget_id).await?,
+            rule_tags: BackupRepo::rule_tags(pool, budget_id).await?,
+            exchange_rates: BackupRepo::exchange_rates(pool, budget_id).await?,
+            savings_goals: BackupRepo::savings_goals(pool, budget_id).await?,
+            savings_goal_categories: BackupRepo::savings_goal_categories(pool, budget_id).await?,
+            debts: BackupRepo::debts(pool, budget_id).await?,
+            holdings: BackupRepo::holdings(pool, budget_id).await?,
+            investment_transactions: BackupRepo::investment_transactions(pool, budget_id).await?,
+            price_snapshots: BackupRepo::price_snapshots(pool, budget_id).await?,
+        })
+    }
+
+    /// Zip with `budget.json` plus every attachment file that could be downloaded.
+    /// Attachments whose file is missing keep pointing at the original URL.
+    pub async fn archive_zip(pool: &DbPool, budget_id: &str) -> Result<Vec<u8>, AppError> {
+        let mut archive = Self::archive(pool, budget_id).await?;
+        let options = zip::write::SimpleFileOptions::default();
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+        let s3 = get_s3_client()?;
+        for attachment in archive.attachments.iter_mut().filter(|a| a.deleted_at.is_none()) {
+            match s3.download_attachment(&attachment.file_url).await {
+                Ok(data) => {
+                    let path = format!("attachments/{}.{}", attachment.id, Self::extension(&attachment.file_url));
+                    zip.start_file(path.as_str(), options).map_err(Self::zip_err)?;
+                    zip.write_all(&data).map_err(|_| AppError::Internal)?;
+                    attachment.blob = Some(path);
+                }
+                Err(e) => tracing::error!("Skipping attachment {} in backup: {:?}", attachment.id, e),
+            }
+        }
+
+        zip.start_file(ARCHIVE_JSON, options).map_err(Self::zip_err)?;
+        serde_json::to_writer_pretty(&mut zip, &archive).map_err(|_| AppError::Internal)?;
+        Ok(zip.finish().map_err(Self::zip_err)?.into_inner())
+    }
+
+    /// Recreate an archived budget owned by `user_id` under new ids. Members are
+    /// matched by email; rows by unknown users are reassigned to the restoring user.
+    pub async fn restore(pool: &DbPool, user_id: &str, req: RestoreReq) -> Result<RestoreReport, AppError> {
+        let base64_data = req.file_data.split("base64,").nth(1).unwrap_or(&req.file_data);
+        let data = general_purpose::STANDARD
+            .decode(base64_data.trim())
+            .map_err(|_| AppError::BadRequest("Invalid base64 file data".to_string()))?;
+
+        let mut blobs: Option<zip::ZipArchive<Cursor<Vec<u8>>>> = None;
+        let archive: BudgetArchive = if data.starts_with(b"PK") {
+            let mut zip = zip::ZipArchive::new(Cursor::new(data))
+                .map_err(|_| AppError::BadRequest("Invalid zip archive".to_string()))?;
+            let mut json = Vec::new();
+            zip.by_name(ARCHIVE_JSON)
+                .map_err(|_| AppError::BadRequest(format!("Archive has no {}", ARCHIVE_JSON)))?
+                .read_to_end(&mut json)
+                .map_err(|_| AppError::BadRequest("Unreadable archive".to_string()))?;
+            blobs = Some(zip);
+            serde_json::from_slice(&json)
+        } else {
+            serde_json::from_slice(&data)
+        }
+        .map_err(|e| AppError::BadRequest(format!("Invalid archive: {}", e)))?;
+
+        if archive.version > ARCHIVE_VERSION {
+            return Err(AppError::BadRequest(format!("Archive version {} is newer than supported version {}", archive.version, ARCHIVE_VERSION)));
+        }
+        Self::check_references(&archive)?;
+
+        let mut plan = Self::plan(pool, user_id, &archive, req.name).await?;
+        let mut conflicts = Self::conflicts(&archive, &plan);
+        let mut report = RestoreReport {
+            dry_run: req.dry_run.unwrap_or(false),
+            budget_id: None,
+            budget_name: plan.name.clone(),
+            categories: archive.categories.len(),
+            entries: archive.entries.len(),
+            comments: archive.comments.len(),
+            attachments: archive.attachments.len(),
+            conflicts: RestoreConflicts::default(),
+        };
+        if report.dry_run {
+            conflicts.missing_attachment_files = archive.attachments.iter()
+                .filter(|a| a.blob.is_none() || blobs.is_none())
+                .count();
+            report.conflicts = conflicts;
+            return Ok(report);
+        }
+
+        let mut uploaded = Vec::new();
+        if let Some(zip) = blobs.as_mut() {
+            let s3 = get_s3_client()?;
+            for attachment in &archive.attachments {
+                let Some(path) = &attachment.blob else { continue };
+                let mut data = Vec::new();
+                if zip.by_name(path).map(|mut f| f.read_to_end(&mut data)).is_err() {
+                    continue;
+                }
+                let url = s3.upload_comment_attachment(
+                    &plan.id(&attachment.entry_id),
+                    &plan.id(&attachment.id),
+                    data,
+                    &attachment.mime_type,
+                    Self::extension(path),
+                ).await?;
+                uploaded.push(url.clone());
+                plan.file_urls.insert(attachment.id.clone(), url);
+            }
+        }
+        conflicts.missing_attachment_files = archive.attachments.len() - plan.file_urls.len();
+
+        if let Err(e) = BackupRepo::restore(pool, &archive, &plan).await {
+            for url in &uploaded {
+                if let Err(cleanup) = get_s3_client()?.delete_attachment(url).await {
+                    tracing::error!("Failed to remove restored attachment {}: {:?}", url, cleanup);
+                }
+            }
+            return Err(e);
+        }
+
+        report.budget_id = Some(plan.budget_id);
+        report.conflicts = conflicts;
+        Ok(report)
+    }
+
+    async fn plan(pool: &DbPool, user_id: &str, archive: &BudgetArchive, name: Option<String>) -> Result<RestorePlan, AppError> {
+        let mut emails: HashSet<String> = archive.members.iter().map(|m| m.email.to_lowercase()).collect();
+        emails.extend(archive.entries.iter().map(|e| e.created_by_email.to_lowercase()));
+        emails.extend(archive.entries.iter().filter_map(|e| e.updated_by_email.as_ref()).map(|e| e.to_lowercase()));
+        emails.extend(archive.recurring_entries.iter().map(|r| r.created_by_email.to_lowercase()));
+        emails.extend(archive.comments.iter().map(|c| c.user_email.to_lowercase()));
+        emails.extend(archive.mentions.iter().map(|m| m.user_email.to_lowercase()));
+        emails.extend(archive.attachments.iter().map(|a| a.user_email.to_lowercase()));
+        emails.extend(archive.savings_goals.iter().map(|g| g.created_by_email.to_lowercase()));
+        emails.extend(archive.debts.iter().map(|d| d.created_by_email.to_lowercase()));
+        emails.extend(archive.investment_transactions.iter().map(|t| t.created_by_email.to_lowercase()));
+        let emails: Vec<String> = emails.into_iter().collect();
+        let users: HashMap<String, String> = BackupRepo::user_ids_by_email(pool, &emails).await?.into_iter().collect();
+
+        let old_ids = archive.categories.iter().map(|c| &c.id)
+            .chain(archive.category_limits.iter().map(|l| &l.id))
+            .chain(archive.payees.iter().map(|p| &p.id))
+            .chain(archive.recurring_entries.iter().map(|r| &r.id))
+            .chain(archive.entries.iter().map(|e| &e.id))
+            .chain(archive.tags.iter().map(|t| &t.id))
+            .chain(archive.comments.iter().map(|c| &c.id))
+            .chain(archive.attachments.iter().map(|a| &a.id))
+            .chain(archive.rules.iter().map(|r| &r.id))
+            .chain(archive.savings_goals.iter().map(|g| &g.id))
+            .chain(archive.holdings.iter().map(|h| &h.id));
+        let ids = old_ids.map(|id| (id.clone(), uuid::Uuid::new_v4().to_string())).collect();
+
+        let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())
+            .unwrap_or_else(|| archive.budget.name.clone());
+
+        Ok(RestorePlan {
+            budget_id: uuid::Uuid::new_v4().to_string(),
+            owner_id: user_id.to_string(),
+            name,
+            ids,
+            users,
+            file_urls: HashMap::new(),
+        })
+    }
+
+    fn conflicts(archive: &BudgetArchive, plan: &RestorePlan) -> RestoreConflicts {
+        let known = |email: &str| plan.users.contains_key(&email.to_lowercase());
+        let mut unknown: Vec<String> = archive.members.iter()
+            .filter(|m| !known(&m.email))
+            .map(|m| m.email.clone())
+            .collect();
+        unknown.sort();
+
+        let reassigned_rows = archive.entries.iter().filter(|e| !known(&e.created_by_email)).count()
+            + archive.recurring_entries.iter().filter(|r| !known(&r.created_by_email)).count()
+            + archive.comments.iter().filter(|c| !known(&c.user_email)).count()
+            + archive.attachments.iter().filter(|a| !known(&a.user_email)).count()
+            + archive.savings_goals.iter().filter(|g| !known(&g.created_by_email)).count()
+            + archive.debts.iter().filter(|d| !known(&d.created_by_email)).count()
+            + archive.investment_transactions.iter().filter(|t| !known(&t.created_by_email)).count();
+
+        RestoreConflicts {
+            unknown_member_emails: unknown,
+            reassigned_rows,
+            skipped_mentions: archive.mentions.iter().filter(|m| !known(&m.user_email)).count(),
+            unlinked_transfers: archive.entries.iter().filter(|e| e.transfer_id.is_some()).count(),
+            missing_attachment_files: 0,
+        }
+    }
+
+    /// Reject archives whose rows point at ids that are not in the archive or
+    /// hold values the typed models cannot read back.
+    fn check_references(archive: &BudgetArchive) -> Result<(), AppError> {
+        let categories: HashSet<&str> = archive.categories.iter().map(|c| c.id.as_str()).collect();
+        let recurring: HashSet<&str> = archive.recurring_entries.iter().map(|r| r.id.as_str()).collect();
+        let entries: HashSet<&str> = archive.entries.iter().map(|e| e.id.as_str()).collect();
+        let comments: HashSet<&str> = archive.comments.iter().map(|c| c.id.as_str()).collect();
+        let tags: HashSet<&str> = archive.tags.iter().map(|t| t.id.as_str()).collect();
+        let payees: HashSet<&str> = archive.payees.iter().map(|p| p.id.as_str()).collect();
+        let rules: HashSet<&str> = archive.rules.iter().map(|r| r.id.as_str()).collect();
+        let goals: HashSet<&str> = archive.savings_goals.iter().map(|g| g.id.as_str()).collect();
+        let holdings: HashSet<&str> = archive.holdings.iter().map(|h| h.id.as_str()).collect();
+        let broken = |what: &str, id: &str| AppError::BadRequest(format!("Archive is inconsistent: {} references unknown id {}", what, id));
+        let invalid = |what: &str, value: &str| AppError::BadRequest(format!("Archive is inconsistent: {} has invalid value {}", what, value));
+
+        if archive.budget.budget_type.parse::<BudgetType>().is_err() { return Err(invalid("budget type", &archive.budget.budget_type)); }
+        for m in &archive.members {
+            if Role::from_str(&m.role).is_none() { return Err(invalid("member role", &m.role)); }
+        }
+        for c in &archive.categories {
+            if c.rollover_policy.parse::<RolloverPolicy>().is_err() { return Err(invalid("category rollover policy", &c.rollover_policy)); }
+        }
+        for r in &archive.recurring_entries {
+            if r.frequency.parse::<Frequency>().is_err() { return Err(invalid("recurring frequency", &r.frequency)); }
+        }
+        for r in &archive.rules {
+            if !["contains", "equals", "starts_with"].contains(&r.counterparty_match.as_str()) { return Err(invalid("rule counterparty match", &r.counterparty_match)); }
+        }
+        for h in &archive.holdings {
+            if h.cost_method.parse::<CostMethod>().is_err() { return Err(invalid("holding cost method", &h.cost_method)); }
+        }
+        for t in &archive.investment_transactions {
+            if t.kind.parse::<TradeKind>().is_err() { return Err(invalid("investment transaction kind", &t.kind)); }
+        }
+
+        for c in &archive.categories {
+            if let Some(id) = c.parent_id.as_deref().filter(|id| !categories.contains(id)) { return Err(broken("category", id)); }
+        }
+        for l in &archive.category_limits {
+            if !categories.contains(l.category_id.as_str()) { return Err(broken("category limit", &l.category_id)); }
+        }
+        for p in &archive.payees {
+            if let Some(id) = p.default_category_id.as_deref().filter(|id| !categories.contains(id)) { return Err(broken("payee", id)); }
+        }
+        for a in &archive.payee_aliases {
+            if !payees.contains(a.payee_id.as_str()) { return Err(broken("payee alias", &a.payee_id)); }
+        }
+        for r in &archive.recurring_entries {
+            if !categories.contains(r.category_id.as_str()) { return Err(broken("recurring entry", &r.category_id)); }
+        }
+        for e in &archive.entries {
+            if !categories.contains(e.category_id.as_str()) { return Err(broken("entry", &e.category_id)); }
+            if let Some(id) = e.recurring_id.as_deref().filter(|id| !recurring.contains(id)) { return Err(broken("entry", id)); }
+            if let Some(id) = e.payee_id.as_deref().filter(|id| !payees.contains(id)) { return Err(broken("entry", id)); }
+        }
+        for s in &archive.entry_splits {
+            if !entries.contains(s.entry_id.as_str()) { return Err(broken("entry split", &s.entry_id)); }
+            if !categories.contains(s.category_id.as_str()) { return Err(broken("entry split", &s.category_id)); }
+        }
+        for et in &archive.entry_tags {
+            if !entries.contains(et.entry_id.as_str()) { return Err(broken("entry tag", &et.entry_id)); }
+            if !tags.contains(et.tag_id.as_str()) { return Err(broken("entry tag", &et.tag_id)); }
+        }
+        for c in &archive.comments {
+            if !entries.contains(c.entry_id.as_str()) { return Err(broken("comment", &c.entry_id)); }
+        }
+        for m in &archive.mentions {
+            if !comments.contains(m.comment_id.as_str()) { return Err(broken("mention", &m.comment_id)); }
+        }
+        for a in &archive.attachments {
+            if !entries.contains(a.entry_id.as_str()) { return Err(broken("attachment", &a.entry_id)); }
+            if let Some(id) = a.comment_id.as_deref().filter(|id| !comments.contains(id)) { return Err(broken("attachment", id)); }
+        }
+        for r in &archive.rules {
+            if let Some(id) = r.set_category_id.as_deref().filter(|id| !categories.contains(id)) { return Err(broken("rule", id)); }
+        }
+        for rt in &archive.rule_tags {
+            if !rules.contains(rt.rule_id.as_str()) { return Err(broken("rule tag", &rt.rule_id)); }
+            if !tags.contains(rt.tag_id.as_str()) { return Err(broken("rule tag", &rt.tag_id)); }
+        }
+        for gc in &archive.savings_goal_categories {
+            if !goals.contains(gc.goal_id.as_str()) { return Err(broken("savings goal category", &gc.goal_id)); }
+            if !categories.contains(gc.category_id.as_str()) { return Err(broken("savings goal category", &gc.category_id)); }
+        }
+        for d in &archive.debts {
+            if let Some(id) = d.category_id.as_deref().filter(|id| !categories.contains(id)) { return Err(broken("debt", id)); }
+        }
+        for t in &archive.investment_transactions {
+            if !holdings.contains(t.holding_id.as_str()) { return Err(broken("investment transaction", &t.holding_id)); }
+            if let Some(id) = t.entry_id.as_deref().filter(|id| !entries.contains(id)) { return Err(broken("investment transaction", id)); }
+        }
+        Ok(())
+    }
+
+    fn extension(path: &str) -> &str {
+        path.rsplit_once('.').map(|(_, ext)| ext).filter(|ext| !ext.contains('/')).unwrap_or("bin")
+    }
+
+    fn zip_err(e: zip::result::ZipError) -> AppError {
+        tracing::error!("Failed to write backup zip: {:?}", e);
+        AppError::Internal
+    }
+}
@@ -1,4 +1,5 @@
-use crate::manager::{models::budget::{Budget, BudgetWithRole, CreateBudgetReq}};
+use crate::manager::{models::budget::{Budget, BudgetType, BudgetWithRole, CreateBudgetReq}};
+use crate::manager::biz::category_templates::CategoryTemplateService;
 use crate::handler::budgets::UpdateBudgetReq;
 use crate::utils::{database::database::DbPool, error::error::AppError};
 use crate::manager::{repository::budgets::BudgetRepo};
@@ -23,7 +24,14 @@ impl BudgetService {
     }
     
     pub async fn create(pool: &DbPool, req: CreateBudgetReq) -> Result<Budget, AppError> {
-        BudgetRepo::create(pool, req).await
+        let categories = match req.template_id.as_deref() {
+            Some(template_id) => {
+                let budget_type = req.budget_type.clone().unwrap_or(BudgetType::Standard);
+                CategoryTemplateService::resolve(pool, &req.owner_id, template_id, &budget_type).await?
+            }
+            None => Vec::new(),
+        };
+        BudgetRepo::create(pool, req, &categories).await
     }
     
     pub async fn update(pool: &DbPool, id: &str, req: UpdateBudgetReq) -> Result<Budget, AppError> {
@@ -1,20 +1,78 @@
-use crate::manager::{models::category::{Category, CreateCategoryReq, UpdateCategoryReq}};
+use std::collections::HashMap;
+use crate::manager::{models::category::{Category, CategoryNode, CategorySummaryRow, CreateCategoryReq, MergeCategoryReq, MergeCategoryReport, UpdateCategoryReq}, models::category_limit::CategoryLimit};
 use crate::utils::{database::database::DbPool, error::error::AppError};
-use crate::manager::{repository::categories::CategoryRepo};
+use crate::manager::{repository::categories::CategoryRepo, repository::category_snapshots::CategorySnapshotRepo, repository::entries::EntryRepo, repository::category_limits::CategoryLimitRepo};
 pub struct CategoryService;
 
 impl CategoryService {
-    pub async fn list(pool: &DbPool, budget_id: &str, kind: Option<String>) -> Result<Vec<Category>, AppError> {
-        CategoryRepo::list(pool, budget_id, kind).await
+    /// Hidden categories are left out unless `include_hidden` is set. Reports read
+    /// the repository directly so hidden categories keep their history.
+    pub async fn list(pool: &DbPool, budget_id: &str, kind: Option<String>, include_hidden: bool) -> Result<Vec<Category>, AppError> {
+        let mut categories = CategoryRepo::list(pool, budget_id, kind).await?;
+        if !include_hidden {
+            categories.retain(|c| !c.is_hidden);
+        }
+        Ok(categories)
+    }
+    
+    /// Categories nested under their parents; siblings keep the name order of `list`.
+    pub async fn tree(pool: &DbPool, budget_id: &str, kind: Option<String>, include_hidden: bool) -> Result<Vec<CategoryNode>, AppError> {
+        let categories = Self::list(pool, budget_id, kind, include_hidden).await?;
+        let ids: std::collections::HashSet<String> = categories.iter().map(|c| c.id.clone()).collect();
+        let mut by_parent: HashMap<Option<String>, Vec<Category>> = HashMap::new();
+        for category in categories {
+            // A parent outside the result is treated as top level
+            let parent = category.parent_id.clone().filter(|p| ids.contains(p));
+            by_parent.entry(parent).or_default().push(category);
+        }
+        Ok(Self::build_nodes(&mut by_parent, None))
+    }
+
+    /// Income and expense per category over `from..=to`, with child totals rolled up into parents.
+    pub async fn summary(pool: &DbPool, budget_id: &str, from: chrono::NaiveDate, to: chrono::NaiveDate) -> Result<Vec<CategorySummaryRow>, AppError> {
+        if from > to {
+            return Err(AppError::BadRequest("from must not be after to".to_string()));
+        }
+        let categories = CategoryRepo::list(pool, budget_id, None).await?;
+        let mut own: HashMap<String, [i64; 2]> = HashMap::new();
+        for (_, category_id, income, expense) in EntryRepo::monthly_category_summary(pool, budget_id, from, to).await? {
+            let values = own.entry(category_id).or_insert([0; 2]);
+            values[0] += income;
+            values[1] += expense;
+        }
+        let totals = Self::roll_up(&categories, &own);
+
+        Ok(categories.into_iter()
+            .filter_map(|c| {
+                let [total_income_minor, total_expense_minor] = *totals.get(&c.id)?;
+                let [income_minor, expense_minor] = own.get(&c.id).copied().unwrap_or([0; 2]);
+                Some(CategorySummaryRow {
+                    category_id: c.id,
+                    category_name: c.name,
+                    kind: c.kind,
+                    parent_id: c.parent_id,
+                    income_minor,
+                    expense_minor,
+                    total_income_minor,
+                    total_expense_minor,
+                })
+            })
+            .collect())
     }
     
-    pub async fn create(pool: &DbPool, budget_id: &str, req: CreateCategoryReq) -> Result<Category, AppError> {
+    pub async fn create(pool: &DbPool, budget_id: &str, mut req: CreateCategoryReq) -> Result<Category, AppError> {
         // Validate color format if provided
         if let Some(color) = &req.color {
             if !Self::is_valid_color(color) {
                 return Err(AppError::BadRequest("Invalid color format. Use hex format like #FF5733".to_string()));
             }
         }
+
+        req.parent_id = req.parent_id.filter(|p| !p.is_empty());
+        if let Some(parent_id) = req.parent_id.as_deref() {
+            let categories = CategoryRepo::list(pool, budget_id, None).await?;
+            Self::validate_parent(&categories, None, parent_id, &req.kind)?;
+        }
         
         CategoryRepo::create(pool, budget_id, req).await
     }
@@ -35,8 +93,10 @@ impl CategoryService {
             if &current.kind != new_kind {
                 // Check if category has any active entries
                 let entry_count = sqlx::query_scalar::<_, i64>(
-                    "SELECT COUNT(*) FROM entries WHERE category_id = ? AND deleted_at IS NULL"
+                    "SELECT COUNT(*) FROM entries e WHERE e.deleted_at IS NULL \
+                     AND (e.category_id = ? OR EXISTS (SELECT 1 FROM entry_splits s WHERE s.entry_id = e.id AND s.category_id = ?))"
                 )
+                    .bind(category_id)
                     .bind(category_id)
                     .fetch_one(pool)
                     .await?;
@@ -49,14 +109,42 @@ impl CategoryService {
             }
         }
         
-        CategoryRepo::update(pool, budget_id, category_id, req).await
+        // Subcategories share their parent's kind, and a category cannot move below itself
+        if req.parent_id.is_some() || req.kind.is_some() {
+            let categories = CategoryRepo::list(pool, budget_id, None).await?;
+            let current = categories.iter().find(|c| c.id == category_id).ok_or(AppError::NotFound)?;
+            let kind = req.kind.as_deref().unwrap_or(&current.kind);
+            let parent_id = match req.parent_id.as_deref() {
+                Some("") => None,
+                Some(parent_id) => Some(parent_id),
+                None => current.parent_id.as_deref(),
+            };
+            if let Some(parent_id) = parent_id {
+                Self::validate_parent(&categories, Some(category_id), parent_id, kind)?;
+            }
+            if kind != current.kind && categories.iter().any(|c| c.parent_id.as_deref() == Some(category_id)) {
+                return Err(AppError::BadRequest("Cannot change category type. It has subcategories.".to_string()));
+            }
+        }
+        
+        // Kind and rollover policy both change every cached envelope month
+        let invalidate = req.kind.is_some() || req.rollover_policy.is_some();
+        let category = CategoryRepo::update(pool, budget_id, category_id, req).await?;
+        if invalidate {
+            CategorySnapshotRepo::invalidate_all(pool, budget_id).await?;
+        }
+        Ok(category)
     }
     
-    pub async fn delete(pool: &DbPool, budget_id: &str, category_id: &str) -> Result<(), AppError> {
+    /// Subcategories block the delete unless `reparent_children` moves them up to
+    /// the deleted category's parent.
+    pub async fn delete(pool: &DbPool, budget_id: &str, category_id: &str, reparent_children: bool) -> Result<(), AppError> {
         // Check if category is being used by any active (non-deleted) entries
         let entry_count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM entries WHERE category_id = ? AND deleted_at IS NULL"
+            "SELECT COUNT(*) FROM entries e WHERE e.deleted_at IS NULL \
+             AND (e.category_id = ? OR EXISTS (SELECT 1 FROM entry_splits s WHERE s.entry_id = e.id AND s.category_id = ?))"
         )
+            .bind(category_id)
             .bind(category_id)
             .fetch_one(pool)
             .await?;
@@ -64,15 +152,185 @@ impl CategoryService {
         if entry_count > 0 {
             return Err(AppError::BadRequest(format!("Cannot delete category. It is used by {} active entries.", entry_count)));
         }
+
+        let recurring_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM recurring_entries WHERE category_id = ?")
+            .bind(category_id)
+            .fetch_one(pool)
+            .await?;
+
+        if recurring_count > 0 {
+            return Err(AppError::BadRequest(format!("Cannot delete category. It is used by {} recurring entries.", recurring_count)));
+        }
+
+        let schedule_count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM scheduled_transfers \
+             WHERE from_category_id = ? OR to_category_id = ? OR fee_category_id = ?"
+        )
+            .bind(category_id)
+            .bind(category_id)
+            .bind(category_id)
+            .fetch_one(pool)
+            .await?;
+
+        if schedule_count > 0 {
+            return Err(AppError::BadRequest(format!("Cannot delete category. It is used by {} scheduled transfers.", schedule_count)));
+        }
+
+        let category = CategoryRepo::get_by_id(pool, budget_id, category_id).await?;
+        let child_count = CategoryRepo::list(pool, budget_id, None).await?
+            .iter()
+            .filter(|c| c.parent_id.as_deref() == Some(category_id))
+            .count();
+        if child_count > 0 {
+            if !reparent_children {
+                return Err(AppError::BadRequest(format!(
+                    "Cannot delete category. It has {} subcategories. Move them first or set reparent_children=true.", child_count
+                )));
+            }
+            CategoryRepo::reparent_children(pool, budget_id, category_id, category.parent_id.as_deref()).await?;
+        }
         
         CategoryRepo::delete(pool, budget_id, category_id).await
     }
     
+    /// Move all entries, split lines, recurring entries, subcategories and limits
+    /// of `source_id` into the target category, then remove the source.
+    pub async fn merge(pool: &DbPool, budget_id: &str, source_id: &str, req: MergeCategoryReq) -> Result<MergeCategoryReport, AppError> {
+        let target_id = req.target_category_id.as_str();
+        if target_id == source_id {
+            return Err(AppError::BadRequest("Cannot merge a category into itself".to_string()));
+        }
+        let categories = CategoryRepo::list(pool, budget_id, None).await?;
+        let source = categories.iter().find(|c| c.id == source_id).ok_or(AppError::NotFound)?;
+        let target = categories.iter().find(|c| c.id == target_id)
+            .ok_or_else(|| AppError::BadRequest("Target category not found in this budget".to_string()))?;
+        if source.kind != target.kind {
+            return Err(AppError::BadRequest(format!("Cannot merge a {} category into a {} category", source.kind, target.kind)));
+        }
+        let below_source = Self::lineage(&categories).get(target_id)
+            .is_some_and(|chain| chain.iter().any(|id| id == source_id));
+        if below_source {
+            return Err(AppError::BadRequest("Cannot merge a category into one of its subcategories".to_string()));
+        }
+
+        let limits = Self::merge_limits(&CategoryLimitRepo::list_by_budget(pool, budget_id).await?, source_id, target_id);
+        let report = CategoryRepo::merge(pool, budget_id, source_id, target_id, &limits).await?;
+        CategorySnapshotRepo::invalidate_all(pool, budget_id).await?;
+        Ok(report)
+    }
+
+    /// Archiving hides a category from pickers and new entries; its entries and
+    /// reports are untouched.
+    pub async fn set_hidden(pool: &DbPool, budget_id: &str, category_id: &str, hidden: bool) -> Result<Category, AppError> {
+        CategoryRepo::set_hidden(pool, budget_id, category_id, hidden).await
+    }
+
+    /// Category for a new assignment: it must belong to the budget and not be hidden,
+    /// unless it is in `keep`, the categories the record already uses.
+    pub async fn ensure_assignable(pool: &DbPool, budget_id: &str, category_id: &str, keep: &[&str]) -> Result<Category, AppError> {
+        let category = CategoryRepo::list(pool, budget_id, None).await?
+            .into_iter()
+            .find(|c| c.id == category_id)
+            .ok_or_else(|| AppError::BadRequest("Category not found in this budget".to_string()))?;
+        if category.is_hidden && !keep.contains(&category_id) {
+            return Err(AppError::BadRequest(format!("Category '{}' is archived", category.name)));
+        }
+        Ok(category)
+    }
+
     pub async fn get_by_id(pool: &DbPool, budget_id: &str, category_id: &str) -> Result<Category, AppError> {
         CategoryRepo::get_by_id(pool, budget_id, category_id).await
     }
     
-    fn is_valid_color(color: &str) -> bool {
+    /// Ids of every category's chain up to the top level, starting with the
+    /// category itself. A chain stops early at an unknown parent or a loop.
+    pub(crate) fn lineage(categories: &[Category]) -> HashMap<String, Vec<String>> {
+        let parents: HashMap<&str, Option<&str>> = categories.iter().map(|c| (c.id.as_str(), c.parent_id.as_deref())).collect();
+        categories.iter()
+            .map(|c| {
+                let mut chain = vec![c.id.clone()];
+                let mut next = c.parent_id.as_deref();
+                while let Some(parent_id) = next {
+                    if !parents.contains_key(parent_id) || chain.iter().any(|id| id == parent_id) {
+                        break;
+                    }
+                    chain.push(parent_id.to_string());
+                    next = parents[parent_id];
+                }
+                (c.id.clone(), chain)
+            })
+            .collect()
+    }
+
+    /// Ids of the given categories together with all of their subcategories.
+    pub(crate) fn with_descendants(categories: &[Category], roots: &[String]) -> Vec<String> {
+        Self::lineage(categories).into_iter()
+            .filter(|(_, chain)| chain.iter().any(|id| roots.contains(id)))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Add each category's own values into itself and all of its ancestors.
+    pub(crate) fn roll_up<const N: usize>(categories: &[Category], own: &HashMap<String, [i64; N]>) -> HashMap<String, [i64; N]> {
+        let lineage = Self::lineage(categories);
+        let mut totals: HashMap<String, [i64; N]> = HashMap::new();
+        for (category_id, values) in own {
+            let Some(chain) = lineage.get(category_id) else { continue };
+            for id in chain {
+                let total = totals.entry(id.clone()).or_insert([0; N]);
+                for (t, v) in total.iter_mut().zip(values) {
+                    *t += v;
+                }
+            }
+        }
+        totals
+    }
+
+    /// A limit stays in effect until superseded, so at every period start of either
+    /// category the merged plan is the sum of both plans in effect at that point.
+    fn merge_limits(limits: &[CategoryLimit], source_id: &str, target_id: &str) -> Vec<(chrono::NaiveDate, i64)> {
+        // Limits are sorted by period_start, so the last match is the one in effect
+        let in_effect = |category_id: &str, month: chrono::NaiveDate| limits.iter()
+            .rev()
+            .find(|l| l.category_id == category_id && l.period_start <= month)
+            .map(|l| l.planned_minor)
+            .unwrap_or(0);
+        let mut starts: Vec<chrono::NaiveDate> = limits.iter()
+            .filter(|l| l.category_id == source_id || l.category_id == target_id)
+            .map(|l| l.period_start)
+            .collect();
+        starts.sort();
+        starts.dedup();
+        starts.into_iter().map(|month| (month, in_effect(source_id, month) + in_effect(target_id, month))).collect()
+    }
+
+    fn validate_parent(categories: &[Category], category_id: Option<&str>, parent_id: &str, kind: &str) -> Result<(), AppError> {
+        let parent = categories.iter().find(|c| c.id == parent_id)
+            .ok_or_else(|| AppError::BadRequest("Parent category not found in this budget".to_string()))?;
+        if parent.kind != kind {
+            return Err(AppError::BadRequest(format!("Parent category '{}' is for {} entries", parent.name, parent.kind)));
+        }
+        if let Some(category_id) = category_id {
+            let below_itself = Self::lineage(categories).get(parent_id)
+                .is_some_and(|chain| chain.iter().any(|id| id == category_id));
+            if below_itself {
+                return Err(AppError::BadRequest("A category cannot be moved under itself or one of its subcategories".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    fn build_nodes(by_parent: &mut HashMap<Option<String>, Vec<Category>>, parent_id: Option<String>) -> Vec<CategoryNode> {
+        by_parent.remove(&parent_id).unwrap_or_default()
+            .into_iter()
+            .map(|category| {
+                let children = Self::build_nodes(by_parent, Some(category.id.clone()));
+                CategoryNode { category, children }
+            })
+            .collect()
+    }
+
+    pub(crate) fn is_valid_color(color: &str) -> bool {
         // Check if it's a valid hex color (e.g., #FF5733 or #f57)
         if !color.starts_with('#') {
             return false;
@@ -0,0 +1,188 @@
+use chrono::Datelike;
+use std::collections::HashMap;
+use crate::manager::models::category::Category;
+use crate::manager::biz::categories::CategoryService;
+use crate::manager::models::category_limit::{BudgetVsActualRow, CategoryLimit, CategoryMonthSnapshot, CreateCategoryLimitReq, UpdateCategoryLimitReq};
+use crate::manager::repository::{categories::CategoryRepo, category_limits::CategoryLimitRepo, category_snapshots::CategorySnapshotRepo, entries::EntryRepo};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct CategoryLimitService;
+
+impl CategoryLimitService {
+    pub async fn list(pool: &DbPool, budget_id: &str, category_id: &str) -> Result<Vec<CategoryLimit>, AppError> {
+        CategoryRepo::get_by_id(pool, budget_id, category_id).await?;
+        CategoryLimitRepo::list_by_category(pool, budget_id, category_id).await
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, category_id: &str, mut req: CreateCategoryLimitReq) -> Result<CategoryLimit, AppError> {
+        CategoryRepo::get_by_id(pool, budget_id, category_id).await?;
+        if req.planned_minor < 0 {
+            return Err(AppError::BadRequest("Planned amount cannot be negative".to_string()));
+        }
+        req.period_start = Self::month_start(req.period_start);
+        let limit = CategoryLimitRepo::create(pool, budget_id, category_id, &req).await?;
+        Self::invalidate_from(pool, budget_id, limit.period_start).await?;
+        Ok(limit)
+    }
+
+    pub async fn update(pool: &DbPool, budget_id: &str, category_id: &str, limit_id: &str, req: UpdateCategoryLimitReq) -> Result<CategoryLimit, AppError> {
+        let mut limit = CategoryLimitRepo::get_by_id(pool, budget_id, category_id, limit_id).await?;
+        let previous_start = limit.period_start;
+
+        if let Some(period_start) = req.period_start {
+            limit.period_start = Self::month_start(period_start);
+        }
+        if let Some(planned_minor) = req.planned_minor {
+            if planned_minor < 0 {
+                return Err(AppError::BadRequest("Planned amount cannot be negative".to_string()));
+            }
+            limit.planned_minor = planned_minor;
+        }
+
+        let limit = CategoryLimitRepo::update(pool, &limit).await?;
+        Self::invalidate_from(pool, budget_id, previous_start.min(limit.period_start)).await?;
+        Ok(limit)
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, category_id: &str, limit_id: &str) -> Result<(), AppError> {
+        let limit = CategoryLimitRepo::get_by_id(pool, budget_id, category_id, limit_id).await?;
+        CategoryLimitRepo::delete(pool, budget_id, category_id, limit_id).await?;
+        Self::invalidate_from(pool, budget_id, limit.period_start).await
+    }
+
+    /// Planned vs actual for every category that has a limit, a rollover or activity in the month.
+    /// Available is rollover from the previous month plus planned minus spent.
+    pub async fn budget_vs_actual(pool: &DbPool, budget_id: &str, month: chrono::NaiveDate) -> Result<Vec<BudgetVsActualRow>, AppError> {
+        let month_start = Self::month_start(month);
+        let categories = CategoryRepo::list(pool, budget_id, None).await?;
+        let snapshots: HashMap<String, CategoryMonthSnapshot> = Self::envelopes_for_month(pool, budget_id, month_start, &categories).await?
+            .into_iter()
+            .map(|s| (s.category_id.clone(), s))
+            .collect();
+
+        let own: HashMap<String, [i64; 4]> = snapshots.values()
+            .map(|s| (s.category_id.clone(), [s.planned_minor, s.spent_minor, s.rollover_minor, s.available_minor]))
+            .collect();
+        let totals = CategoryService::roll_up(&categories, &own);
+
+        let rows = categories.into_iter()
+            .filter_map(|c| {
+                let s = snapshots.get(&c.id)?;
+                let [total_planned_minor, total_spent_minor, total_rollover_minor, total_available_minor] = totals.get(&c.id).copied().unwrap_or([0; 4]);
+                // Parents stay in the report while any subcategory has activity
+                if total_planned_minor == 0 && total_spent_minor == 0 && total_rollover_minor == 0 {
+                    return None;
+                }
+                Some(BudgetVsActualRow {
+                    category_id: c.id,
+                    category_name: c.name,
+                    kind: c.kind,
+                    parent_id: c.parent_id,
+                    rollover_policy: c.rollover_policy,
+                    planned_minor: s.planned_minor,
+                    spent_minor: s.spent_minor,
+                    remaining_minor: s.planned_minor - s.spent_minor,
+                    percent_used: Self::percent(s.spent_minor, s.planned_minor),
+                    rollover_minor: s.rollover_minor,
+                    available_minor: s.available_minor,
+                    total_planned_minor,
+                    total_spent_minor,
+                    total_available_minor,
+                })
+            })
+            .collect();
+
+        Ok(rows)
+    }
+
+    /// Drop cached envelope months affected by a change dated `date`.
+    pub async fn invalidate_from(pool: &DbPool, budget_id: &str, date: chrono::NaiveDate) -> Result<(), AppError> {
+        CategorySnapshotRepo::invalidate_from(pool, budget_id, Self::month_start(date)).await
+    }
+
+    /// Walks the rollover chain forward from the last cached month (or the budget's
+    /// first activity) up to `month_start`, caching every closed month on the way.
+    async fn envelopes_for_month(pool: &DbPool, budget_id: &str, month_start: chrono::NaiveDate, categories: &[Category]) -> Result<Vec<CategoryMonthSnapshot>, AppError> {
+        let cached = CategorySnapshotRepo::latest_cached_month(pool, budget_id, month_start).await?;
+        if cached == Some(month_start) {
+            return CategorySnapshotRepo::list_for_month(pool, budget_id, month_start).await;
+        }
+
+        let limits = CategoryLimitRepo::list_by_budget(pool, budget_id).await?;
+        let mut available: HashMap<String, i64> = HashMap::new();
+        let start = match cached {
+            Some(cached_month) => {
+                for s in CategorySnapshotRepo::list_for_month(pool, budget_id, cached_month).await? {
+                    available.insert(s.category_id, s.available_minor);
+                }
+                Self::next_month_start(cached_month)
+            }
+            None => {
+                let first_entry = EntryRepo::earliest_entry_date(pool, budget_id).await?.map(Self::month_start);
+                let first_limit = limits.first().map(|l| l.period_start);
+                [first_entry, first_limit].into_iter().flatten().min().unwrap_or(month_start).min(month_start)
+            }
+        };
+
+        let month_end = Self::next_month_start(month_start).pred_opt().unwrap_or(month_start);
+        let actual: HashMap<(chrono::NaiveDate, String), (i64, i64)> = EntryRepo::monthly_category_summary(pool, budget_id, start, month_end).await?
+            .into_iter()
+            .map(|(m, category_id, income, expense)| ((m, category_id), (income, expense)))
+            .collect();
+        let current_month = Self::month_start(chrono::Utc::now().date_naive());
+
+        let mut month = start;
+        loop {
+            let snapshots: Vec<CategoryMonthSnapshot> = categories.iter().map(|c| {
+                // Limits are sorted by period_start, so the last match is the one in effect
+                let planned_minor = limits.iter()
+                    .rev()
+                    .find(|l| l.category_id == c.id && l.period_start <= month)
+                    .map(|l| l.planned_minor)
+                    .unwrap_or(0);
+                let (income, expense) = actual.get(&(month, c.id.clone())).copied().unwrap_or((0, 0));
+                let spent_minor = if c.kind == "income" { income } else { expense };
+                let rollover_minor = c.rollover_policy.carry(available.get(&c.id).copied().unwrap_or(0));
+                CategoryMonthSnapshot {
+                    budget_id: budget_id.to_string(),
+                    category_id: c.id.clone(),
+                    month_start: month,
+                    planned_minor,
+                    spent_minor,
+                    rollover_minor,
+                    available_minor: rollover_minor + planned_minor - spent_minor,
+                }
+            }).collect();
+
+            for s in &snapshots {
+                available.insert(s.category_id.clone(), s.available_minor);
+            }
+
+            // Only closed months are cached; the current month can still change
+            if month < current_month {
+                CategorySnapshotRepo::save_month(pool, budget_id, month, &snapshots).await?;
+            }
+
+            if month >= month_start {
+                return Ok(snapshots);
+            }
+            month = Self::next_month_start(month);
+        }
+    }
+
+    pub(crate) fn month_start(date: chrono::NaiveDate) -> chrono::NaiveDate {
+        date.with_day(1).unwrap_or(date)
+    }
+
+    pub(crate) fn next_month_start(month_start: chrono::NaiveDate) -> chrono::NaiveDate {
+        let (year, month) = if month_start.month() == 12 { (month_start.year() + 1, 1) } else { (month_start.year(), month_start.month() + 1) };
+        chrono::NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(month_start)
+    }
+
+    fn percent(spent_minor: i64, planned_minor: i64) -> Option<f64> {
+        if planned_minor <= 0 {
+            return None;
+        }
+        Some((spent_minor as f64 / planned_minor as f64 * 10000.0).round() / 100.0)
+    }
+}
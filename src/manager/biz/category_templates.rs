@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use crate::manager::models::budget::BudgetType;
+use crate::manager::models::category_template::{CategoryTemplate, SaveTemplateReq, TemplateCategory, TemplateFilter};
+use crate::manager::repository::{budgets::BudgetRepo, categories::CategoryRepo, category_templates::CategoryTemplateRepo, users::UserRepo};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct CategoryTemplateService;
+
+impl CategoryTemplateService {
+    /// Built-in templates (one per budget type unless filtered) followed by the user's own.
+    pub async fn list(pool: &DbPool, user_id: &str, filter: TemplateFilter) -> Result<Vec<CategoryTemplate>, AppError> {
+        let locale = match filter.locale {
+            Some(locale) => locale,
+            None => UserRepo::get_by_id(pool, user_id).await?.locale,
+        };
+        let types = match &filter.budget_type {
+            Some(budget_type) => vec![budget_type.clone()],
+            None => vec![BudgetType::Standard, BudgetType::Saving, BudgetType::Debt, BudgetType::Invest, BudgetType::Sharing],
+        };
+        let mut templates: Vec<CategoryTemplate> = types.iter().map(|t| CategoryTemplate::builtin(t, &locale)).collect();
+
+        let wanted_type = filter.budget_type.map(|t| t.to_string());
+        let mut custom: Vec<CategoryTemplate> = CategoryTemplateRepo::list_for_owner(pool, user_id).await?
+            .into_iter()
+            .filter(|t| wanted_type.is_none() || t.budget_type.is_none() || t.budget_type == wanted_type)
+            .collect();
+        let ids: Vec<String> = custom.iter().map(|t| t.id.clone()).collect();
+        let mut items: HashMap<String, Vec<TemplateCategory>> = HashMap::new();
+        for item in CategoryTemplateRepo::items(pool, &ids).await? {
+            items.entry(item.template_id.clone()).or_default().push(item);
+        }
+        for template in custom.iter_mut() {
+            template.categories = items.remove(&template.id).unwrap_or_default();
+        }
+        templates.extend(custom);
+        Ok(templates)
+    }
+
+    /// Save the budget's current categories, including hierarchy, as a template of the user.
+    pub async fn save_from_budget(pool: &DbPool, budget_id: &str, user_id: &str, req: SaveTemplateReq) -> Result<CategoryTemplate, AppError> {
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest("Template name is required".to_string()));
+        }
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let categories = CategoryRepo::list(pool, budget_id, None).await?;
+        if categories.is_empty() {
+            return Err(AppError::BadRequest("Budget has no categories to save".to_string()));
+        }
+
+        let template_id = uuid::Uuid::new_v4().to_string();
+        let item_ids: HashMap<&str, String> = categories.iter().map(|c| (c.id.as_str(), uuid::Uuid::new_v4().to_string())).collect();
+        let items: Vec<TemplateCategory> = categories.iter()
+            .enumerate()
+            .map(|(position, c)| TemplateCategory {
+                id: item_ids[c.id.as_str()].clone(),
+                template_id: template_id.clone(),
+                parent_id: c.parent_id.as_deref().and_then(|p| item_ids.get(p)).cloned(),
+                name: c.name.clone(),
+                kind: c.kind.clone(),
+                color: c.color.clone(),
+                icon: c.icon.clone(),
+                rollover_policy: c.rollover_policy.to_string(),
+                position: position as i32,
+            })
+            .collect();
+
+        CategoryTemplateRepo::create(pool, &template_id, user_id, name, &budget.budget_type.to_string(), &items).await?;
+        let mut template = CategoryTemplateRepo::get(pool, user_id, &template_id).await?;
+        template.categories = items;
+        Ok(template)
+    }
+
+    pub async fn delete(pool: &DbPool, user_id: &str, template_id: &str) -> Result<(), AppError> {
+        CategoryTemplateRepo::delete(pool, user_id, template_id).await
+    }
+
+    /// Categories to seed a new budget with: "builtin" picks the built-in template
+    /// for the budget type in the user's locale, anything else is a saved template id.
+    pub async fn resolve(pool: &DbPool, user_id: &str, template_id: &str, budget_type: &BudgetType) -> Result<Vec<TemplateCategory>, AppError> {
+        if template_id == "builtin" {
+            let locale = UserRepo::get_by_id(pool, user_id).await?.locale;
+            return Ok(CategoryTemplate::builtin(budget_type, &locale).categories);
+        }
+        let template = CategoryTemplateRepo::get(pool, user_id, template_id).await
+            .map_err(|e| match e {
+                AppError::NotFound => AppError::BadRequest("Category template not found".to_string()),
+                e => e,
+            })?;
+        CategoryTemplateRepo::items(pool, &[template.id]).await
+    }
+}
@@ -0,0 +1,273 @@
+use chrono::Datelike;
+use crate::manager::biz::{categories::CategoryService, category_limits::CategoryLimitService};
+use crate::manager::models::budget::BudgetType;
+use crate::manager::models::category::Category;
+use crate::manager::models::debt::{CreateDebtReq, Debt, DebtPayoff, PayoffPlan, PayoffPlanQuery, PayoffStrategy, PlanMonth, PlanPayment, StrategyPlan, UpdateDebtReq};
+use crate::manager::repository::{budgets::BudgetRepo, categories::CategoryRepo, debts::DebtRepo};
+use crate::utils::{database::database::DbPool, error::error::{AppError, FieldError}};
+
+/// Simulations give up after 50 years.
+const MAX_PLAN_MONTHS: usize = 600;
+
+pub struct DebtService;
+
+/// A debt's state while a payoff plan is simulated.
+struct SimulatedDebt<'a> {
+    debt: &'a Debt,
+    balance: i64,
+    interest: i64,
+    paid: i64,
+    payoff_date: Option<chrono::NaiveDate>,
+}
+
+impl DebtService {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<Debt>, AppError> {
+        let mut debts = DebtRepo::list(pool, budget_id).await?;
+        Self::load_balances(pool, budget_id, &mut debts).await?;
+        Ok(debts)
+    }
+
+    pub async fn get(pool: &DbPool, budget_id: &str, debt_id: &str) -> Result<Debt, AppError> {
+        let mut debt = DebtRepo::get_by_id(pool, budget_id, debt_id).await?;
+        Self::load_balances(pool, budget_id, std::slice::from_mut(&mut debt)).await?;
+        Ok(debt)
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, user_id: &str, mut req: CreateDebtReq) -> Result<Debt, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        if budget.budget_type != BudgetType::Debt {
+            return Err(AppError::BadRequest("Debts can only be added to debt budgets".to_string()));
+        }
+        req.name = req.name.trim().to_string();
+        req.note = req.note.take().map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        req.category_id = req.category_id.take().map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+        let errors = Self::check_terms(&req.name, req.principal_minor, req.apr, req.minimum_payment_minor.unwrap_or(0), req.due_day.unwrap_or(1));
+        Self::validate(pool, budget_id, errors, req.category_id.as_deref()).await?;
+        let debt = DebtRepo::create(pool, budget_id, &req, user_id).await?;
+        Self::get(pool, budget_id, &debt.id).await
+    }
+
+    /// An empty `category_id` unlinks the payment category.
+    pub async fn update(pool: &DbPool, budget_id: &str, debt_id: &str, req: UpdateDebtReq) -> Result<Debt, AppError> {
+        let mut debt = DebtRepo::get_by_id(pool, budget_id, debt_id).await?;
+        if let Some(name) = req.name {
+            debt.name = name.trim().to_string();
+        }
+        if let Some(principal) = req.principal_minor {
+            debt.principal_minor = principal;
+        }
+        if let Some(apr) = req.apr {
+            debt.apr = apr;
+        }
+        if let Some(minimum) = req.minimum_payment_minor {
+            debt.minimum_payment_minor = minimum;
+        }
+        if let Some(due_day) = req.due_day {
+            debt.due_day = due_day;
+        }
+        if let Some(category_id) = req.category_id {
+            debt.category_id = Some(category_id.trim().to_string()).filter(|c| !c.is_empty());
+        }
+        if req.opened_on.is_some() {
+            debt.opened_on = req.opened_on;
+        }
+        if let Some(note) = req.note {
+            debt.note = Some(note.trim().to_string()).filter(|n| !n.is_empty());
+        }
+        let errors = Self::check_terms(&debt.name, debt.principal_minor, debt.apr, debt.minimum_payment_minor, debt.due_day);
+        Self::validate(pool, budget_id, errors, debt.category_id.as_deref()).await?;
+        DebtRepo::update(pool, &debt).await?;
+        Self::get(pool, budget_id, debt_id).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, debt_id: &str) -> Result<(), AppError> {
+        DebtRepo::delete(pool, budget_id, debt_id).await
+    }
+
+    /// Simulate paying off the current balances from next month on, with the
+    /// minimums plus `extra_monthly_minor` every month. Whatever a paid-off
+    /// debt's minimum frees up rolls over to the next debt in line.
+    pub async fn plan(pool: &DbPool, budget_id: &str, query: PayoffPlanQuery) -> Result<PayoffPlan, AppError> {
+        let extra_monthly_minor = query.extra_monthly_minor.unwrap_or(0);
+        if extra_monthly_minor < 0 {
+            return Err(AppError::Validation(vec![FieldError::new("extra_monthly_minor", "Extra amount must not be negative")]));
+        }
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let debts: Vec<Debt> = Self::list(pool, budget_id).await?
+            .into_iter()
+            .filter(|d| d.balance_minor > 0)
+            .collect();
+
+        let monthly_payment_minor = debts.iter().map(|d| d.minimum_payment_minor).sum::<i64>() + extra_monthly_minor;
+        let first_month = CategoryLimitService::next_month_start(CategoryLimitService::month_start(chrono::Utc::now().date_naive()));
+        let strategies = match query.strategy {
+            Some(strategy) => vec![strategy],
+            None => vec![PayoffStrategy::Snowball, PayoffStrategy::Avalanche],
+        };
+
+        Ok(PayoffPlan {
+            currency_code: budget.currency_code,
+            extra_monthly_minor,
+            monthly_payment_minor,
+            starting_balance_minor: debts.iter().map(|d| d.balance_minor).sum(),
+            strategies: strategies.into_iter()
+                .map(|strategy| Self::simulate(&debts, monthly_payment_minor, strategy, first_month))
+                .collect(),
+        })
+    }
+
+    fn simulate(debts: &[Debt], monthly_payment_minor: i64, strategy: PayoffStrategy, first_month: chrono::NaiveDate) -> StrategyPlan {
+        let mut sims: Vec<SimulatedDebt> = debts.iter()
+            .map(|debt| SimulatedDebt { debt, balance: debt.balance_minor, interest: 0, paid: 0, payoff_date: None })
+            .collect();
+        let mut schedule = Vec::new();
+        let mut month = first_month;
+
+        while schedule.len() < MAX_PLAN_MONTHS {
+            let before: i64 = sims.iter().map(|s| s.balance).sum();
+            if before == 0 {
+                break;
+            }
+            let active: Vec<usize> = (0..sims.len()).filter(|&i| sims[i].balance > 0).collect();
+            let mut interest = vec![0i64; sims.len()];
+            let mut payment = vec![0i64; sims.len()];
+            let mut available = monthly_payment_minor;
+
+            for &i in &active {
+                let sim = &mut sims[i];
+                interest[i] = (sim.balance as f64 * sim.debt.apr / 1200.0).round() as i64;
+                sim.balance += interest[i];
+            }
+            for &i in &active {
+                let pay = sims[i].debt.minimum_payment_minor.min(sims[i].balance).min(available);
+                payment[i] += pay;
+                sims[i].balance -= pay;
+                available -= pay;
+            }
+            let mut order = active.clone();
+            order.sort_by(|&a, &b| {
+                let (a, b) = (&sims[a], &sims[b]);
+                match strategy {
+                    PayoffStrategy::Snowball => a.balance.cmp(&b.balance).then(b.debt.apr.total_cmp(&a.debt.apr)),
+                    PayoffStrategy::Avalanche => b.debt.apr.total_cmp(&a.debt.apr).then(a.balance.cmp(&b.balance)),
+                }.then_with(|| a.debt.name.cmp(&b.debt.name))
+            });
+            for i in order {
+                if available == 0 {
+                    break;
+                }
+                let pay = available.min(sims[i].balance);
+                payment[i] += pay;
+                sims[i].balance -= pay;
+                available -= pay;
+            }
+
+            let mut payments = Vec::with_capacity(active.len());
+            for i in active {
+                let sim = &mut sims[i];
+                let due_date = Self::due_date(month, sim.debt.due_day);
+                sim.interest += interest[i];
+                sim.paid += payment[i];
+                if sim.balance == 0 {
+                    sim.payoff_date = Some(due_date);
+                }
+                payments.push(PlanPayment {
+                    debt_id: sim.debt.id.clone(),
+                    due_date,
+                    payment_minor: payment[i],
+                    interest_minor: interest[i],
+                    principal_minor: payment[i] - interest[i],
+                    balance_minor: sim.balance,
+                });
+            }
+            let remaining_balance_minor: i64 = sims.iter().map(|s| s.balance).sum();
+            schedule.push(PlanMonth { month_start: month, payments, remaining_balance_minor });
+            month = CategoryLimitService::next_month_start(month);
+            // Payments that do not outpace the interest never clear the debts
+            if remaining_balance_minor >= before {
+                break;
+            }
+        }
+
+        let paid_off = sims.iter().all(|s| s.balance == 0);
+        StrategyPlan {
+            strategy,
+            paid_off,
+            months: schedule.len() as u32,
+            payoff_date: if paid_off { sims.iter().filter_map(|s| s.payoff_date).max() } else { None },
+            total_interest_minor: sims.iter().map(|s| s.interest).sum(),
+            total_paid_minor: sims.iter().map(|s| s.paid).sum(),
+            debts: sims.into_iter()
+                .map(|s| DebtPayoff {
+                    debt_id: s.debt.id.clone(),
+                    name: s.debt.name.clone(),
+                    starting_balance_minor: s.debt.balance_minor,
+                    interest_minor: s.interest,
+                    paid_minor: s.paid,
+                    payoff_date: s.payoff_date,
+                })
+                .collect(),
+            schedule,
+        }
+    }
+
+    /// The due day in the month, or its last day when the month is shorter.
+    fn due_date(month_start: chrono::NaiveDate, due_day: i32) -> chrono::NaiveDate {
+        (1..=due_day.clamp(1, 31) as u32).rev()
+            .find_map(|day| month_start.with_day(day))
+            .unwrap_or(month_start)
+    }
+
+    async fn load_balances(pool: &DbPool, budget_id: &str, debts: &mut [Debt]) -> Result<(), AppError> {
+        let mut categories: Option<Vec<Category>> = None;
+        for debt in debts.iter_mut() {
+            if let Some(category_id) = debt.category_id.clone() {
+                if categories.is_none() {
+                    categories = Some(CategoryRepo::list(pool, budget_id, None).await?);
+                }
+                let category_ids = CategoryService::with_descendants(categories.as_deref().unwrap_or_default(), &[category_id]);
+                debt.paid_minor = DebtRepo::payments(pool, budget_id, &category_ids, debt.opened_on).await?;
+            }
+            debt.balance_minor = (debt.principal_minor - debt.paid_minor).max(0);
+        }
+        Ok(())
+    }
+
+    fn check_terms(name: &str, principal_minor: i64, apr: f64, minimum_payment_minor: i64, due_day: i32) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if name.is_empty() {
+            errors.push(FieldError::new("name", "Debt name is required"));
+        }
+        if principal_minor <= 0 {
+            errors.push(FieldError::new("principal_minor", "Principal must be positive"));
+        }
+        if !apr.is_finite() || !(0.0..=100.0).contains(&apr) {
+            errors.push(FieldError::new("apr", "APR must be between 0 and 100"));
+        }
+        if minimum_payment_minor < 0 {
+            errors.push(FieldError::new("minimum_payment_minor", "Minimum payment must not be negative"));
+        }
+        if !(1..=31).contains(&due_day) {
+            errors.push(FieldError::new("due_day", "Due day must be between 1 and 31"));
+        }
+        errors
+    }
+
+    /// Fails with `errors` plus any problem with the payment category.
+    async fn validate(pool: &DbPool, budget_id: &str, mut errors: Vec<FieldError>, category_id: Option<&str>) -> Result<(), AppError> {
+        if let Some(category_id) = category_id {
+            match CategoryRepo::list(pool, budget_id, None).await?.into_iter().find(|c| c.id == category_id) {
+                None => errors.push(FieldError::new("category_id", "Category not found in this budget")),
+                Some(c) if c.kind != "expense" => {
+                    errors.push(FieldError::new("category_id", format!("Category '{}' is for {} entries", c.name, c.kind)));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(AppError::Validation(errors));
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,23 @@
-use crate::manager::{models::entry::{Entry, CreateEntryReq}};
+use std::collections::HashMap;
+use crate::manager::{models::budget::Budget, models::category::Category, models::entry::{Entry, CreateEntryReq, EntrySplitReq, RecategorizeReq, RecategorizeReport}, models::tag::TagFilter, models::rule::RuleSubject, models::exchange_rate::Conversion};
 use crate::handler::entries::UpdateEntryReq;
-use crate::utils::{database::database::DbPool, error::error::AppError};
-use crate::manager::{repository::entries::EntryRepo, repository::budgets::BudgetRepo};
+use crate::utils::{database::database::DbPool, error::error::{AppError, FieldError}};
+use crate::manager::{repository::entries::EntryRepo, repository::budgets::BudgetRepo, repository::categories::CategoryRepo, repository::entry_splits::EntrySplitRepo, repository::category_snapshots::CategorySnapshotRepo, repository::investments::InvestmentRepo};
+use crate::manager::biz::{categories::CategoryService, category_limits::CategoryLimitService, tags::TagService, rules::RuleService, payees::PayeeService, exchange_rates::ExchangeRateService};
 pub struct EntryService;
 
+/// The values of a new or edited entry that `validate_entry` checks.
+struct EntryDraft<'a> {
+    kind: &'a str,
+    amount_minor: i64,
+    currency_code: &'a str,
+    exchange_rate: Option<f64>, // given by the client or kept from before; looked up otherwise
+    entry_date: chrono::NaiveDate,
+    category_id: Option<&'a str>,
+    splits: Option<&'a [EntrySplitReq]>,
+    keep: Vec<&'a str>, // hidden categories the entry already uses
+}
+
 impl EntryService {
     pub async fn list(
         pool: &DbPool,
@@ -14,27 +28,263 @@ impl EntryService {
         category_id: Option<String>,
         member_id: Option<String>,
         search: Option<String>,
+        tags: Option<TagFilter>,
         sort_by: Option<String>,
         sort_order: Option<String>,
         page: Option<u32>,
         per_page: Option<u32>,
     ) -> Result<Vec<Entry>, AppError> {
-        EntryRepo::list(pool, budget_id, from, to, kind, category_id, member_id, search, sort_by, sort_order, page, per_page).await
+        let mut entries = EntryRepo::list(pool, budget_id, from, to, kind, category_id, member_id, search, tags.as_ref(), sort_by, sort_order, page, per_page).await?;
+        Self::attach_splits(pool, &mut entries).await?;
+        TagService::attach_tags(pool, &mut entries).await?;
+        Ok(entries)
     }
-    pub async fn create(pool: &DbPool, budget_id: &str, req: CreateEntryReq) -> Result<Entry, AppError> {
+    pub async fn create(pool: &DbPool, budget_id: &str, mut req: CreateEntryReq) -> Result<Entry, AppError> {
         let budget = BudgetRepo::get(pool, budget_id).await?;
-        EntryRepo::create(pool, budget_id, req, &budget.currency_code).await
+        Self::apply_rules(pool, budget_id, &mut req).await?;
+        let payee = PayeeService::find(pool, budget_id, req.counterparty.as_deref()).await?;
+        if let Some(category_id) = payee.as_ref().and_then(|p| p.default_category_id.as_deref()) {
+            if req.category_id.is_none() && req.splits.as_ref().is_none_or(|s| s.is_empty()) {
+                let category = CategoryRepo::get_by_id(pool, budget_id, category_id).await?;
+                if category.kind == req.kind && !category.is_hidden {
+                    req.category_id = Some(category.id);
+                }
+            }
+        }
+        // The parent of a split keeps a category for list views; reports use the lines
+        if req.category_id.is_none() {
+            req.category_id = req.splits.as_deref().and_then(|s| s.first()).map(|s| s.category_id.clone());
+        }
+        let conversion = Self::validate_entry(pool, &budget, &EntryDraft {
+            kind: &req.kind,
+            amount_minor: req.amount_minor,
+            currency_code: req.currency_code.as_deref().unwrap_or(&budget.currency_code),
+            exchange_rate: req.exchange_rate,
+            entry_date: req.entry_date,
+            category_id: req.category_id.as_deref(),
+            splits: req.splits.as_deref(),
+            keep: Vec::new(),
+        }).await?;
+        req.base_amount_minor = conversion.base_amount_minor;
+        req.exchange_rate = conversion.exchange_rate;
+        if let Some(tag_ids) = &req.tag_ids {
+            req.tag_ids = Some(TagService::resolve_ids(pool, budget_id, tag_ids).await?);
+        }
+        let payee = match payee {
+            Some(payee) => Some(payee),
+            None => PayeeService::resolve(pool, budget_id, req.counterparty.as_deref()).await?,
+        };
+        if let Some(payee) = payee {
+            req.counterparty = Some(payee.name);
+            req.payee_id = Some(payee.id);
+        }
+        let mut entry = EntryRepo::create(pool, budget_id, req, &budget.currency_code).await?;
+        CategoryLimitService::invalidate_from(pool, budget_id, entry.entry_date).await?;
+        Self::attach_splits(pool, std::slice::from_mut(&mut entry)).await?;
+        TagService::attach_tags(pool, std::slice::from_mut(&mut entry)).await?;
+        Ok(entry)
     }
-    pub async fn update(pool: &DbPool, budget_id: &str, entry_id: &str, req: UpdateEntryReq, user_id: &str) -> Result<Entry, AppError> {
-        EntryRepo::update(pool, budget_id, entry_id, req, user_id).await
+    pub async fn update(pool: &DbPool, budget_id: &str, entry_id: &str, mut req: UpdateEntryReq, user_id: &str) -> Result<Entry, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let mut previous = EntryRepo::get_by_id(pool, budget_id, entry_id).await?;
+        Self::ensure_not_transfer(&previous)?;
+        Self::ensure_not_investment(pool, entry_id).await?;
+        Self::attach_splits(pool, std::slice::from_mut(&mut previous)).await?;
+
+        let kind = req.kind.clone().unwrap_or_else(|| previous.kind.clone());
+        let amount_minor = req.amount_minor.unwrap_or(previous.amount_minor);
+        if req.splits.is_none() && !previous.splits.is_empty() && (kind != previous.kind || amount_minor != previous.amount_minor) {
+            return Err(AppError::BadRequest("Entry is split; send the splits together with a new amount or kind".to_string()));
+        }
+        let currency_code = req.currency_code.clone().unwrap_or_else(|| previous.currency_code.clone());
+        let entry_date = req.entry_date.unwrap_or(previous.entry_date);
+        // The stored rate still holds unless the currency or the date moves
+        let keep_rate = currency_code.eq_ignore_ascii_case(&previous.currency_code) && entry_date == previous.entry_date;
+        // Hidden categories the entry already uses stay allowed; new assignments must be visible
+        let conversion = Self::validate_entry(pool, &budget, &EntryDraft {
+            kind: &kind,
+            amount_minor,
+            currency_code: &currency_code,
+            exchange_rate: req.exchange_rate.or(previous.exchange_rate.filter(|_| keep_rate)),
+            entry_date,
+            category_id: Some(req.category_id.as_deref().unwrap_or(&previous.category_id)),
+            splits: req.splits.as_deref(),
+            keep: previous.splits.iter().map(|s| s.category_id.as_str())
+                .chain(std::iter::once(previous.category_id.as_str()))
+                .collect(),
+        }).await?;
+        req.base_amount_minor = Some(conversion.base_amount_minor);
+        req.exchange_rate = conversion.exchange_rate;
+        if let Some(tag_ids) = &req.tag_ids {
+            req.tag_ids = Some(TagService::resolve_ids(pool, budget_id, tag_ids).await?);
+        }
+        if req.counterparty.is_some() {
+            if let Some(payee) = PayeeService::resolve(pool, budget_id, req.counterparty.as_deref()).await? {
+                req.counterparty = Some(payee.name);
+                req.payee_id = Some(payee.id);
+            }
+        }
+
+        let mut entry = EntryRepo::update(pool, budget_id, entry_id, req, user_id).await?;
+        CategoryLimitService::invalidate_from(pool, budget_id, previous.entry_date.min(entry.entry_date)).await?;
+        Self::attach_splits(pool, std::slice::from_mut(&mut entry)).await?;
+        TagService::attach_tags(pool, std::slice::from_mut(&mut entry)).await?;
+        Ok(entry)
     }
     
     pub async fn delete(pool: &DbPool, budget_id: &str, entry_id: &str, user_id: &str) -> Result<(), AppError> {
-        EntryRepo::delete(pool, budget_id, entry_id, user_id).await
+        let entry = EntryRepo::get_by_id(pool, budget_id, entry_id).await?;
+        Self::ensure_not_transfer(&entry)?;
+        Self::ensure_not_investment(pool, entry_id).await?;
+        EntryRepo::delete(pool, budget_id, entry_id, user_id).await?;
+        CategoryLimitService::invalidate_from(pool, budget_id, entry.entry_date).await
     }
     
+    /// Transfer legs change only together, through the transfer endpoints.
+    fn ensure_not_transfer(entry: &Entry) -> Result<(), AppError> {
+        match &entry.transfer_id {
+            Some(transfer_id) => Err(AppError::BadRequest(format!("Entry is part of transfer {}; edit or delete the transfer instead", transfer_id))),
+            None => Ok(()),
+        }
+    }
+
+    /// The cash side of an investment transaction goes away with the transaction.
+    async fn ensure_not_investment(pool: &DbPool, entry_id: &str) -> Result<(), AppError> {
+        match InvestmentRepo::transaction_id_for_entry(pool, entry_id).await? {
+            Some(transaction_id) => Err(AppError::BadRequest(format!("Entry is the cash of investment transaction {}; delete the transaction instead", transaction_id))),
+            None => Ok(()),
+        }
+    }
+
+    /// Bulk move of the entries matching a filter to another category.
+    pub async fn recategorize(pool: &DbPool, budget_id: &str, req: RecategorizeReq, user_id: &str) -> Result<RecategorizeReport, AppError> {
+        let target = CategoryService::ensure_assignable(pool, budget_id, &req.target_category_id, &[]).await?;
+        let tags = TagFilter::parse(req.tags.as_deref(), req.tag_match.as_deref())?;
+        // Guard against moving the whole budget by accident
+        let has_filter = req.from.is_some() || req.to.is_some() || req.category_id.is_some()
+            || req.member_id.is_some() || req.search.is_some() || tags.is_some();
+        if !has_filter {
+            return Err(AppError::BadRequest("At least one filter is required".to_string()));
+        }
+
+        let report = EntryRepo::recategorize(pool, budget_id, &req, &target.kind, tags.as_ref(), user_id).await?;
+        CategorySnapshotRepo::invalidate_all(pool, budget_id).await?;
+        Ok(report)
+    }
+
     pub async fn monthly_summary(pool: &DbPool, budget_id: &str, from: chrono::NaiveDate, to: chrono::NaiveDate)
         -> Result<Vec<(chrono::NaiveDate, i64, i64, i64)>, AppError> {
         EntryRepo::monthly_summary(pool, budget_id, from, to).await
     }
+
+    /// Run the budget's rules over a new entry. A category chosen by the client
+    /// wins over the rules; the normalized counterparty and rule tags always apply.
+    async fn apply_rules(pool: &DbPool, budget_id: &str, req: &mut CreateEntryReq) -> Result<(), AppError> {
+        let rules = RuleService::rule_set(pool, budget_id).await?;
+        if rules.is_empty() {
+            return Ok(());
+        }
+        let outcome = rules.apply(&RuleSubject {
+            kind: &req.kind,
+            amount_minor: req.amount_minor,
+            description: req.description.as_deref(),
+            counterparty: req.counterparty.as_deref(),
+        });
+        if req.category_id.is_none() && req.splits.as_ref().is_none_or(|s| s.is_empty()) {
+            req.category_id = outcome.category_id;
+        }
+        if outcome.counterparty.is_some() {
+            req.counterparty = outcome.counterparty;
+        }
+        if !outcome.tag_ids.is_empty() {
+            let tag_ids = req.tag_ids.get_or_insert_with(Vec::new);
+            tag_ids.extend(outcome.tag_ids);
+        }
+        Ok(())
+    }
+
+    pub async fn attach_splits(pool: &DbPool, entries: &mut [Entry]) -> Result<(), AppError> {
+        let ids: Vec<String> = entries.iter().map(|e| e.id.clone()).collect();
+        let mut by_entry: HashMap<String, Vec<_>> = HashMap::new();
+        for split in EntrySplitRepo::list_for_entries(pool, &ids).await? {
+            by_entry.entry(split.entry_id.clone()).or_default().push(split);
+        }
+        for entry in entries.iter_mut() {
+            entry.splits = by_entry.remove(&entry.id).unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    /// Check an entry against its budget: a category of the budget matching the
+    /// kind, a positive amount, and split lines that use such categories and add
+    /// up to the amount. Every problem is reported as a field error; on success
+    /// returns the `Conversion` of the amount into the budget currency.
+    async fn validate_entry(pool: &DbPool, budget: &Budget, draft: &EntryDraft<'_>) -> Result<Conversion, AppError> {
+        let categories = CategoryRepo::list(pool, &budget.id, None).await?;
+        let mut errors = Vec::new();
+
+        let kind_valid = draft.kind == "income" || draft.kind == "expense";
+        if !kind_valid {
+            errors.push(FieldError::new("kind", "Kind must be income or expense"));
+        }
+        if draft.amount_minor <= 0 {
+            errors.push(FieldError::new("amount_minor", "Amount must be positive"));
+        }
+        let mut conversion = None;
+        if draft.currency_code.len() != 3 || !draft.currency_code.chars().all(|c| c.is_ascii_alphabetic()) {
+            errors.push(FieldError::new("currency_code", format!("Invalid currency code: {}", draft.currency_code)));
+        } else if draft.exchange_rate.is_some_and(|r| !r.is_finite() || r <= 0.0) {
+            errors.push(FieldError::new("exchange_rate", "Exchange rate must be positive"));
+        } else {
+            conversion = ExchangeRateService::convert(pool, budget, draft.currency_code, draft.amount_minor, draft.entry_date, draft.exchange_rate).await?;
+            if conversion.is_none() {
+                errors.push(FieldError::new("currency_code", format!(
+                    "No exchange rate from {} to {} on or before {}; add one or send exchange_rate",
+                    draft.currency_code.to_uppercase(), budget.currency_code, draft.entry_date
+                )));
+            }
+        }
+        match draft.category_id {
+            None => errors.push(FieldError::new("category_id", "Category is required")),
+            Some(category_id) => {
+                if let Some(message) = Self::category_problem(&categories, category_id, kind_valid.then_some(draft.kind), &draft.keep) {
+                    errors.push(FieldError::new("category_id", message));
+                }
+            }
+        }
+
+        if let Some(splits) = draft.splits.filter(|s| !s.is_empty()) {
+            let mut total: i64 = 0;
+            for (i, split) in splits.iter().enumerate() {
+                if split.amount_minor <= 0 {
+                    errors.push(FieldError::new(format!("splits[{}].amount_minor", i), "Amount must be positive"));
+                }
+                if let Some(message) = Self::category_problem(&categories, &split.category_id, kind_valid.then_some(draft.kind), &draft.keep) {
+                    errors.push(FieldError::new(format!("splits[{}].category_id", i), message));
+                }
+                total = total.saturating_add(split.amount_minor);
+            }
+            if total != draft.amount_minor {
+                errors.push(FieldError::new("splits", format!("Split amounts add up to {} but the entry amount is {}", total, draft.amount_minor)));
+            }
+        }
+
+        match conversion {
+            Some(conversion) if errors.is_empty() => Ok(conversion),
+            _ => Err(AppError::Validation(errors)),
+        }
+    }
+
+    fn category_problem(categories: &[Category], category_id: &str, kind: Option<&str>, keep: &[&str]) -> Option<String> {
+        let Some(category) = categories.iter().find(|c| c.id == category_id) else {
+            return Some("Category not found in this budget".to_string());
+        };
+        if kind.is_some_and(|k| k != category.kind) {
+            return Some(format!("Category '{}' is for {} entries", category.name, category.kind));
+        }
+        if category.is_hidden && !keep.contains(&category_id) {
+            return Some(format!("Category '{}' is archived", category.name));
+        }
+        None
+    }
 }
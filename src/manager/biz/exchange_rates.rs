@@ -0,0 +1,94 @@
+use std::collections::BTreeSet;
+use crate::manager::models::budget::Budget;
+use crate::manager::models::exchange_rate::{Conversion, ExchangeRate, ExchangeRateFilter, ImportExchangeRatesReport, ImportExchangeRatesReq, RateRow, SaveExchangeRateReq};
+use crate::manager::repository::exchange_rates::ExchangeRateRepo;
+use crate::manager::biz::imports::ImportService;
+use crate::utils::{database::database::DbPool, error::error::AppError, currency, importers};
+
+pub struct ExchangeRateService;
+
+impl ExchangeRateService {
+    pub async fn list(pool: &DbPool, budget_id: &str, filter: ExchangeRateFilter) -> Result<Vec<ExchangeRate>, AppError> {
+        ExchangeRateRepo::list(pool, budget_id, &filter).await
+    }
+
+    pub async fn save(pool: &DbPool, budget_id: &str, req: SaveExchangeRateReq) -> Result<ExchangeRate, AppError> {
+        let row = RateRow {
+            rate_date: req.rate_date,
+            base_currency: Self::currency_code(&req.base_currency)?,
+            quote_currency: Self::currency_code(&req.quote_currency)?,
+            rate: req.rate,
+        };
+        if row.base_currency == row.quote_currency {
+            return Err(AppError::BadRequest("Base and quote currency must differ".to_string()));
+        }
+        if !row.rate.is_finite() || row.rate <= 0.0 {
+            return Err(AppError::BadRequest("Rate must be positive".to_string()));
+        }
+        ExchangeRateRepo::upsert(pool, budget_id, std::slice::from_ref(&row), "manual").await?;
+        ExchangeRateRepo::find(pool, budget_id, row.rate_date, &row.base_currency, &row.quote_currency).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, rate_id: &str) -> Result<(), AppError> {
+        ExchangeRateRepo::delete(pool, budget_id, rate_id).await
+    }
+
+    /// Load a rate file. Stored rates for the same pair and date are replaced;
+    /// entries keep the conversion they were saved with.
+    pub async fn import(pool: &DbPool, budget_id: &str, req: ImportExchangeRatesReq) -> Result<ImportExchangeRatesReport, AppError> {
+        let data = ImportService::decode_file(&req.file_data)?;
+        let rows = match req.format.as_str() {
+            "csv" => importers::exchange_rates::parse_csv(&data),
+            "ecb" => importers::exchange_rates::parse_ecb(&data),
+            other => return Err(AppError::BadRequest(format!("Unsupported rate format: {}. Use csv or ecb", other))),
+        }
+        .map_err(AppError::BadRequest)?;
+        let rows: Vec<RateRow> = rows.into_iter().filter(|r| r.base_currency != r.quote_currency).collect();
+        if rows.is_empty() {
+            return Err(AppError::BadRequest("The file contains no rates".to_string()));
+        }
+
+        ExchangeRateRepo::upsert(pool, budget_id, &rows, &req.format).await?;
+        let currencies: BTreeSet<String> = rows.iter()
+            .flat_map(|r| [r.base_currency.clone(), r.quote_currency.clone()])
+            .collect();
+        Ok(ImportExchangeRatesReport {
+            imported: rows.len(),
+            currencies: currencies.into_iter().collect(),
+            from: rows.iter().map(|r| r.rate_date).min(),
+            to: rows.iter().map(|r| r.rate_date).max(),
+        })
+    }
+
+    /// Convert an amount in `currency_code` into the budget currency. `given_rate`
+    /// overrides the rate table; `None` means no rate is known for that date.
+    pub async fn convert(
+        pool: &DbPool,
+        budget: &Budget,
+        currency_code: &str,
+        amount_minor: i64,
+        on: chrono::NaiveDate,
+        given_rate: Option<f64>,
+    ) -> Result<Option<Conversion>, AppError> {
+        if currency_code.eq_ignore_ascii_case(&budget.currency_code) {
+            return Ok(Some(Conversion { base_amount_minor: amount_minor, exchange_rate: None }));
+        }
+        let from = currency_code.to_uppercase();
+        let rate = match given_rate {
+            Some(rate) => Some(rate),
+            None => ExchangeRateRepo::rate_on(pool, &budget.id, &from, &budget.currency_code, on).await?,
+        };
+        Ok(rate.map(|rate| Conversion {
+            base_amount_minor: currency::convert_minor(amount_minor, &from, &budget.currency_code, rate),
+            exchange_rate: Some(rate),
+        }))
+    }
+
+    fn currency_code(raw: &str) -> Result<String, AppError> {
+        let code = raw.trim().to_uppercase();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(AppError::BadRequest(format!("Invalid currency code: {}", raw)));
+        }
+        Ok(code)
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, StreamExt};
+use rust_xlsxwriter::Workbook;
+use crate::manager::models::entry::EntrySplit;
+use crate::manager::models::export::{ExportFormat, ExportQuery, ExportRow, JournalFormat, JournalQuery};
+use crate::manager::models::tag::TagFilter;
+use crate::manager::biz::categories::CategoryService;
+use crate::manager::repository::{budgets::BudgetRepo, categories::CategoryRepo, entries::EntryRepo, entry_splits::EntrySplitRepo, transfers::TransferRepo};
+use crate::utils::{database::database::DbPool, error::error::AppError, currency, journal::{self, JournalTransaction, Posting}};
+
+const PAGE_SIZE: u32 = 500;
+
+const COLUMNS: [&str; 17] = [
+    "id", "entry_date", "kind", "category_id", "category_name", "amount_minor", "amount", "currency_code",
+    "base_amount_minor", "exchange_rate", "description", "counterparty", "member_id", "member_name", "comment_count", "attachment_count", "created_at",
+];
+
+pub type ExportStream = BoxStream<'static, Result<Bytes, AppError>>;
+
+pub struct ExportService;
+
+struct Cursor {
+    pool: DbPool,
+    budget_id: String,
+    query: ExportQuery,
+    format: ExportFormat,
+    offset: u32,
+    done: bool,
+}
+
+impl ExportService {
+    /// CSV and JSON are streamed page by page; XLSX has to be assembled in memory.
+    pub async fn export(pool: &DbPool, budget_id: &str, query: ExportQuery) -> Result<(ExportFormat, ExportStream), AppError> {
+        let format: ExportFormat = query.format.as_deref().unwrap_or("csv").parse().map_err(AppError::BadRequest)?;
+        TagFilter::parse(query.tags.as_deref(), query.tag_match.as_deref())?;
+        BudgetRepo::get(pool, budget_id).await?;
+
+        if format == ExportFormat::Xlsx {
+            let mut rows = Vec::new();
+            loop {
+                let page = Self::fetch_page(pool, budget_id, &query, rows.len() as u32).await?;
+                let last = page.len() < PAGE_SIZE as usize;
+                rows.extend(page);
+                if last {
+                    break;
+                }
+            }
+            let file = Self::render_xlsx(&rows)?;
+            return Ok((format, stream::once(async move { Ok(Bytes::from(file)) }).boxed()));
+        }
+
+        let cursor = Cursor {
+            pool: pool.clone(),
+            budget_id: budget_id.to_string(),
+            query,
+            format,
+            offset: 0,
+            done: false,
+        };
+        let body = stream::unfold(cursor, |mut cursor| async move {
+            if cursor.done {
+                return None;
+            }
+            let chunk = Self::next_chunk(&mut cursor).await;
+            if chunk.is_err() {
+                cursor.done = true;
+            }
+            Some((chunk, cursor))
+        });
+        Ok((format, body.boxed()))
+    }
+
+    /// Double-entry journal for one budget, or for every budget the user belongs
+    /// to when `budget_id` is None. Each budget is an `Assets:Budgets:` account and
+    /// each transfer a single transaction between two of them.
+    pub async fn journal(pool: &DbPool, user_id: &str, budget_id: Option<&str>, query: JournalQuery) -> Result<(JournalFormat, String), AppError> {
+        let format: JournalFormat = query.format.as_deref().unwrap_or("hledger").parse().map_err(AppError::BadRequest)?;
+
+        let budgets: Vec<(String, String)> = match budget_id {
+            Some(id) => {
+                let budget = BudgetRepo::get(pool, id).await?;
+                vec![(budget.id, budget.name)]
+            }
+            None => BudgetRepo::list_with_roles_for_user(pool, user_id, None).await?
+                .into_iter()
+                .map(|b| (b.id, b.name))
+                .collect(),
+        };
+
+        let mut asset_accounts: HashMap<String, String> = HashMap::new();
+        for (id, name) in &budgets {
+            Self::register_budget_account(&mut asset_accounts, id, name);
+        }
+
+        let mut transactions = Vec::new();
+        let mut seen_transfers = HashSet::new();
+        for (budget_id, _) in &budgets {
+            let assets = asset_accounts[budget_id].clone();
+            let entries = EntryRepo::list_for_journal(pool, budget_id, query.from, query.to).await?;
+            // Subcategories become sub-accounts so hledger and beancount roll them up
+            let categories = CategoryRepo::list(pool, budget_id, None).await?;
+            let names: HashMap<&str, &str> = categories.iter().map(|c| (c.id.as_str(), c.name.as_str())).collect();
+            let category_paths: HashMap<String, Vec<&str>> = CategoryService::lineage(&categories)
+                .into_iter()
+                .map(|(id, chain)| (id, chain.iter().rev().map(|id| names[id.as_str()]).collect()))
+                .collect();
+            let entry_ids: Vec<String> = entries.iter().map(|e| e.id.clone()).collect();
+            let mut splits: HashMap<String, Vec<EntrySplit>> = HashMap::new();
+            for split in EntrySplitRepo::list_for_entries(pool, &entry_ids).await? {
+                splits.entry(split.entry_id.clone()).or_default().push(split);
+            }
+
+            for entry in entries {
+                let (root, sign) = if entry.kind == "income" { ("Income", -1) } else { ("Expenses", 1) };
+                // Split entries post one line per category
+                let path = |category_id: &str| category_paths.get(category_id).cloned().unwrap_or_else(|| vec!["Uncategorized"]);
+                let lines: Vec<(Vec<&str>, i64)> = match splits.get(&entry.id) {
+                    Some(lines) => lines.iter().map(|s| (path(&s.category_id), s.amount_minor)).collect(),
+                    None => vec![(path(&entry.category_id), entry.amount_minor)],
+                };
+                let mut postings: Vec<Posting> = lines.into_iter()
+                    .map(|(path, amount)| Posting {
+                        account: journal::account(root, &path),
+                        amount_minor: sign * amount,
+                        currency_code: entry.currency_code.clone(),
+                        total_price: None,
+                    })
+                    .collect();
+                postings.push(Posting { account: assets.clone(), amount_minor: -sign * entry.amount_minor, currency_code: entry.currency_code.clone(), total_price: None });
+                transactions.push(JournalTransaction {
+                    date: entry.entry_date,
+                    payee: entry.counterparty,
+                    narration: entry.description.unwrap_or_default(),
+                    id: entry.id,
+                    postings,
+                });
+            }
+
+            for transfer in TransferRepo::list_for_journal(pool, budget_id, query.from, query.to).await? {
+                if !seen_transfers.insert(transfer.id.clone()) {
+                    continue;
+                }
+                for other in [&transfer.from_budget_id, &transfer.to_budget_id] {
+                    if !asset_accounts.contains_key(other) {
+                        let budget = BudgetRepo::get(pool, other).await?;
+                        Self::register_budget_account(&mut asset_accounts, &budget.id, &budget.name);
+                    }
+                }
+                // Legs in different currencies balance through the price of the sent amount
+                let price = (transfer.to_currency_code != transfer.currency_code)
+                    .then(|| (transfer.to_amount_minor, transfer.to_currency_code.clone()));
+                let mut postings = vec![
+                    Posting { account: asset_accounts[&transfer.to_budget_id].clone(), amount_minor: transfer.to_amount_minor, currency_code: transfer.to_currency_code.clone(), total_price: None },
+                    Posting { account: asset_accounts[&transfer.from_budget_id].clone(), amount_minor: -transfer.amount_minor, currency_code: transfer.currency_code.clone(), total_price: price },
+                ];
+                if transfer.fee_minor > 0 {
+                    postings.push(Posting { account: journal::account("Expenses", &["Transfer fees"]), amount_minor: transfer.fee_minor, currency_code: transfer.currency_code.clone(), total_price: None });
+                    postings.push(Posting { account: asset_accounts[&transfer.from_budget_id].clone(), amount_minor: -transfer.fee_minor, currency_code: transfer.currency_code.clone(), total_price: None });
+                }
+                transactions.push(JournalTransaction {
+                    date: transfer.transfer_date,
+                    payee: None,
+                    narration: transfer.note.clone().unwrap_or_else(|| "Transfer".to_string()),
+                    id: transfer.id.clone(),
+                    postings,
+                });
+            }
+        }
+
+        let title = match budgets.as_slice() {
+            [(_, name)] => format!("Philand export: {}", name),
+            _ => "Philand export".to_string(),
+        };
+        Ok((format, journal::render(format, &title, &mut transactions)))
+    }
+
+    /// Budgets with the same name get a short id suffix so their accounts stay apart.
+    fn register_budget_account(accounts: &mut HashMap<String, String>, id: &str, name: &str) {
+        let mut account = journal::account("Assets", &["Budgets", name]);
+        if accounts.values().any(|a| *a == account) {
+            account = journal::account("Assets", &["Budgets", &format!("{}-{}", name, &id[..id.len().min(8)])]);
+        }
+        accounts.insert(id.to_string(), account);
+    }
+
+    async fn next_chunk(cursor: &mut Cursor) -> Result<Bytes, AppError> {
+        let rows = Self::fetch_page(&cursor.pool, &cursor.budget_id, &cursor.query, cursor.offset).await?;
+        let first = cursor.offset == 0;
+        cursor.offset += rows.len() as u32;
+        cursor.done = rows.len() < PAGE_SIZE as usize;
+
+        let mut out = Vec::new();
+        match cursor.format {
+            ExportFormat::Csv => {
+                let mut writer = ::csv::WriterBuilder::new().has_headers(false).from_writer(&mut out);
+                if first {
+                    writer.write_record(COLUMNS).map_err(|_| AppError::Internal)?;
+                }
+                for row in &rows {
+                    writer.serialize(row).map_err(|_| AppError::Internal)?;
+                }
+                writer.flush().map_err(|_| AppError::Internal)?;
+            }
+            ExportFormat::Json => {
+                // One JSON array spread over the chunks
+                if first {
+                    out.push(b'[');
+                }
+                for (i, row) in rows.iter().enumerate() {
+                    if !(first && i == 0) {
+                        out.push(b',');
+                    }
+                    serde_json::to_writer(&mut out, row).map_err(|_| AppError::Internal)?;
+                }
+                if cursor.done {
+                    out.push(b']');
+                }
+            }
+            ExportFormat::Xlsx => unreachable!("xlsx exports are not streamed"),
+        }
+        Ok(Bytes::from(out))
+    }
+
+    async fn fetch_page(pool: &DbPool, budget_id: &str, query: &ExportQuery, offset: u32) -> Result<Vec<ExportRow>, AppError> {
+        let mut rows = EntryRepo::export_page(pool, budget_id, query, false, PAGE_SIZE, offset).await?;
+        for row in &mut rows {
+            row.amount = currency::format_minor(row.amount_minor, currency::minor_units(&row.currency_code));
+        }
+        Ok(rows)
+    }
+
+    fn render_xlsx(rows: &[ExportRow]) -> Result<Vec<u8>, AppError> {
+        let xlsx_err = |e: rust_xlsxwriter::XlsxError| {
+            tracing::error!("Failed to build xlsx export: {:?}", e);
+            AppError::Internal
+        };
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Entries").map_err(xlsx_err)?;
+
+        for (col, name) in COLUMNS.iter().enumerate() {
+            sheet.write_string(0, col as u16, *name).map_err(xlsx_err)?;
+        }
+        for (i, row) in rows.iter().enumerate() {
+            let r = i as u32 + 1;
+            sheet.write_string(r, 0, &row.id).map_err(xlsx_err)?;
+            sheet.write_string(r, 1, row.entry_date.to_string()).map_err(xlsx_err)?;
+            sheet.write_string(r, 2, &row.kind).map_err(xlsx_err)?;
+            sheet.write_string(r, 3, &row.category_id).map_err(xlsx_err)?;
+            sheet.write_string(r, 4, row.category_name.as_deref().unwrap_or("")).map_err(xlsx_err)?;
+            sheet.write_number(r, 5, row.amount_minor as f64).map_err(xlsx_err)?;
+            sheet.write_string(r, 6, &row.amount).map_err(xlsx_err)?;
+            sheet.write_string(r, 7, &row.currency_code).map_err(xlsx_err)?;
+            sheet.write_number(r, 8, row.base_amount_minor as f64).map_err(xlsx_err)?;
+            if let Some(rate) = row.exchange_rate {
+                sheet.write_number(r, 9, rate).map_err(xlsx_err)?;
+            }
+            sheet.write_string(r, 10, row.description.as_deref().unwrap_or("")).map_err(xlsx_err)?;
+            sheet.write_string(r, 11, row.counterparty.as_deref().unwrap_or("")).map_err(xlsx_err)?;
+            sheet.write_string(r, 12, &row.created_by).map_err(xlsx_err)?;
+            sheet.write_string(r, 13, &row.member_name).map_err(xlsx_err)?;
+            sheet.write_number(r, 14, row.comment_count.unwrap_or(0)).map_err(xlsx_err)?;
+            sheet.write_number(r, 15, row.attachment_count.unwrap_or(0)).map_err(xlsx_err)?;
+            sheet.write_string(r, 16, row.created_at.format("%Y-%m-%d %H:%M:%S").to_string()).map_err(xlsx_err)?;
+        }
+
+        workbook.save_to_buffer().map_err(xlsx_err)
+    }
+}
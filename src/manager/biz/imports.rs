@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use base64::{engine::general_purpose, Engine as _};
+use crate::manager::models::budget::Budget;
+use crate::manager::models::category::Category;
+use crate::manager::models::entry::CreateEntryReq;
+use crate::manager::models::payee::Payee;
+use crate::manager::models::rule::{RuleSet, RuleSubject};
+use crate::manager::models::import::{CsvImportReq, ImportReport, ImportRowError, ImportedRow, ParsedTransaction, StatementImportReq};
+use crate::manager::repository::{budgets::BudgetRepo, categories::CategoryRepo, entries::EntryRepo, import_keys::ImportKeyRepo};
+use crate::manager::biz::{category_limits::CategoryLimitService, payees::PayeeService, rules::RuleService};
+use crate::utils::{database::database::DbPool, error::error::AppError, currency, importers};
+
+pub struct ImportService;
+
+impl ImportService {
+    pub async fn import_csv(pool: &DbPool, budget_id: &str, user_id: &str, req: CsvImportReq) -> Result<ImportReport, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let data = Self::decode_file(&req.file_data)?;
+        let parsed = importers::csv_import::parse(&data, &req.mapping, currency::minor_units(&budget.currency_code))
+            .map_err(AppError::BadRequest)?;
+        Self::process(pool, &budget, user_id, parsed, req.mapping.default_category_id.as_deref(), req.dry_run.unwrap_or(false)).await
+    }
+
+    /// OFX 1.x (SGML), OFX 2.x (XML) and QFX statements
+    pub async fn import_ofx(pool: &DbPool, budget_id: &str, user_id: &str, req: StatementImportReq) -> Result<ImportReport, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let data = Self::decode_file(&req.file_data)?;
+        let parsed = importers::ofx::parse(&data, currency::minor_units(&budget.currency_code))
+            .map_err(AppError::BadRequest)?;
+        Self::process(pool, &budget, user_id, parsed, req.default_category_id.as_deref(), req.dry_run.unwrap_or(false)).await
+    }
+
+    /// ISO 20022 camt.053 XML statements
+    pub async fn import_camt053(pool: &DbPool, budget_id: &str, user_id: &str, req: StatementImportReq) -> Result<ImportReport, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let data = Self::decode_file(&req.file_data)?;
+        let parsed = importers::camt053::parse(&data, &budget.currency_code)
+            .map_err(AppError::BadRequest)?;
+        Self::process(pool, &budget, user_id, parsed, req.default_category_id.as_deref(), req.dry_run.unwrap_or(false)).await
+    }
+
+    pub async fn import_qif(pool: &DbPool, budget_id: &str, user_id: &str, req: StatementImportReq) -> Result<ImportReport, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let data = Self::decode_file(&req.file_data)?;
+        let date_format = req.date_format.as_deref().unwrap_or("%m/%d/%Y");
+        let parsed = importers::qif::parse(&data, date_format, req.decimal_separator.unwrap_or('.'), currency::minor_units(&budget.currency_code))
+            .map_err(AppError::BadRequest)?;
+        Self::process(pool, &budget, user_id, parsed, req.default_category_id.as_deref(), req.dry_run.unwrap_or(false)).await
+    }
+
+    /// Validate parsed rows and, unless this is a dry run or any row failed,
+    /// insert them all in a single transaction. Rows whose dedupe key was
+    /// imported before (or repeats within the file) are reported as duplicates.
+    async fn process(
+        pool: &DbPool,
+        budget: &Budget,
+        user_id: &str,
+        parsed: Vec<Result<ParsedTransaction, ImportRowError>>,
+        default_category_id: Option<&str>,
+        dry_run: bool,
+    ) -> Result<ImportReport, AppError> {
+        // Imported rows are new entries, so archived categories are not offered
+        let categories: Vec<Category> = CategoryRepo::list(pool, &budget.id, None).await?
+            .into_iter()
+            .filter(|c| !c.is_hidden)
+            .collect();
+        let rules = RuleService::rule_set(pool, &budget.id).await?;
+        let payees = PayeeService::directory(pool, &budget.id).await?;
+        let total_rows = parsed.len();
+        let keys: Vec<String> = parsed.iter()
+            .filter_map(|item| item.as_ref().ok().and_then(|t| t.dedupe_key.as_deref()).map(Self::store_key))
+            .collect();
+        let mut seen = ImportKeyRepo::existing(pool, &budget.id, &keys).await?;
+        let mut errors = Vec::new();
+        let mut duplicates = Vec::new();
+        let mut valid = Vec::new();
+
+        for item in parsed {
+            let key = item.as_ref().ok().and_then(|t| t.dedupe_key.as_deref()).map(Self::store_key);
+            if let (Ok(t), Some(k)) = (&item, &key) {
+                if !seen.insert(k.clone()) {
+                    duplicates.push(t.row);
+                    continue;
+                }
+            }
+            match item.and_then(|t| Self::to_entry_req(&t, &categories, &rules, &payees, default_category_id, user_id).map(|(req, rule_ids)| (t.row, key, req, rule_ids))) {
+                Ok(v) => valid.push(v),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        let rows: Vec<ImportedRow> = valid.iter().map(|(row, _, req, rule_ids)| ImportedRow {
+            row: *row,
+            entry_date: req.entry_date,
+            kind: req.kind.clone(),
+            amount_minor: req.amount_minor,
+            category_id: req.category_id.clone().unwrap_or_default(),
+            description: req.description.clone(),
+            counterparty: req.counterparty.clone(),
+            tag_ids: req.tag_ids.clone().unwrap_or_default(),
+            rule_ids: rule_ids.clone(),
+        }).collect();
+
+        let mut report = ImportReport {
+            dry_run,
+            committed: false,
+            total_rows,
+            valid_rows: valid.len(),
+            imported: 0,
+            rows,
+            duplicates,
+            errors,
+        };
+        if dry_run || !report.errors.is_empty() || valid.is_empty() {
+            return Ok(report);
+        }
+
+        let mut tx = pool.begin().await?;
+        let mut imported = 0;
+        for (row, key, req, _) in &valid {
+            let id = uuid::Uuid::new_v4().to_string();
+            // A concurrent import may have claimed the key since the pre-check
+            if let Some(k) = key {
+                if !ImportKeyRepo::claim(&mut tx, &budget.id, k, &id).await? {
+                    report.duplicates.push(*row);
+                    continue;
+                }
+            }
+            EntryRepo::insert(&mut tx, &id, &budget.id, req, &budget.currency_code).await?;
+            imported += 1;
+        }
+        tx.commit().await?;
+
+        if let Some(earliest) = valid.iter().map(|(_, _, req, _)| req.entry_date).min() {
+            CategoryLimitService::invalidate_from(pool, &budget.id, earliest).await?;
+        }
+
+        report.committed = true;
+        report.imported = imported;
+        Ok(report)
+    }
+
+    /// Build the entry for a parsed row, along with the ids of the rules that matched.
+    /// A category named in the file wins over the rules, then the payee's default
+    /// category and finally the import's default. Only payees already in the
+    /// directory are linked; imports do not create new ones.
+    fn to_entry_req(
+        t: &ParsedTransaction,
+        categories: &[Category],
+        rules: &RuleSet,
+        payees: &HashMap<String, Payee>,
+        default_category_id: Option<&str>,
+        user_id: &str,
+    ) -> Result<(CreateEntryReq, Vec<String>), ImportRowError> {
+        if t.amount_minor <= 0 {
+            return Err(ImportRowError::new(t.row, Some("amount"), "Amount must be positive"));
+        }
+
+        let outcome = rules.apply(&RuleSubject {
+            kind: &t.kind,
+            amount_minor: t.amount_minor,
+            description: t.description.as_deref(),
+            counterparty: t.counterparty.as_deref(),
+        });
+        let counterparty = outcome.counterparty.or_else(|| t.counterparty.clone());
+        let payee = counterparty.as_deref().and_then(|c| payees.get(&Payee::normalize(c)));
+        let ruled = outcome.category_id.as_deref()
+            .or(payee.and_then(|p| p.default_category_id.as_deref()))
+            .and_then(|id| categories.iter().find(|c| c.id == id && c.kind == t.kind));
+        let category = match (&t.category_name, ruled) {
+            (Some(name), _) => categories.iter()
+                .find(|c| c.kind == t.kind && c.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| ImportRowError::new(t.row, Some("category"), format!("No {} category named '{}'", t.kind, name)))?,
+            (None, Some(category)) => category,
+            (None, None) => {
+                let id = default_category_id
+                    .ok_or_else(|| ImportRowError::new(t.row, Some("category"), "Category is required"))?;
+                categories.iter()
+                    .find(|c| c.id == id)
+                    .ok_or_else(|| ImportRowError::new(t.row, Some("category"), "Default category not found in this budget"))?
+            }
+        };
+        if category.kind != t.kind {
+            return Err(ImportRowError::new(t.row, Some("category"), format!("Category '{}' is for {} entries", category.name, category.kind)));
+        }
+
+        let req = CreateEntryReq {
+            category_id: Some(category.id.clone()),
+            kind: t.kind.clone(),
+            amount_minor: t.amount_minor,
+            currency_code: None,
+            exchange_rate: None,
+            base_amount_minor: t.amount_minor, // imported rows are in the budget currency
+            entry_date: t.entry_date,
+            description: t.description.clone(),
+            counterparty: payee.map(|p| p.name.clone()).or(counterparty),
+            splits: None,
+            tag_ids: (!outcome.tag_ids.is_empty()).then_some(outcome.tag_ids),
+            payee_id: payee.map(|p| p.id.clone()),
+            created_by: user_id.to_string(),
+        };
+        Ok((req, outcome.rule_ids))
+    }
+
+    /// Keys longer than the column are stored hashed
+    fn store_key(key: &str) -> String {
+        if key.len() <= 255 {
+            key.to_string()
+        } else {
+            let source = key.split(':').next().unwrap_or("key");
+            importers::hashed_key(source, &[key])
+        }
+    }
+
+    pub(crate) fn decode_file(file_data: &str) -> Result<Vec<u8>, AppError> {
+        // Remove data URL prefix if present
+        let base64_data = file_data.split("base64,").nth(1).unwrap_or(file_data);
+        general_purpose::STANDARD
+            .decode(base64_data.trim())
+            .map_err(|_| AppError::BadRequest("Invalid base64 file data".to_string()))
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::{HashMap, VecDeque};
+use crate::manager::biz::{categories::CategoryService, category_limits::CategoryLimitService};
+use crate::manager::models::budget::BudgetType;
+use crate::manager::models::investment::{CostMethod, CreateHoldingReq, CreateInvestmentTransactionReq, Holding, HoldingPosition, InvestmentTransaction,
+                                         PortfolioQuery, PortfolioSummary, Position, PriceSnapshot, TradeKind, UpdateHoldingReq};
+use crate::manager::repository::{budgets::BudgetRepo, investments::{CashEntry, InvestmentRepo}, prices::PriceRepo};
+use crate::utils::{currency, database::database::DbPool, error::error::{AppError, FieldError}};
+
+/// Quantities closer than this are treated as equal.
+const QUANTITY_EPSILON: f64 = 1e-9;
+
+pub struct InvestmentService;
+
+impl InvestmentService {
+    pub async fn list_holdings(pool: &DbPool, budget_id: &str) -> Result<Vec<Holding>, AppError> {
+        InvestmentRepo::list_holdings(pool, budget_id).await
+    }
+
+    pub async fn get_holding(pool: &DbPool, budget_id: &str, holding_id: &str) -> Result<Holding, AppError> {
+        InvestmentRepo::get_holding(pool, budget_id, holding_id).await
+    }
+
+    pub async fn create_holding(pool: &DbPool, budget_id: &str, req: CreateHoldingReq) -> Result<Holding, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        if budget.budget_type != BudgetType::Invest {
+            return Err(AppError::BadRequest("Holdings can only be added to invest budgets".to_string()));
+        }
+        let symbol = Self::available_symbol(pool, budget_id, &req.symbol, None).await?;
+        let name = req.name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        InvestmentRepo::create_holding(pool, budget_id, &symbol, name.as_deref(), req.cost_method.unwrap_or(CostMethod::Fifo)).await
+    }
+
+    /// Changing the cost method recomputes the whole history with it.
+    pub async fn update_holding(pool: &DbPool, budget_id: &str, holding_id: &str, req: UpdateHoldingReq) -> Result<Holding, AppError> {
+        let mut holding = InvestmentRepo::get_holding(pool, budget_id, holding_id).await?;
+        if let Some(symbol) = req.symbol {
+            holding.symbol = Self::available_symbol(pool, budget_id, &symbol, Some(holding_id)).await?;
+        }
+        if let Some(name) = req.name {
+            holding.name = Some(name.trim().to_string()).filter(|n| !n.is_empty());
+        }
+        if let Some(cost_method) = req.cost_method {
+            holding.cost_method = cost_method;
+        }
+        InvestmentRepo::update_holding(pool, &holding).await
+    }
+
+    pub async fn delete_holding(pool: &DbPool, budget_id: &str, holding_id: &str, user_id: &str) -> Result<(), AppError> {
+        let transactions = InvestmentRepo::list_transactions(pool, budget_id, holding_id).await?;
+        InvestmentRepo::delete_holding(pool, budget_id, holding_id, user_id).await?;
+        if let Some(first) = transactions.iter().map(|t| t.trade_date).min() {
+            CategoryLimitService::invalidate_from(pool, budget_id, first).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn list_transactions(pool: &DbPool, budget_id: &str, holding_id: &str) -> Result<Vec<InvestmentTransaction>, AppError> {
+        InvestmentRepo::get_holding(pool, budget_id, holding_id).await?;
+        InvestmentRepo::list_transactions(pool, budget_id, holding_id).await
+    }
+
+    /// Record a buy, sell or dividend together with the entry for its cash:
+    /// an expense for a buy, income for a sell or dividend.
+    pub async fn create_transaction(pool: &DbPool, budget_id: &str, holding_id: &str, user_id: &str, req: CreateInvestmentTransactionReq) -> Result<InvestmentTransaction, AppError> {
+        let holding = InvestmentRepo::get_holding(pool, budget_id, holding_id).await?;
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+
+        let mut errors = Vec::new();
+        let fee_minor = req.fee_minor.unwrap_or(0);
+        if fee_minor < 0 {
+            errors.push(FieldError::new("fee_minor", "Fee must not be negative"));
+        }
+        let (quantity, price_minor, gross_minor) = match req.kind {
+            TradeKind::Buy | TradeKind::Sell => {
+                let quantity = req.quantity.unwrap_or(0.0);
+                let price_minor = req.price_minor.unwrap_or(0);
+                if !quantity.is_finite() || quantity <= 0.0 {
+                    errors.push(FieldError::new("quantity", "Quantity must be positive"));
+                }
+                if price_minor <= 0 {
+                    errors.push(FieldError::new("price_minor", "Price must be positive"));
+                }
+                if req.amount_minor.is_some() {
+                    errors.push(FieldError::new("amount_minor", "Amount is only given for dividends; buys and sells use quantity and price"));
+                }
+                (quantity, price_minor, (quantity * price_minor as f64).round() as i64)
+            }
+            TradeKind::Dividend => {
+                let amount_minor = req.amount_minor.unwrap_or(0);
+                if amount_minor <= 0 {
+                    errors.push(FieldError::new("amount_minor", "Dividend amount must be positive"));
+                }
+                if req.quantity.is_some() || req.price_minor.is_some() {
+                    errors.push(FieldError::new("quantity", "Dividends have no quantity or price"));
+                }
+                (0.0, 0, amount_minor)
+            }
+        };
+        let amount_minor = match req.kind {
+            TradeKind::Buy => gross_minor + fee_minor,
+            TradeKind::Sell | TradeKind::Dividend => gross_minor - fee_minor,
+        };
+        if errors.is_empty() && amount_minor <= 0 {
+            errors.push(FieldError::new("fee_minor", "Fee must be less than the proceeds"));
+        }
+
+        let entry_kind = if req.kind == TradeKind::Buy { "expense" } else { "income" };
+        match CategoryService::ensure_assignable(pool, budget_id, &req.category_id, &[]).await {
+            Ok(category) if category.kind != entry_kind => {
+                errors.push(FieldError::new("category_id", format!("A {} is recorded in an {} category", req.kind, entry_kind)));
+            }
+            Ok(_) => {}
+            Err(AppError::BadRequest(message)) => errors.push(FieldError::new("category_id", message)),
+            Err(e) => return Err(e),
+        }
+        if !errors.is_empty() {
+            return Err(AppError::Validation(errors));
+        }
+
+        let txn = InvestmentTransaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            budget_id: budget_id.to_string(),
+            holding_id: holding.id.clone(),
+            kind: req.kind,
+            trade_date: req.trade_date,
+            quantity,
+            price_minor,
+            fee_minor,
+            amount_minor,
+            entry_id: Some(uuid::Uuid::new_v4().to_string()),
+            note: req.note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+            created_by: user_id.to_string(),
+            created_at: chrono::Utc::now().naive_utc(),
+        };
+        // The sale must be covered by the units held on its date
+        let mut history = InvestmentRepo::list_transactions(pool, budget_id, holding_id).await?;
+        history.push(txn.clone());
+        history.sort_by(|a, b| a.trade_date.cmp(&b.trade_date).then(a.created_at.cmp(&b.created_at)));
+        Self::position(holding.cost_method, &history)
+            .map_err(|message| AppError::Validation(vec![FieldError::new("quantity", message)]))?;
+
+        let minor_units = currency::minor_units(&budget.currency_code);
+        let description = match txn.kind {
+            TradeKind::Buy => format!("Buy {} {} @ {}", txn.quantity, holding.symbol, currency::format_minor(txn.price_minor, minor_units)),
+            TradeKind::Sell => format!("Sell {} {} @ {}", txn.quantity, holding.symbol, currency::format_minor(txn.price_minor, minor_units)),
+            TradeKind::Dividend => format!("Dividend {}", holding.symbol),
+        };
+        let created = InvestmentRepo::create_transaction(pool, &txn, CashEntry {
+            category_id: &req.category_id,
+            kind: entry_kind,
+            currency_code: &budget.currency_code,
+            description: &description,
+        }).await?;
+        CategoryLimitService::invalidate_from(pool, budget_id, created.trade_date).await?;
+        Ok(created)
+    }
+
+    /// Delete a transaction and its cash entry, unless a later sale depends on it.
+    pub async fn delete_transaction(pool: &DbPool, budget_id: &str, holding_id: &str, transaction_id: &str, user_id: &str) -> Result<(), AppError> {
+        let holding = InvestmentRepo::get_holding(pool, budget_id, holding_id).await?;
+        let txn = InvestmentRepo::get_transaction(pool, budget_id, transaction_id).await?;
+        if txn.holding_id != holding.id {
+            return Err(AppError::NotFound);
+        }
+        let remaining: Vec<InvestmentTransaction> = InvestmentRepo::list_transactions(pool, budget_id, holding_id).await?
+            .into_iter()
+            .filter(|t| t.id != txn.id)
+            .collect();
+        Self::position(holding.cost_method, &remaining)
+            .map_err(|message| AppError::BadRequest(format!("Transaction is needed by a later sale: {}", message)))?;
+        InvestmentRepo::delete_transaction(pool, &txn, user_id).await?;
+        CategoryLimitService::invalidate_from(pool, budget_id, txn.trade_date).await
+    }
+
+    /// Positions of every holding on `as_of`, valued at the latest price known then.
+    pub async fn portfolio(pool: &DbPool, budget_id: &str, query: PortfolioQuery) -> Result<PortfolioSummary, AppError> {
+        let as_of = query.as_of.unwrap_or_else(|| chrono::Utc::now().date_naive());
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let holdings = InvestmentRepo::list_holdings(pool, budget_id).await?;
+        let mut transactions: HashMap<String, Vec<InvestmentTransaction>> = HashMap::new();
+        for txn in InvestmentRepo::list_budget_transactions(pool, budget_id, as_of).await? {
+            transactions.entry(txn.holding_id.clone()).or_default().push(txn);
+        }
+        let prices: HashMap<String, PriceSnapshot> = PriceRepo::latest(pool, budget_id, as_of).await?
+            .into_iter()
+            .map(|p| (p.symbol.clone(), p))
+            .collect();
+
+        let mut summary = PortfolioSummary {
+            currency_code: budget.currency_code,
+            as_of,
+            cost_basis_minor: 0,
+            market_value_minor: 0,
+            unrealized_pnl_minor: 0,
+            realized_pnl_minor: 0,
+            dividends_minor: 0,
+            fees_minor: 0,
+            unpriced_symbols: Vec::new(),
+            holdings: Vec::with_capacity(holdings.len()),
+        };
+        for holding in holdings {
+            let position = Self::position(holding.cost_method, transactions.get(&holding.id).map(Vec::as_slice).unwrap_or_default())
+                .map_err(|message| AppError::BadRequest(format!("{}: {}", holding.symbol, message)))?;
+            let held = position.quantity > QUANTITY_EPSILON;
+            let price = prices.get(&holding.symbol);
+            let market_value_minor = price.map(|p| (position.quantity * p.price_minor as f64).round() as i64);
+            let unrealized_pnl_minor = market_value_minor.map(|value| value - position.cost_basis_minor);
+            if held && price.is_none() {
+                summary.unpriced_symbols.push(holding.symbol.clone());
+            }
+
+            summary.cost_basis_minor += position.cost_basis_minor;
+            summary.market_value_minor += market_value_minor.unwrap_or(0);
+            summary.unrealized_pnl_minor += unrealized_pnl_minor.unwrap_or(0);
+            summary.realized_pnl_minor += position.realized_pnl_minor;
+            summary.dividends_minor += position.dividends_minor;
+            summary.fees_minor += position.fees_minor;
+            summary.holdings.push(HoldingPosition {
+                holding_id: holding.id,
+                symbol: holding.symbol,
+                name: holding.name,
+                cost_method: holding.cost_method,
+                quantity: position.quantity,
+                cost_basis_minor: position.cost_basis_minor,
+                average_cost_minor: held.then(|| (position.cost_basis_minor as f64 / position.quantity).round() as i64),
+                price_minor: price.map(|p| p.price_minor),
+                price_date: price.map(|p| p.price_date),
+                market_value_minor,
+                unrealized_pnl_minor,
+                realized_pnl_minor: position.realized_pnl_minor,
+                dividends_minor: position.dividends_minor,
+                fees_minor: position.fees_minor,
+            });
+        }
+        Ok(summary)
+    }
+
+    /// Replay transactions in order. Buys add a lot costing what was paid,
+    /// fees included; a sale realizes its net proceeds less the cost of the
+    /// units it removes. Fails on a sale of more units than are held.
+    pub(crate) fn position(method: CostMethod, transactions: &[InvestmentTransaction]) -> Result<Position, String> {
+        let mut lots: VecDeque<(f64, f64)> = VecDeque::new(); // (quantity, cost)
+        let mut realized = 0.0;
+        let mut position = Position::default();
+        for txn in transactions {
+            position.fees_minor += txn.fee_minor;
+            match txn.kind {
+                TradeKind::Buy => lots.push_back((txn.quantity, txn.amount_minor as f64)),
+                TradeKind::Dividend => position.dividends_minor += txn.amount_minor,
+                TradeKind::Sell => {
+                    let held: f64 = lots.iter().map(|(quantity, _)| quantity).sum();
+                    if txn.quantity > held + QUANTITY_EPSILON {
+                        return Err(format!("Sale of {} on {} exceeds the {} units held", txn.quantity, txn.trade_date, held));
+                    }
+                    let cost = match method {
+                        CostMethod::Fifo => {
+                            let mut left = txn.quantity;
+                            let mut cost = 0.0;
+                            while left > QUANTITY_EPSILON {
+                                let Some(lot) = lots.front_mut() else { break };
+                                if lot.0 <= left + QUANTITY_EPSILON {
+                                    cost += lot.1;
+                                    left -= lot.0;
+                                    lots.pop_front();
+                                } else {
+                                    let part = lot.1 * left / lot.0;
+                                    cost += part;
+                                    lot.0 -= left;
+                                    lot.1 -= part;
+                                    left = 0.0;
+                                }
+                            }
+                            cost
+                        }
+                        CostMethod::Average => {
+                            // Collapse into one lot at the average cost
+                            let total: f64 = lots.iter().map(|(_, cost)| cost).sum();
+                            let cost = total * (txn.quantity / held).min(1.0);
+                            lots.clear();
+                            if held - txn.quantity > QUANTITY_EPSILON {
+                                lots.push_back((held - txn.quantity, total - cost));
+                            }
+                            cost
+                        }
+                    };
+                    realized += txn.amount_minor as f64 - cost;
+                }
+            }
+        }
+        let quantity: f64 = lots.iter().map(|(quantity, _)| quantity).sum();
+        position.quantity = if quantity > QUANTITY_EPSILON { quantity } else { 0.0 };
+        position.cost_basis_minor = lots.iter().map(|(_, cost)| cost).sum::<f64>().round() as i64;
+        position.realized_pnl_minor = realized.round() as i64;
+        Ok(position)
+    }
+
+    async fn available_symbol(pool: &DbPool, budget_id: &str, raw: &str, holding_id: Option<&str>) -> Result<String, AppError> {
+        let symbol = Holding::normalize_symbol(raw)
+            .ok_or_else(|| AppError::Validation(vec![FieldError::new("symbol", "Symbol must be 1 to 32 characters without spaces")]))?;
+        if let Some(existing) = InvestmentRepo::find_holding_by_symbol(pool, budget_id, &symbol).await? {
+            if Some(existing.id.as_str()) != holding_id {
+                return Err(AppError::BadRequest(format!("Holding '{}' already exists", symbol)));
+            }
+        }
+        Ok(symbol)
+    }
+}
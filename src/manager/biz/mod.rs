@@ -1,6 +1,7 @@
 pub mod users;
 pub mod budgets;
 pub mod categories;
+pub mod debts;
 pub mod entries;
 pub mod authz;
 pub mod members;
@@ -9,3 +10,17 @@ pub mod profile;
 pub mod google_auth;
 pub mod comments;
 pub mod transfers;
+pub mod category_limits;
+pub mod recurring;
+pub mod imports;
+pub mod exports;
+pub mod backups;
+pub mod tags;
+pub mod category_templates;
+pub mod rules;
+pub mod savings_goals;
+pub mod payees;
+pub mod prices;
+pub mod exchange_rates;
+pub mod investments;
+pub mod scheduled_transfers;
\ No newline at end of file
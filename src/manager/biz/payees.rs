@@ -0,0 +1,166 @@
+use std::collections::{HashMap, HashSet};
+use crate::manager::models::payee::{AddAliasReq, CreatePayeeReq, MergePayeeReport, MergePayeeReq, Payee, PayeeSearch, PayeeSuggestion, PayeeSummaryRow, UpdatePayeeReq};
+use crate::manager::repository::payees::PayeeRepo;
+use crate::manager::biz::categories::CategoryService;
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct PayeeService;
+
+impl PayeeService {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<Payee>, AppError> {
+        PayeeRepo::list(pool, budget_id).await
+    }
+
+    pub async fn get(pool: &DbPool, budget_id: &str, payee_id: &str) -> Result<Payee, AppError> {
+        PayeeRepo::get_by_id(pool, budget_id, payee_id).await
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, req: CreatePayeeReq) -> Result<Payee, AppError> {
+        let name = Self::validate_text(&req.name, "Payee name")?;
+        if let Some(category_id) = req.default_category_id.as_deref() {
+            CategoryService::ensure_assignable(pool, budget_id, category_id, &[]).await?;
+        }
+        let mut aliases = Vec::new();
+        let mut seen = HashSet::new();
+        for text in std::iter::once(&name).chain(req.aliases.iter().flatten()) {
+            let alias = Self::validate_text(text, "Alias")?;
+            let normalized = Payee::normalize(&alias);
+            if !seen.insert(normalized.clone()) {
+                continue;
+            }
+            Self::ensure_alias_free(pool, budget_id, &alias, &normalized).await?;
+            aliases.push((alias, normalized));
+        }
+        PayeeRepo::create(pool, budget_id, &name, req.default_category_id.as_deref(), &aliases).await
+    }
+
+    pub async fn update(pool: &DbPool, budget_id: &str, payee_id: &str, req: UpdatePayeeReq) -> Result<Payee, AppError> {
+        let mut payee = PayeeRepo::get_by_id(pool, budget_id, payee_id).await?;
+        let mut new_alias = None;
+        if let Some(name) = req.name {
+            let name = Self::validate_text(&name, "Payee name")?;
+            let normalized = Payee::normalize(&name);
+            match PayeeRepo::find_by_alias(pool, budget_id, &normalized).await? {
+                Some(owner) if owner.id != payee.id => {
+                    return Err(AppError::BadRequest(format!("'{}' is already an alias of payee '{}'", name, owner.name)));
+                }
+                Some(_) => {}
+                None => new_alias = Some((name.clone(), normalized)),
+            }
+            payee.name = name;
+        }
+        match req.default_category_id.as_deref() {
+            Some("") => payee.default_category_id = None,
+            Some(category_id) => {
+                CategoryService::ensure_assignable(pool, budget_id, category_id, &[]).await?;
+                payee.default_category_id = Some(category_id.to_string());
+            }
+            None => {}
+        }
+        PayeeRepo::update(pool, &payee, new_alias).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, payee_id: &str) -> Result<(), AppError> {
+        PayeeRepo::delete(pool, budget_id, payee_id).await
+    }
+
+    pub async fn add_alias(pool: &DbPool, budget_id: &str, payee_id: &str, req: AddAliasReq) -> Result<Payee, AppError> {
+        PayeeRepo::get_by_id(pool, budget_id, payee_id).await?;
+        let alias = Self::validate_text(&req.alias, "Alias")?;
+        let normalized = Payee::normalize(&alias);
+        Self::ensure_alias_free(pool, budget_id, &alias, &normalized).await?;
+        PayeeRepo::add_alias(pool, budget_id, payee_id, &alias, &normalized).await?;
+        PayeeRepo::get_by_id(pool, budget_id, payee_id).await
+    }
+
+    pub async fn delete_alias(pool: &DbPool, budget_id: &str, payee_id: &str, alias_id: &str) -> Result<Payee, AppError> {
+        let payee = PayeeRepo::get_by_id(pool, budget_id, payee_id).await?;
+        let alias = payee.aliases.iter().find(|a| a.id == alias_id).ok_or(AppError::NotFound)?;
+        if Payee::normalize(&alias.alias) == Payee::normalize(&payee.name) {
+            return Err(AppError::BadRequest("The payee's own name cannot be removed from its aliases".to_string()));
+        }
+        PayeeRepo::delete_alias(pool, budget_id, payee_id, alias_id).await?;
+        PayeeRepo::get_by_id(pool, budget_id, payee_id).await
+    }
+
+    pub async fn merge(pool: &DbPool, budget_id: &str, payee_id: &str, req: MergePayeeReq) -> Result<MergePayeeReport, AppError> {
+        if req.target_payee_id == payee_id {
+            return Err(AppError::BadRequest("Cannot merge a payee into itself".to_string()));
+        }
+        PayeeRepo::get_by_id(pool, budget_id, payee_id).await?;
+        let target = PayeeRepo::get_by_id(pool, budget_id, &req.target_payee_id).await
+            .map_err(|e| match e {
+                AppError::NotFound => AppError::BadRequest("Target payee not found in this budget".to_string()),
+                e => e,
+            })?;
+        PayeeRepo::merge(pool, budget_id, payee_id, &target).await
+    }
+
+    pub async fn autocomplete(pool: &DbPool, budget_id: &str, search: PayeeSearch) -> Result<Vec<PayeeSuggestion>, AppError> {
+        let limit = search.limit.unwrap_or(10).clamp(1, 50);
+        let normalized = Payee::normalize(search.q.as_deref().unwrap_or_default());
+        PayeeRepo::autocomplete(pool, budget_id, &normalized, limit).await
+    }
+
+    pub async fn summary(pool: &DbPool, budget_id: &str, from: chrono::NaiveDate, to: chrono::NaiveDate) -> Result<Vec<PayeeSummaryRow>, AppError> {
+        if from > to {
+            return Err(AppError::BadRequest("from must not be after to".to_string()));
+        }
+        PayeeRepo::summary(pool, budget_id, from, to).await
+    }
+
+    /// Payee a free-text counterparty refers to, if it is in the directory.
+    pub async fn find(pool: &DbPool, budget_id: &str, counterparty: Option<&str>) -> Result<Option<Payee>, AppError> {
+        let normalized = Payee::normalize(counterparty.unwrap_or_default());
+        if normalized.is_empty() {
+            return Ok(None);
+        }
+        PayeeRepo::find_by_alias(pool, budget_id, &normalized).await
+    }
+
+    /// Like `find`, but a counterparty not seen before becomes a new payee.
+    pub async fn resolve(pool: &DbPool, budget_id: &str, counterparty: Option<&str>) -> Result<Option<Payee>, AppError> {
+        if let Some(payee) = Self::find(pool, budget_id, counterparty).await? {
+            return Ok(Some(payee));
+        }
+        let Some(name) = counterparty.map(|c| c.split_whitespace().collect::<Vec<_>>().join(" ")).filter(|c| !c.is_empty()) else {
+            return Ok(None);
+        };
+        let name: String = name.chars().take(255).collect();
+        let normalized = Payee::normalize(&name);
+        match PayeeRepo::create(pool, budget_id, &name, None, &[(name.clone(), normalized.clone())]).await {
+            Ok(payee) => Ok(Some(payee)),
+            // Another request created it in the meantime
+            Err(e) => PayeeRepo::find_by_alias(pool, budget_id, &normalized).await?.map(Some).ok_or(e),
+        }
+    }
+
+    /// Every alias of the budget keyed by normalized form, for bulk lookups.
+    pub async fn directory(pool: &DbPool, budget_id: &str) -> Result<HashMap<String, Payee>, AppError> {
+        let mut directory = HashMap::new();
+        for payee in PayeeRepo::list(pool, budget_id).await? {
+            for alias in &payee.aliases {
+                directory.insert(Payee::normalize(&alias.alias), payee.clone());
+            }
+        }
+        Ok(directory)
+    }
+
+    async fn ensure_alias_free(pool: &DbPool, budget_id: &str, alias: &str, normalized: &str) -> Result<(), AppError> {
+        match PayeeRepo::find_by_alias(pool, budget_id, normalized).await? {
+            Some(owner) => Err(AppError::BadRequest(format!("'{}' is already an alias of payee '{}'", alias, owner.name))),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_text(text: &str, what: &str) -> Result<String, AppError> {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            return Err(AppError::BadRequest(format!("{} is required", what)));
+        }
+        if text.chars().count() > 255 {
+            return Err(AppError::BadRequest(format!("{} must be at most 255 characters", what)));
+        }
+        Ok(text)
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::BTreeSet;
+use crate::manager::models::investment::{Holding, ImportPricesReport, ImportPricesReq, PriceFilter, PriceRow, PriceSnapshot, SavePriceReq};
+use crate::manager::repository::{budgets::BudgetRepo, prices::PriceRepo};
+use crate::manager::biz::imports::ImportService;
+use crate::utils::{currency, database::database::DbPool, error::error::AppError, importers};
+
+pub struct PriceService;
+
+impl PriceService {
+    pub async fn list(pool: &DbPool, budget_id: &str, filter: PriceFilter) -> Result<Vec<PriceSnapshot>, AppError> {
+        PriceRepo::list(pool, budget_id, &filter).await
+    }
+
+    pub async fn save(pool: &DbPool, budget_id: &str, req: SavePriceReq) -> Result<PriceSnapshot, AppError> {
+        let row = PriceRow {
+            price_date: req.price_date,
+            symbol: Holding::normalize_symbol(&req.symbol)
+                .ok_or_else(|| AppError::BadRequest(format!("Invalid symbol: {}", req.symbol)))?,
+            price_minor: req.price_minor,
+        };
+        if row.price_minor <= 0 {
+            return Err(AppError::BadRequest("Price must be positive".to_string()));
+        }
+        PriceRepo::upsert(pool, budget_id, std::slice::from_ref(&row), "manual").await?;
+        PriceRepo::find(pool, budget_id, &row.symbol, row.price_date).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, price_id: &str) -> Result<(), AppError> {
+        PriceRepo::delete(pool, budget_id, price_id).await
+    }
+
+    /// Load a CSV of prices. Stored prices for the same symbol and date are replaced.
+    pub async fn import(pool: &DbPool, budget_id: &str, req: ImportPricesReq) -> Result<ImportPricesReport, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let data = ImportService::decode_file(&req.file_data)?;
+        let rows = importers::prices::parse_csv(&data, currency::minor_units(&budget.currency_code))
+            .map_err(AppError::BadRequest)?;
+        if rows.is_empty() {
+            return Err(AppError::BadRequest("The file contains no prices".to_string()));
+        }
+
+        PriceRepo::upsert(pool, budget_id, &rows, "csv").await?;
+        let symbols: BTreeSet<String> = rows.iter().map(|r| r.symbol.clone()).collect();
+        Ok(ImportPricesReport {
+            imported: rows.len(),
+            symbols: symbols.into_iter().collect(),
+            from: rows.iter().map(|r| r.price_date).min(),
+            to: rows.iter().map(|r| r.price_date).max(),
+        })
+    }
+}
@@ -0,0 +1,198 @@
+use crate::manager::models::recurrence::RecurrenceRule;
+use crate::manager::models::recurring::{CreateRecurringEntryReq, RecurringEntry, RecurringPreview, UpdateRecurringEntryReq};
+use crate::manager::repository::{budgets::BudgetRepo, categories::CategoryRepo, recurring::RecurringRepo};
+use crate::manager::models::exchange_rate::Conversion;
+use crate::manager::biz::{category_limits::CategoryLimitService, exchange_rates::ExchangeRateService};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct RecurringService;
+
+impl RecurringService {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<RecurringEntry>, AppError> {
+        RecurringRepo::list(pool, budget_id).await
+    }
+
+    pub async fn get(pool: &DbPool, budget_id: &str, recurring_id: &str) -> Result<RecurringEntry, AppError> {
+        RecurringRepo::get_by_id(pool, budget_id, recurring_id).await
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, req: CreateRecurringEntryReq) -> Result<RecurringEntry, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        Self::validate_template(pool, budget_id, &req.category_id, &req.kind, req.amount_minor, false).await?;
+        RecurrenceRule {
+            frequency: req.frequency,
+            interval: req.interval_count.unwrap_or(1).max(0) as u32,
+            by_month_day: req.by_month_day.map(|d| d.max(0) as u32),
+            start_date: req.start_date,
+            end_date: req.end_date,
+            count: req.occurrence_count.map(|c| c.max(0) as u32),
+        }
+        .validate()
+        .map_err(AppError::BadRequest)?;
+
+        let currency = req.currency_code.clone().unwrap_or_else(|| budget.currency_code.clone());
+        RecurringRepo::create(pool, budget_id, &req, &currency).await
+    }
+
+    pub async fn update(pool: &DbPool, budget_id: &str, recurring_id: &str, req: UpdateRecurringEntryReq) -> Result<RecurringEntry, AppError> {
+        let mut recurring = RecurringRepo::get_by_id(pool, budget_id, recurring_id).await?;
+        // A template may keep an archived category but not be moved into one
+        let keep_hidden = req.category_id.as_ref().is_none_or(|c| *c == recurring.category_id);
+
+        if let Some(category_id) = req.category_id {
+            recurring.category_id = category_id;
+        }
+        if let Some(kind) = req.kind {
+            recurring.kind = kind;
+        }
+        if let Some(amount_minor) = req.amount_minor {
+            recurring.amount_minor = amount_minor;
+        }
+        if let Some(description) = req.description {
+            recurring.description = Some(description);
+        }
+        if let Some(counterparty) = req.counterparty {
+            recurring.counterparty = Some(counterparty);
+        }
+        if let Some(frequency) = req.frequency {
+            recurring.frequency = frequency;
+        }
+        if let Some(interval_count) = req.interval_count {
+            recurring.interval_count = interval_count;
+        }
+        if let Some(by_month_day) = req.by_month_day {
+            recurring.by_month_day = Some(by_month_day);
+        }
+        if let Some(end_date) = req.end_date {
+            recurring.end_date = Some(end_date);
+        }
+        if let Some(occurrence_count) = req.occurrence_count {
+            recurring.occurrence_count = Some(occurrence_count);
+        }
+        if let Some(active) = req.active {
+            recurring.active = active;
+        }
+
+        Self::validate_template(pool, budget_id, &recurring.category_id, &recurring.kind, recurring.amount_minor, keep_hidden).await?;
+        if recurring.interval_count < 1 {
+            return Err(AppError::BadRequest("Interval must be at least 1".to_string()));
+        }
+        recurring.rule().validate().map_err(AppError::BadRequest)?;
+
+        RecurringRepo::update(pool, &recurring).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, recurring_id: &str) -> Result<(), AppError> {
+        RecurringRepo::delete(pool, budget_id, recurring_id).await
+    }
+
+    /// Next `count` occurrences that the scheduler has not generated yet.
+    pub async fn preview(pool: &DbPool, budget_id: &str, recurring_id: &str, count: Option<u32>) -> Result<RecurringPreview, AppError> {
+        let recurring = RecurringRepo::get_by_id(pool, budget_id, recurring_id).await?;
+        let count = count.unwrap_or(5).clamp(1, 100) as usize;
+        let from = recurring.generated_through
+            .and_then(|d| d.succ_opt())
+            .unwrap_or(recurring.start_date);
+
+        Ok(RecurringPreview {
+            occurrences: recurring.rule().next_occurrences(from, count),
+            recurring_id: recurring.id,
+        })
+    }
+
+    /// Generate entries for every occurrence due on or before `today`.
+    /// Safe to run repeatedly: already generated occurrences are skipped.
+    pub async fn materialize_due(pool: &DbPool, today: chrono::NaiveDate) -> Result<RecurringRunStats, AppError> {
+        let mut stats = RecurringRunStats { templates_processed: 0, entries_created: 0, failures: 0 };
+
+        for recurring in RecurringRepo::list_due(pool, today).await? {
+            let rule = recurring.rule();
+            let from = recurring.generated_through
+                .and_then(|d| d.succ_opt())
+                .unwrap_or(recurring.start_date);
+            let dates = rule.occurrences_between(from, today);
+            let still_active = today.succ_opt()
+                .map(|tomorrow| !rule.next_occurrences(tomorrow, 1).is_empty())
+                .unwrap_or(false);
+
+            // A missing exchange rate fails the whole template so it is retried on the next run
+            let converted = match Self::convert_dates(pool, &recurring, &dates).await {
+                Ok(converted) => converted,
+                Err(e) => {
+                    stats.failures += 1;
+                    tracing::error!("Failed to convert recurring entry {}: {:?}", recurring.id, e);
+                    continue;
+                }
+            };
+
+            match RecurringRepo::materialize(pool, &recurring, &converted, today, still_active).await {
+                Ok(created) => {
+                    stats.templates_processed += 1;
+                    stats.entries_created += created;
+                    if let Some(first) = dates.first() {
+                        if let Err(e) = CategoryLimitService::invalidate_from(pool, &recurring.budget_id, *first).await {
+                            tracing::error!("Failed to invalidate category snapshots for recurring entry {}: {:?}", recurring.id, e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    stats.failures += 1;
+                    tracing::error!("Failed to materialize recurring entry {}: {:?}", recurring.id, e);
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+
+    async fn convert_dates(pool: &DbPool, recurring: &RecurringEntry, dates: &[chrono::NaiveDate]) -> Result<Vec<(chrono::NaiveDate, Conversion)>, AppError> {
+        let budget = BudgetRepo::get(pool, &recurring.budget_id).await?;
+        let mut converted = Vec::with_capacity(dates.len());
+        for date in dates {
+            let conversion = ExchangeRateService::convert(pool, &budget, &recurring.currency_code, recurring.amount_minor, *date, None).await?
+                .ok_or_else(|| AppError::BadRequest(format!(
+                    "No exchange rate from {} to {} on or before {}",
+                    recurring.currency_code, budget.currency_code, date
+                )))?;
+            converted.push((*date, conversion));
+        }
+        Ok(converted)
+    }
+
+    async fn validate_template(pool: &DbPool, budget_id: &str, category_id: &str, kind: &str, amount_minor: i64, allow_hidden: bool) -> Result<(), AppError> {
+        if kind != "income" && kind != "expense" {
+            return Err(AppError::BadRequest("Kind must be income or expense".to_string()));
+        }
+        if amount_minor <= 0 {
+            return Err(AppError::BadRequest("Amount must be positive".to_string()));
+        }
+        let category = CategoryRepo::get_by_id(pool, budget_id, category_id).await
+            .map_err(|_| AppError::BadRequest("Category not found in this budget".to_string()))?;
+        if category.kind != kind {
+            return Err(AppError::BadRequest(format!("Category is for {} entries", category.kind)));
+        }
+        if category.is_hidden && !allow_hidden {
+            return Err(AppError::BadRequest(format!("Category '{}' is archived", category.name)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RecurringRunStats {
+    pub templates_processed: u64,
+    pub entries_created: u64,
+    pub failures: u64,
+}
+
+impl std::fmt::Display for RecurringRunStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Recurring entries: {} templates processed, {} entries created, {} failures",
+            self.templates_processed,
+            self.entries_created,
+            self.failures
+        )
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+use crate::manager::models::export::ExportQuery;
+use crate::manager::models::rule::{ApplyRulesReport, ApplyRulesReq, Rule, RuleChange, RuleSet, RuleSubject, SaveRuleReq};
+use crate::manager::models::tag::TagFilter;
+use crate::manager::repository::{categories::CategoryRepo, entries::EntryRepo, entry_splits::EntrySplitRepo, rules::RuleRepo, tags::TagRepo};
+use crate::manager::biz::{category_limits::CategoryLimitService, tags::TagService};
+use crate::utils::{database::database::DbPool, error::error::{AppError, FieldError}};
+
+const APPLY_PAGE_SIZE: u32 = 500;
+
+pub struct RuleService;
+
+impl RuleService {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<Rule>, AppError> {
+        RuleRepo::list(pool, budget_id).await
+    }
+
+    pub async fn get(pool: &DbPool, budget_id: &str, rule_id: &str) -> Result<Rule, AppError> {
+        RuleRepo::get_by_id(pool, budget_id, rule_id).await
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, mut req: SaveRuleReq) -> Result<Rule, AppError> {
+        let tag_ids = Self::validate(pool, budget_id, &mut req).await?;
+        RuleRepo::create(pool, budget_id, &req, &tag_ids).await
+    }
+
+    pub async fn replace(pool: &DbPool, budget_id: &str, rule_id: &str, mut req: SaveRuleReq) -> Result<Rule, AppError> {
+        RuleRepo::get_by_id(pool, budget_id, rule_id).await?;
+        let tag_ids = Self::validate(pool, budget_id, &mut req).await?;
+        RuleRepo::replace(pool, budget_id, rule_id, &req, &tag_ids).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, rule_id: &str) -> Result<(), AppError> {
+        RuleRepo::delete(pool, budget_id, rule_id).await
+    }
+
+    /// Active rules of the budget, ready to run over new entries.
+    pub async fn rule_set(pool: &DbPool, budget_id: &str) -> Result<RuleSet, AppError> {
+        let rules = RuleRepo::list(pool, budget_id).await?;
+        if rules.iter().all(|r| !r.active) {
+            return Ok(RuleSet::new(Vec::new(), &[]));
+        }
+        let categories = CategoryRepo::list(pool, budget_id, None).await?;
+        Ok(RuleSet::new(rules, &categories))
+    }
+
+    /// Run the rules over existing entries. Unlike on create, a matching rule
+    /// replaces the category the entry already has; split entries keep theirs.
+    pub async fn apply(pool: &DbPool, budget_id: &str, req: ApplyRulesReq, user_id: &str) -> Result<ApplyRulesReport, AppError> {
+        let dry_run = req.dry_run.unwrap_or(false);
+        let tags = TagFilter::parse(req.tags.as_deref(), req.tag_match.as_deref())?;
+        let has_filter = req.from.is_some() || req.to.is_some() || req.kind.is_some() || req.category_id.is_some()
+            || req.member_id.is_some() || req.search.is_some() || tags.is_some();
+        // Rewriting the whole history is allowed, but only after looking at it
+        if !has_filter && !dry_run {
+            return Err(AppError::BadRequest("At least one filter is required unless dry_run is set".to_string()));
+        }
+
+        let mut rules = RuleRepo::list(pool, budget_id).await?;
+        if let Some(only) = &req.rule_ids {
+            for id in only {
+                if !rules.iter().any(|r| &r.id == id) {
+                    return Err(AppError::BadRequest(format!("Rule {} not found in this budget", id)));
+                }
+            }
+            rules.retain(|r| only.contains(&r.id));
+        }
+        let categories = CategoryRepo::list(pool, budget_id, None).await?;
+        let rule_set = RuleSet::new(rules, &categories);
+
+        let filter = ExportQuery {
+            format: None,
+            from: req.from,
+            to: req.to,
+            kind: req.kind,
+            category_id: req.category_id,
+            member_id: req.member_id,
+            search: req.search,
+            tags: req.tags,
+            tag_match: req.tag_match,
+            sort_by: None,
+            sort_order: Some("asc".to_string()),
+        };
+        let mut report = ApplyRulesReport { dry_run, entries_checked: 0, entries_changed: 0, changes: Vec::new() };
+        let mut offset = 0;
+        loop {
+            let page = EntryRepo::export_page(pool, budget_id, &filter, true, APPLY_PAGE_SIZE, offset).await?;
+            if page.is_empty() || rule_set.is_empty() {
+                break;
+            }
+            let ids: Vec<String> = page.iter().map(|e| e.id.clone()).collect();
+            let split: HashSet<String> = EntrySplitRepo::list_for_entries(pool, &ids).await?
+                .into_iter().map(|s| s.entry_id).collect();
+            let mut entry_tags: HashMap<String, HashSet<String>> = HashMap::new();
+            for tag in TagRepo::list_for_entries(pool, &ids).await? {
+                entry_tags.entry(tag.entry_id).or_default().insert(tag.id);
+            }
+
+            for entry in &page {
+                report.entries_checked += 1;
+                let outcome = rule_set.apply(&RuleSubject {
+                    kind: &entry.kind,
+                    amount_minor: entry.amount_minor,
+                    description: entry.description.as_deref(),
+                    counterparty: entry.counterparty.as_deref(),
+                });
+                let category_id = outcome.category_id
+                    .filter(|c| *c != entry.category_id && !split.contains(&entry.id));
+                let counterparty = outcome.counterparty
+                    .filter(|c| entry.counterparty.as_deref() != Some(c.as_str()));
+                let existing = entry_tags.get(&entry.id);
+                let added_tag_ids: Vec<String> = outcome.tag_ids.into_iter()
+                    .filter(|t| !existing.is_some_and(|e| e.contains(t)))
+                    .collect();
+                if category_id.is_none() && counterparty.is_none() && added_tag_ids.is_empty() {
+                    continue;
+                }
+                report.changes.push(RuleChange {
+                    entry_id: entry.id.clone(),
+                    entry_date: entry.entry_date,
+                    rule_ids: outcome.rule_ids,
+                    previous_category_id: entry.category_id.clone(),
+                    category_id,
+                    previous_counterparty: entry.counterparty.clone(),
+                    counterparty,
+                    added_tag_ids,
+                });
+            }
+            if (page.len() as u32) < APPLY_PAGE_SIZE {
+                break;
+            }
+            offset += APPLY_PAGE_SIZE;
+        }
+        report.entries_changed = report.changes.len();
+        if dry_run || report.changes.is_empty() {
+            return Ok(report);
+        }
+
+        RuleRepo::apply_changes(pool, budget_id, &report.changes, user_id).await?;
+        if let Some(earliest) = report.changes.iter().filter(|c| c.category_id.is_some()).map(|c| c.entry_date).min() {
+            CategoryLimitService::invalidate_from(pool, budget_id, earliest).await?;
+        }
+        Ok(report)
+    }
+
+    /// Normalize the request in place and return the resolved tag ids.
+    async fn validate(pool: &DbPool, budget_id: &str, req: &mut SaveRuleReq) -> Result<Vec<String>, AppError> {
+        let mut errors = Vec::new();
+        req.name = req.name.trim().to_string();
+        if req.name.is_empty() {
+            errors.push(FieldError::new("name", "Rule name is required"));
+        }
+        for value in [&mut req.counterparty_pattern, &mut req.description_regex, &mut req.set_counterparty, &mut req.set_category_id] {
+            *value = value.take().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        }
+
+        if let Some(mode) = req.counterparty_match.as_deref() {
+            if !["contains", "equals", "starts_with"].contains(&mode) {
+                errors.push(FieldError::new("counterparty_match", "Use contains, equals or starts_with"));
+            }
+        }
+        if let Some(pattern) = req.description_regex.as_deref() {
+            if let Err(e) = Rule::compile_regex(pattern) {
+                errors.push(FieldError::new("description_regex", format!("Invalid regular expression: {}", e)));
+            }
+        }
+        if let Some(kind) = req.kind.as_deref() {
+            if kind != "income" && kind != "expense" {
+                errors.push(FieldError::new("kind", "Kind must be income or expense"));
+            }
+        }
+        if req.min_amount_minor.is_some_and(|m| m < 0) {
+            errors.push(FieldError::new("min_amount_minor", "Amount must not be negative"));
+        }
+        if let (Some(min), Some(max)) = (req.min_amount_minor, req.max_amount_minor) {
+            if min > max {
+                errors.push(FieldError::new("max_amount_minor", "Maximum must not be below the minimum"));
+            }
+        }
+        let has_condition = req.counterparty_pattern.is_some() || req.description_regex.is_some()
+            || req.min_amount_minor.is_some() || req.max_amount_minor.is_some() || req.kind.is_some();
+        if !has_condition {
+            errors.push(FieldError::new("conditions", "A rule needs at least one condition"));
+        }
+        let tag_ids = req.set_tag_ids.take().unwrap_or_default();
+        if req.set_category_id.is_none() && req.set_counterparty.is_none() && tag_ids.is_empty() {
+            errors.push(FieldError::new("actions", "A rule must set a category, tags or a counterparty"));
+        }
+
+        if let Some(category_id) = req.set_category_id.as_deref() {
+            match CategoryRepo::list(pool, budget_id, None).await?.into_iter().find(|c| c.id == category_id) {
+                None => errors.push(FieldError::new("set_category_id", "Category not found in this budget")),
+                Some(c) if c.is_hidden => errors.push(FieldError::new("set_category_id", format!("Category '{}' is archived", c.name))),
+                Some(c) if req.kind.as_deref().is_some_and(|k| k != c.kind) => {
+                    errors.push(FieldError::new("set_category_id", format!("Category '{}' is for {} entries", c.name, c.kind)));
+                }
+                Some(_) => {}
+            }
+        }
+        let tag_ids = match TagService::resolve_ids(pool, budget_id, &tag_ids).await {
+            Ok(ids) => ids,
+            Err(AppError::BadRequest(message)) => {
+                errors.push(FieldError::new("set_tag_ids", message));
+                Vec::new()
+            }
+            Err(e) => return Err(e),
+        };
+
+        if !errors.is_empty() {
+            return Err(AppError::Validation(errors));
+        }
+        Ok(tag_ids)
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+use chrono::{Datelike, Months};
+use crate::manager::biz::{categories::CategoryService, category_limits::CategoryLimitService};
+use crate::manager::models::budget::BudgetType;
+use crate::manager::models::savings_goal::{CreateSavingsGoalReq, GoalMonth, SavingsGoal, SavingsGoalProgress, UpdateSavingsGoalReq};
+use crate::manager::repository::{budgets::BudgetRepo, categories::CategoryRepo, savings_goals::SavingsGoalRepo};
+use crate::utils::{database::database::DbPool, error::error::{AppError, FieldError}};
+
+const DEFAULT_AVERAGE_MONTHS: u32 = 3;
+const MAX_AVERAGE_MONTHS: u32 = 24;
+
+pub struct SavingsGoalService;
+
+impl SavingsGoalService {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<SavingsGoal>, AppError> {
+        SavingsGoalRepo::list(pool, budget_id).await
+    }
+
+    pub async fn get(pool: &DbPool, budget_id: &str, goal_id: &str) -> Result<SavingsGoal, AppError> {
+        SavingsGoalRepo::get_by_id(pool, budget_id, goal_id).await
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, user_id: &str, mut req: CreateSavingsGoalReq) -> Result<SavingsGoal, AppError> {
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        if budget.budget_type != BudgetType::Saving {
+            return Err(AppError::BadRequest("Savings goals can only be added to saving budgets".to_string()));
+        }
+        req.name = req.name.trim().to_string();
+        req.note = req.note.take().map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        let category_ids = req.category_ids.take().unwrap_or_default();
+        let category_ids = Self::validate(pool, budget_id, &req.name, req.target_amount_minor, req.start_date, req.target_date, category_ids).await?;
+        SavingsGoalRepo::create(pool, budget_id, &req, &category_ids, user_id).await
+    }
+
+    pub async fn update(pool: &DbPool, budget_id: &str, goal_id: &str, req: UpdateSavingsGoalReq) -> Result<SavingsGoal, AppError> {
+        let mut goal = SavingsGoalRepo::get_by_id(pool, budget_id, goal_id).await?;
+        if let Some(name) = req.name {
+            goal.name = name.trim().to_string();
+        }
+        if let Some(target) = req.target_amount_minor {
+            goal.target_amount_minor = target;
+        }
+        if req.target_date.is_some() {
+            goal.target_date = req.target_date;
+        }
+        if req.start_date.is_some() {
+            goal.start_date = req.start_date;
+        }
+        if let Some(note) = req.note {
+            goal.note = Some(note.trim().to_string()).filter(|n| !n.is_empty());
+        }
+        let category_ids = req.category_ids.unwrap_or(std::mem::take(&mut goal.category_ids));
+        goal.category_ids = Self::validate(pool, budget_id, &goal.name, goal.target_amount_minor, goal.start_date, goal.target_date, category_ids).await?;
+        SavingsGoalRepo::update(pool, &goal).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, goal_id: &str) -> Result<(), AppError> {
+        SavingsGoalRepo::delete(pool, budget_id, goal_id).await
+    }
+
+    /// Amount saved so far, the monthly contribution still needed to reach the
+    /// target date, and when the goal is reached at the trailing average pace.
+    /// The average covers the last `months` complete months, not before the
+    /// goal's start.
+    pub async fn progress(pool: &DbPool, budget_id: &str, goal_id: &str, months: Option<u32>) -> Result<SavingsGoalProgress, AppError> {
+        let goal = SavingsGoalRepo::get_by_id(pool, budget_id, goal_id).await?;
+        let budget = BudgetRepo::get(pool, budget_id).await?;
+        let today = chrono::Utc::now().date_naive();
+        let current_month = CategoryLimitService::month_start(today);
+
+        let category_ids = if goal.category_ids.is_empty() {
+            None
+        } else {
+            // A linked category also counts everything filed under its subcategories
+            let categories = CategoryRepo::list(pool, budget_id, None).await?;
+            Some(CategoryService::with_descendants(&categories, &goal.category_ids))
+        };
+        let rows = SavingsGoalRepo::monthly_contributions(pool, budget_id, category_ids.as_deref(), goal.start_date, today).await?;
+
+        let from_entries_minor: i64 = rows.iter().map(|(_, entries, _)| entries).sum();
+        let from_transfers_minor: i64 = rows.iter().map(|(_, _, transfers)| transfers).sum();
+        let saved_minor = from_entries_minor + from_transfers_minor;
+        let remaining_minor = (goal.target_amount_minor - saved_minor).max(0);
+        let achieved = remaining_minor == 0;
+        let percent = (saved_minor.max(0) as f64 / goal.target_amount_minor as f64 * 10000.0).round() / 100.0;
+
+        let months_left = goal.target_date.map(|target| {
+            let target_month = CategoryLimitService::month_start(target);
+            if target_month < current_month { 0 } else { Self::months_between(current_month, target_month) + 1 }
+        });
+        let required_monthly_minor = months_left.map(|left| match left {
+            _ if achieved => 0,
+            0 => remaining_minor, // past the target date: everything is due now
+            left => Self::div_ceil(remaining_minor, left as i64),
+        });
+
+        let requested = months.unwrap_or(DEFAULT_AVERAGE_MONTHS).clamp(1, MAX_AVERAGE_MONTHS);
+        let mut window_start = current_month.checked_sub_months(Months::new(requested)).unwrap_or(current_month);
+        if let Some(start) = goal.start_date {
+            // A goal started mid-month only averages the months after that one
+            let first_full = match CategoryLimitService::month_start(start) {
+                month if month == start => month,
+                month => CategoryLimitService::next_month_start(month),
+            };
+            window_start = window_start.max(first_full);
+        }
+        let average_months = if window_start < current_month { Self::months_between(window_start, current_month) } else { 0 };
+        let window_total: i64 = rows.iter()
+            .filter(|(month, _, _)| *month >= window_start && *month < current_month)
+            .map(|(_, entries, transfers)| entries + transfers)
+            .sum();
+        let average_monthly_minor = if average_months > 0 { (window_total as f64 / average_months as f64).round() as i64 } else { 0 };
+
+        let projected_completion_date = if achieved || average_monthly_minor <= 0 {
+            None
+        } else {
+            let months_needed = Self::div_ceil(remaining_minor, average_monthly_minor).min(u32::MAX as i64) as u32;
+            today.checked_add_months(Months::new(months_needed))
+        };
+        let on_track = goal.target_date.map(|target| achieved || projected_completion_date.is_some_and(|p| p <= target));
+
+        Ok(SavingsGoalProgress {
+            goal_id: goal.id,
+            currency_code: budget.currency_code,
+            target_amount_minor: goal.target_amount_minor,
+            saved_minor,
+            from_entries_minor,
+            from_transfers_minor,
+            remaining_minor,
+            percent,
+            achieved,
+            target_date: goal.target_date,
+            months_left,
+            required_monthly_minor,
+            average_months,
+            average_monthly_minor,
+            projected_completion_date,
+            on_track,
+            monthly: rows.into_iter()
+                .map(|(month_start, entries, transfers)| GoalMonth { month_start, contributed_minor: entries + transfers })
+                .collect(),
+        })
+    }
+
+    async fn validate(pool: &DbPool, budget_id: &str, name: &str, target_amount_minor: i64, start_date: Option<chrono::NaiveDate>,
+                      target_date: Option<chrono::NaiveDate>, category_ids: Vec<String>) -> Result<Vec<String>, AppError> {
+        let mut errors = Vec::new();
+        if name.is_empty() {
+            errors.push(FieldError::new("name", "Goal name is required"));
+        }
+        if target_amount_minor <= 0 {
+            errors.push(FieldError::new("target_amount_minor", "Target amount must be positive"));
+        }
+        if let (Some(start), Some(target)) = (start_date, target_date) {
+            if target < start {
+                errors.push(FieldError::new("target_date", "Target date must not be before the start date"));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let category_ids: Vec<String> = category_ids.into_iter().filter(|id| seen.insert(id.clone())).collect();
+        if !category_ids.is_empty() {
+            let categories = CategoryRepo::list(pool, budget_id, None).await?;
+            for (i, id) in category_ids.iter().enumerate() {
+                if !categories.iter().any(|c| &c.id == id) {
+                    errors.push(FieldError::new(format!("category_ids[{}]", i), "Category not found in this budget"));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(AppError::Validation(errors));
+        }
+        Ok(category_ids)
+    }
+
+    fn months_between(from: chrono::NaiveDate, to: chrono::NaiveDate) -> u32 {
+        let months = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32;
+        months.max(0) as u32
+    }
+
+    fn div_ceil(amount: i64, by: i64) -> i64 {
+        (amount + by - 1) / by
+    }
+}
@@ -0,0 +1,268 @@
+use crate::manager::models::notification::CreateNotificationReq;
+use crate::manager::models::recurrence::RecurrenceRule;
+use crate::manager::models::role::Role;
+use crate::manager::models::scheduled_transfer::{CreateScheduledTransferReq, ScheduledTransfer, ScheduledTransferPreview, UpdateScheduledTransferReq};
+use crate::manager::models::transfer::CreateTransferReq;
+use crate::manager::repository::{budgets::BudgetRepo, notifications::NotificationRepo, scheduled_transfers::ScheduledTransferRepo};
+use crate::manager::biz::transfers::TransferService;
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct ScheduledTransferService;
+
+impl ScheduledTransferService {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<ScheduledTransfer>, AppError> {
+        ScheduledTransferRepo::list_by_budget(pool, budget_id).await
+    }
+
+    /// Visible to viewers of either budget.
+    pub async fn get(pool: &DbPool, user_id: &str, scheduled_id: &str) -> Result<ScheduledTransfer, AppError> {
+        let scheduled = ScheduledTransferRepo::get_by_id(pool, scheduled_id).await?;
+        if crate::manager::biz::authz::ensure_role(pool, &scheduled.from_budget_id, user_id, Role::Viewer).await.is_err() {
+            crate::manager::biz::authz::ensure_role(pool, &scheduled.to_budget_id, user_id, Role::Viewer).await?;
+        }
+        Ok(scheduled)
+    }
+
+    pub async fn create(pool: &DbPool, user_id: &str, req: CreateScheduledTransferReq) -> Result<ScheduledTransfer, AppError> {
+        if req.from_budget_id == req.to_budget_id {
+            return Err(AppError::BadRequest("Cannot transfer to the same budget".to_string()));
+        }
+        Self::ensure_both(pool, &req.from_budget_id, &req.to_budget_id, user_id).await?;
+        Self::validate_amounts(req.amount_minor, req.fee_minor.unwrap_or(0), req.exchange_rate)?;
+        TransferService::validate_category(pool, &req.from_budget_id, &req.from_category_id, "expense", false).await?;
+        TransferService::validate_category(pool, &req.to_budget_id, &req.to_category_id, "income", false).await?;
+        if let Some(fee_category_id) = &req.fee_category_id {
+            TransferService::validate_category(pool, &req.from_budget_id, fee_category_id, "expense", false).await?;
+        }
+        RecurrenceRule {
+            frequency: req.frequency,
+            interval: req.interval_count.unwrap_or(1).max(0) as u32,
+            by_month_day: req.by_month_day.map(|d| d.max(0) as u32),
+            start_date: req.start_date,
+            end_date: req.end_date,
+            count: req.occurrence_count.map(|c| c.max(0) as u32),
+        }
+        .validate()
+        .map_err(AppError::BadRequest)?;
+
+        ScheduledTransferRepo::create(pool, &req, user_id).await
+    }
+
+    pub async fn update(pool: &DbPool, user_id: &str, scheduled_id: &str, req: UpdateScheduledTransferReq) -> Result<ScheduledTransfer, AppError> {
+        let mut scheduled = ScheduledTransferRepo::get_by_id(pool, scheduled_id).await?;
+        Self::ensure_both(pool, &scheduled.from_budget_id, &scheduled.to_budget_id, user_id).await?;
+        // A schedule may keep an archived category but not be moved into one
+        let keep_from = req.from_category_id.as_ref().is_none_or(|c| *c == scheduled.from_category_id);
+        let keep_to = req.to_category_id.as_ref().is_none_or(|c| *c == scheduled.to_category_id);
+        let keep_fee = req.fee_category_id.is_none() || req.fee_category_id == scheduled.fee_category_id;
+
+        if let Some(from_category_id) = req.from_category_id {
+            scheduled.from_category_id = from_category_id;
+        }
+        if let Some(to_category_id) = req.to_category_id {
+            scheduled.to_category_id = to_category_id;
+        }
+        if let Some(amount_minor) = req.amount_minor {
+            scheduled.amount_minor = amount_minor;
+        }
+        if let Some(exchange_rate) = req.exchange_rate {
+            scheduled.exchange_rate = Some(exchange_rate);
+        }
+        if let Some(fee_minor) = req.fee_minor {
+            scheduled.fee_minor = fee_minor;
+        }
+        if let Some(fee_category_id) = req.fee_category_id {
+            scheduled.fee_category_id = Some(fee_category_id);
+        }
+        if let Some(note) = req.note {
+            scheduled.note = Some(note);
+        }
+        if let Some(frequency) = req.frequency {
+            scheduled.frequency = frequency;
+        }
+        if let Some(interval_count) = req.interval_count {
+            scheduled.interval_count = interval_count;
+        }
+        if let Some(by_month_day) = req.by_month_day {
+            scheduled.by_month_day = Some(by_month_day);
+        }
+        if let Some(end_date) = req.end_date {
+            scheduled.end_date = Some(end_date);
+        }
+        if let Some(occurrence_count) = req.occurrence_count {
+            scheduled.occurrence_count = Some(occurrence_count);
+        }
+        if let Some(active) = req.active {
+            scheduled.active = active;
+        }
+
+        Self::validate_amounts(scheduled.amount_minor, scheduled.fee_minor, scheduled.exchange_rate)?;
+        TransferService::validate_category(pool, &scheduled.from_budget_id, &scheduled.from_category_id, "expense", keep_from).await?;
+        TransferService::validate_category(pool, &scheduled.to_budget_id, &scheduled.to_category_id, "income", keep_to).await?;
+        if let Some(fee_category_id) = &scheduled.fee_category_id {
+            TransferService::validate_category(pool, &scheduled.from_budget_id, fee_category_id, "expense", keep_fee).await?;
+        }
+        if scheduled.interval_count < 1 {
+            return Err(AppError::BadRequest("Interval must be at least 1".to_string()));
+        }
+        scheduled.rule().validate().map_err(AppError::BadRequest)?;
+
+        ScheduledTransferRepo::update(pool, &scheduled).await
+    }
+
+    /// Transfers already executed stay; they only lose the link to the schedule.
+    pub async fn delete(pool: &DbPool, user_id: &str, scheduled_id: &str) -> Result<(), AppError> {
+        let scheduled = ScheduledTransferRepo::get_by_id(pool, scheduled_id).await?;
+        Self::ensure_both(pool, &scheduled.from_budget_id, &scheduled.to_budget_id, user_id).await?;
+        ScheduledTransferRepo::delete(pool, scheduled_id).await
+    }
+
+    /// Next `count` occurrences that the scheduler has not executed yet.
+    pub async fn preview(pool: &DbPool, user_id: &str, scheduled_id: &str, count: Option<u32>) -> Result<ScheduledTransferPreview, AppError> {
+        let scheduled = Self::get(pool, user_id, scheduled_id).await?;
+        let count = count.unwrap_or(5).clamp(1, 100) as usize;
+        let from = scheduled.generated_through
+            .and_then(|d| d.succ_opt())
+            .unwrap_or(scheduled.start_date);
+
+        Ok(ScheduledTransferPreview {
+            occurrences: scheduled.rule().next_occurrences(from, count),
+            scheduled_transfer_id: scheduled.id,
+        })
+    }
+
+    /// Execute every occurrence due on or before `today` as the schedule's
+    /// creator, with the same checks as a manual transfer. Each outcome is
+    /// reported to the creator; a failed occurrence is not retried.
+    pub async fn execute_due(pool: &DbPool, today: chrono::NaiveDate) -> Result<ScheduledTransferRunStats, AppError> {
+        let mut stats = ScheduledTransferRunStats { schedules_processed: 0, transfers_created: 0, failures: 0 };
+
+        for scheduled in ScheduledTransferRepo::list_due(pool, today).await? {
+            let rule = scheduled.rule();
+            let from = scheduled.generated_through
+                .and_then(|d| d.succ_opt())
+                .unwrap_or(scheduled.start_date);
+            let still_active = today.succ_opt()
+                .map(|tomorrow| !rule.next_occurrences(tomorrow, 1).is_empty())
+                .unwrap_or(false);
+
+            for date in rule.occurrences_between(from, today) {
+                // Re-runs after a crash skip occurrences already executed
+                if ScheduledTransferRepo::executed(pool, &scheduled.id, date).await? {
+                    continue;
+                }
+                match Self::execute(pool, &scheduled, date).await {
+                    Ok(transfer_id) => {
+                        stats.transfers_created += 1;
+                        Self::notify(pool, &scheduled, date, Ok(&transfer_id)).await;
+                    }
+                    Err(e) => {
+                        stats.failures += 1;
+                        tracing::error!("Failed to execute scheduled transfer {} for {}: {:?}", scheduled.id, date, e);
+                        Self::notify(pool, &scheduled, date, Err(&e)).await;
+                    }
+                }
+            }
+            ScheduledTransferRepo::advance(pool, &scheduled.id, today, still_active).await?;
+            stats.schedules_processed += 1;
+        }
+
+        Ok(stats)
+    }
+
+    async fn execute(pool: &DbPool, scheduled: &ScheduledTransfer, date: chrono::NaiveDate) -> Result<String, AppError> {
+        let from_budget = BudgetRepo::get(pool, &scheduled.from_budget_id).await?;
+        let created = TransferService::create_transfer(pool, &scheduled.created_by, CreateTransferReq {
+            from_budget_id: scheduled.from_budget_id.clone(),
+            to_budget_id: scheduled.to_budget_id.clone(),
+            amount_minor: scheduled.amount_minor,
+            currency_code: from_budget.currency_code,
+            transfer_date: date,
+            note: scheduled.note.clone(),
+            from_category_id: scheduled.from_category_id.clone(),
+            to_category_id: scheduled.to_category_id.clone(),
+            exchange_rate: scheduled.exchange_rate,
+            fee_minor: Some(scheduled.fee_minor),
+            fee_category_id: scheduled.fee_category_id.clone(),
+            scheduled_transfer_id: Some(scheduled.id.clone()),
+        }).await?;
+        Ok(created.transfer.id)
+    }
+
+    /// Failing to notify must not stop the run.
+    async fn notify(pool: &DbPool, scheduled: &ScheduledTransfer, date: chrono::NaiveDate, outcome: Result<&str, &AppError>) {
+        let (notification_type, title, message, related_id) = match outcome {
+            Ok(transfer_id) => (
+                "scheduled_transfer_executed",
+                "Scheduled transfer completed".to_string(),
+                format!("The transfer scheduled for {} was made", date),
+                transfer_id.to_string(),
+            ),
+            Err(e) => {
+                let reason = match e {
+                    AppError::BadRequest(msg) => msg.clone(),
+                    AppError::Forbidden => "you are no longer a contributor in both budgets".to_string(),
+                    AppError::NotFound => "a budget or category no longer exists".to_string(),
+                    _ => "an internal error occurred".to_string(),
+                };
+                (
+                    "scheduled_transfer_failed",
+                    "Scheduled transfer failed".to_string(),
+                    format!("The transfer scheduled for {} was not made: {}. Make it by hand if it is still needed", date, reason),
+                    scheduled.id.clone(),
+                )
+            }
+        };
+        let notification = CreateNotificationReq {
+            user_id: scheduled.created_by.clone(),
+            budget_id: scheduled.from_budget_id.clone(),
+            notification_type: notification_type.to_string(),
+            title,
+            message,
+            link_url: Some(format!("/budgets/{}/transfers", scheduled.from_budget_id)),
+            related_id: Some(related_id),
+        };
+        if let Err(e) = NotificationRepo::create(pool, notification).await {
+            tracing::error!("Failed to notify about scheduled transfer {}: {:?}", scheduled.id, e);
+        }
+    }
+
+    async fn ensure_both(pool: &DbPool, from_budget_id: &str, to_budget_id: &str, user_id: &str) -> Result<(), AppError> {
+        for budget_id in [from_budget_id, to_budget_id] {
+            crate::manager::biz::authz::ensure_role(pool, budget_id, user_id, Role::Contributor).await?;
+        }
+        Ok(())
+    }
+
+    fn validate_amounts(amount_minor: i64, fee_minor: i64, exchange_rate: Option<f64>) -> Result<(), AppError> {
+        if amount_minor <= 0 {
+            return Err(AppError::BadRequest("Transfer amount must be positive".to_string()));
+        }
+        if fee_minor < 0 {
+            return Err(AppError::BadRequest("Transfer fee cannot be negative".to_string()));
+        }
+        if exchange_rate.is_some_and(|r| !r.is_finite() || r <= 0.0) {
+            return Err(AppError::BadRequest("Exchange rate must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ScheduledTransferRunStats {
+    pub schedules_processed: u64,
+    pub transfers_created: u64,
+    pub failures: u64,
+}
+
+impl std::fmt::Display for ScheduledTransferRunStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Scheduled transfers: {} schedules processed, {} transfers created, {} failures",
+            self.schedules_processed,
+            self.transfers_created,
+            self.failures
+        )
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::{HashMap, HashSet};
+use crate::manager::models::entry::Entry;
+use crate::manager::models::tag::{CreateTagReq, Tag, TagSummaryRow, UpdateTagReq};
+use crate::manager::repository::tags::TagRepo;
+use crate::manager::biz::categories::CategoryService;
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct TagService;
+
+impl TagService {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<Tag>, AppError> {
+        TagRepo::list(pool, budget_id).await
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, mut req: CreateTagReq) -> Result<Tag, AppError> {
+        req.name = Self::validate_name(&req.name)?;
+        Self::validate_color(req.color.as_deref())?;
+        if TagRepo::find_by_name(pool, budget_id, &req.name).await?.is_some() {
+            return Err(AppError::BadRequest(format!("Tag '{}' already exists", req.name)));
+        }
+        TagRepo::create(pool, budget_id, &req).await
+    }
+
+    pub async fn update(pool: &DbPool, budget_id: &str, tag_id: &str, req: UpdateTagReq) -> Result<Tag, AppError> {
+        let mut tag = TagRepo::get_by_id(pool, budget_id, tag_id).await?;
+        if let Some(name) = req.name {
+            let name = Self::validate_name(&name)?;
+            if let Some(existing) = TagRepo::find_by_name(pool, budget_id, &name).await? {
+                if existing.id != tag.id {
+                    return Err(AppError::BadRequest(format!("Tag '{}' already exists", name)));
+                }
+            }
+            tag.name = name;
+        }
+        if let Some(color) = req.color {
+            Self::validate_color(Some(&color))?;
+            tag.color = Some(color);
+        }
+        TagRepo::update(pool, &tag).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, tag_id: &str) -> Result<(), AppError> {
+        TagRepo::delete(pool, budget_id, tag_id).await
+    }
+
+    pub async fn summary(pool: &DbPool, budget_id: &str, from: chrono::NaiveDate, to: chrono::NaiveDate) -> Result<Vec<TagSummaryRow>, AppError> {
+        if from > to {
+            return Err(AppError::BadRequest("from must not be after to".to_string()));
+        }
+        TagRepo::summary(pool, budget_id, from, to).await
+    }
+
+    /// Check that every id belongs to the budget's vocabulary; returns them deduplicated.
+    pub async fn resolve_ids(pool: &DbPool, budget_id: &str, tag_ids: &[String]) -> Result<Vec<String>, AppError> {
+        if tag_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let known: HashSet<String> = TagRepo::list(pool, budget_id).await?.into_iter().map(|t| t.id).collect();
+        let mut seen = HashSet::new();
+        let mut resolved = Vec::new();
+        for id in tag_ids {
+            if !known.contains(id) {
+                return Err(AppError::BadRequest(format!("Tag {} not found in this budget", id)));
+            }
+            if seen.insert(id.clone()) {
+                resolved.push(id.clone());
+            }
+        }
+        Ok(resolved)
+    }
+
+    pub async fn attach_tags(pool: &DbPool, entries: &mut [Entry]) -> Result<(), AppError> {
+        let ids: Vec<String> = entries.iter().map(|e| e.id.clone()).collect();
+        let mut by_entry: HashMap<String, Vec<_>> = HashMap::new();
+        for tag in TagRepo::list_for_entries(pool, &ids).await? {
+            by_entry.entry(tag.entry_id.clone()).or_default().push(tag);
+        }
+        for entry in entries.iter_mut() {
+            entry.tags = by_entry.remove(&entry.id).unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    fn validate_name(name: &str) -> Result<String, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest("Tag name is required".to_string()));
+        }
+        if name.chars().count() > 64 {
+            return Err(AppError::BadRequest("Tag name must be at most 64 characters".to_string()));
+        }
+        Ok(name.to_string())
+    }
+
+    fn validate_color(color: Option<&str>) -> Result<(), AppError> {
+        match color {
+            Some(color) if !CategoryService::is_valid_color(color) => {
+                Err(AppError::BadRequest("Invalid color format. Use hex format like #FF5733".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -1,11 +1,28 @@
-use crate::manager::models::transfer::{CreateTransferReq, TransferWithEntries};
+use sqlx::MySqlConnection;
+use crate::manager::models::budget::Budget;
+use crate::manager::models::exchange_rate::Conversion;
+use crate::manager::models::transfer::{BudgetTransfer, CreateTransferReq, TransferWithEntries, UpdateTransferReq};
 use crate::manager::repository::transfers::TransferRepo;
 use crate::manager::repository::budgets::BudgetRepo;
+use crate::manager::repository::categories::CategoryRepo;
 use crate::manager::models::role::Role;
+use crate::manager::biz::{category_limits::CategoryLimitService, exchange_rates::ExchangeRateService};
 use crate::utils::{database::database::DbPool, error::error::AppError};
 
 pub struct TransferService;
 
+/// One entry generated by a transfer, in its budget's currency.
+struct NewLeg<'a> {
+    entry_id: &'a str,
+    budget_id: &'a str,
+    category_id: &'a str,
+    kind: &'a str,
+    leg: &'a str,
+    amount_minor: i64,
+    currency_code: &'a str,
+    description: &'a str,
+}
+
 impl TransferService {
     pub async fn create_transfer(
         pool: &DbPool,
@@ -16,10 +33,6 @@ impl TransferService {
             return Err(AppError::BadRequest("Cannot transfer to the same budget".to_string()));
         }
 
-        if req.amount_minor <= 0 {
-            return Err(AppError::BadRequest("Transfer amount must be positive".to_string()));
-        }
-
         crate::manager::biz::authz::ensure_role(
             pool,
             &req.from_budget_id,
@@ -37,81 +50,339 @@ impl TransferService {
         let from_budget = BudgetRepo::get(pool, &req.from_budget_id).await?;
         let to_budget = BudgetRepo::get(pool, &req.to_budget_id).await?;
 
-        if from_budget.currency_code != to_budget.currency_code {
+        if !req.currency_code.eq_ignore_ascii_case(&from_budget.currency_code) {
             return Err(AppError::BadRequest(
-                format!("Currency mismatch: {} vs {}", from_budget.currency_code, to_budget.currency_code)
+                format!("Transfer amount must be in the sending budget currency {}", from_budget.currency_code)
             ));
         }
+        let fee_minor = req.fee_minor.unwrap_or(0);
+        let received = Self::received(pool, &from_budget, &to_budget, req.amount_minor, fee_minor, req.transfer_date, req.exchange_rate).await?;
+        // Schedules keep running into categories archived after they were set up
+        let allow_hidden = req.scheduled_transfer_id.is_some();
+        Self::validate_category(pool, &req.from_budget_id, &req.from_category_id, "expense", allow_hidden).await?;
+        Self::validate_category(pool, &req.to_budget_id, &req.to_category_id, "income", allow_hidden).await?;
+        if let Some(fee_category_id) = req.fee_category_id.as_deref().filter(|_| fee_minor > 0) {
+            Self::validate_category(pool, &req.from_budget_id, fee_category_id, "expense", allow_hidden).await?;
+        }
 
         let transfer_id = uuid::Uuid::new_v4().to_string();
         let from_entry_id = uuid::Uuid::new_v4().to_string();
         let to_entry_id = uuid::Uuid::new_v4().to_string();
+        let fee_entry_id = (fee_minor > 0).then(|| uuid::Uuid::new_v4().to_string());
 
         let mut tx = pool.begin().await?;
 
         sqlx::query(
-            "INSERT INTO budget_transfers 
-             (id, from_budget_id, to_budget_id, amount_minor, currency_code, transfer_date, note, created_by) 
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+            "INSERT INTO budget_transfers
+             (id, from_budget_id, to_budget_id, amount_minor, currency_code, to_amount_minor, to_currency_code, exchange_rate, fee_minor, transfer_date, note, created_by,
+              scheduled_transfer_id, scheduled_date)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(&transfer_id)
         .bind(&req.from_budget_id)
         .bind(&req.to_budget_id)
         .bind(req.amount_minor)
-        .bind(&req.currency_code)
+        .bind(&from_budget.currency_code)
+        .bind(received.base_amount_minor)
+        .bind(&to_budget.currency_code)
+        .bind(received.exchange_rate)
+        .bind(fee_minor)
         .bind(req.transfer_date)
         .bind(&req.note)
         .bind(user_id)
+        .bind(&req.scheduled_transfer_id)
+        .bind(req.scheduled_transfer_id.as_ref().map(|_| req.transfer_date))
         .execute(&mut *tx)
         .await?;
 
-        let transfer_note = req.note.clone().unwrap_or_else(|| {
-            format!("Transfer to {}", to_budget.name)
+        let (transfer_note, receive_note, fee_note) = Self::descriptions(req.note.as_deref(), &from_budget, &to_budget);
+
+        // Each leg is booked in its own budget currency
+        let legs = [
+            NewLeg {
+                entry_id: &from_entry_id,
+                budget_id: &req.from_budget_id,
+                category_id: &req.from_category_id,
+                kind: "expense",
+                leg: "from",
+                amount_minor: req.amount_minor,
+                currency_code: &from_budget.currency_code,
+                description: &transfer_note,
+            },
+            NewLeg {
+                entry_id: &to_entry_id,
+                budget_id: &req.to_budget_id,
+                category_id: &req.to_category_id,
+                kind: "income",
+                leg: "to",
+                amount_minor: received.base_amount_minor,
+                currency_code: &to_budget.currency_code,
+                description: &receive_note,
+            },
+        ];
+        let fee_leg = fee_entry_id.as_deref().map(|entry_id| NewLeg {
+            entry_id,
+            budget_id: &req.from_budget_id,
+            category_id: req.fee_category_id.as_deref().unwrap_or(&req.from_category_id),
+            kind: "expense",
+            leg: "fee",
+            amount_minor: fee_minor,
+            currency_code: &from_budget.currency_code,
+            description: &fee_note,
         });
-        let receive_note = format!("Transfer from {}", from_budget.name);
+        for leg in legs.into_iter().chain(fee_leg) {
+            Self::insert_leg(&mut tx, &transfer_id, req.transfer_date, user_id, leg).await?;
+        }
+
+        tx.commit().await?;
+
+        CategoryLimitService::invalidate_from(pool, &req.from_budget_id, req.transfer_date).await?;
+        CategoryLimitService::invalidate_from(pool, &req.to_budget_id, req.transfer_date).await?;
+
+        let transfer = TransferRepo::get_by_id(pool, &transfer_id).await?;
+
+        Ok(TransferWithEntries {
+            transfer,
+            from_entry_id,
+            to_entry_id,
+            fee_entry_id,
+            from_budget_name: from_budget.name,
+            to_budget_name: to_budget.name,
+        })
+    }
+
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<BudgetTransfer>, AppError> {
+        TransferRepo::list_by_budget(pool, budget_id).await
+    }
+
+    /// A transfer seen from one of its two budgets.
+    pub async fn get(pool: &DbPool, budget_id: &str, transfer_id: &str) -> Result<TransferWithEntries, AppError> {
+        let transfer = TransferRepo::get_by_id(pool, transfer_id).await?;
+        if transfer.from_budget_id != budget_id && transfer.to_budget_id != budget_id {
+            return Err(AppError::NotFound);
+        }
+        Self::with_entries(pool, transfer).await
+    }
+
+    /// Apply an edit to the transfer row and every entry it generated in one
+    /// transaction. The caller must be a contributor in both budgets.
+    pub async fn update(pool: &DbPool, user_id: &str, transfer_id: &str, req: UpdateTransferReq) -> Result<TransferWithEntries, AppError> {
+        let transfer = TransferRepo::get_by_id(pool, transfer_id).await?;
+        Self::ensure_both(pool, &transfer, user_id).await?;
+        let from_budget = BudgetRepo::get(pool, &transfer.from_budget_id).await?;
+        let to_budget = BudgetRepo::get(pool, &transfer.to_budget_id).await?;
+        let legs = TransferRepo::legs(pool, transfer_id).await?;
+        let leg = |name: &str| legs.iter().find(|l| l.leg(&transfer) == name);
+        let (from_leg, to_leg) = leg("from").zip(leg("to"))
+            .ok_or_else(|| AppError::BadRequest("A leg of this transfer was deleted; delete the transfer instead".to_string()))?;
+        let fee_leg = leg("fee");
+
+        let amount_minor = req.amount_minor.unwrap_or(transfer.amount_minor);
+        let transfer_date = req.transfer_date.unwrap_or(transfer.transfer_date);
+        let fee_minor = req.fee_minor.unwrap_or(transfer.fee_minor);
+        // The stored rate still holds unless the date moves
+        let rate = req.exchange_rate.or(transfer.exchange_rate.filter(|_| transfer_date == transfer.transfer_date));
+        let received = Self::received(pool, &from_budget, &to_budget, amount_minor, fee_minor, transfer_date, rate).await?;
+        let note = match req.note {
+            Some(note) => Some(note).filter(|n| !n.trim().is_empty()),
+            None => transfer.note.clone(),
+        };
+        let (transfer_note, receive_note, fee_note) = Self::descriptions(note.as_deref(), &from_budget, &to_budget);
+        let from_category_id = req.from_category_id.as_deref().unwrap_or(&from_leg.category_id);
+        let to_category_id = req.to_category_id.as_deref().unwrap_or(&to_leg.category_id);
+        let fee_category_id = req.fee_category_id.as_deref()
+            .or(fee_leg.map(|l| l.category_id.as_str()))
+            .unwrap_or(from_category_id);
+        // Categories the legs already use stay allowed even once archived
+        let keep_from = from_category_id == from_leg.category_id;
+        Self::validate_category(pool, &transfer.from_budget_id, from_category_id, "expense", keep_from).await?;
+        Self::validate_category(pool, &transfer.to_budget_id, to_category_id, "income", to_category_id == to_leg.category_id).await?;
+        if fee_minor > 0 {
+            let keep_fee = fee_leg.is_some_and(|l| l.category_id == fee_category_id) || (keep_from && fee_category_id == from_category_id);
+            Self::validate_category(pool, &transfer.from_budget_id, fee_category_id, "expense", keep_fee).await?;
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        let mut tx = pool.begin().await?;
 
         sqlx::query(
-            "INSERT INTO entries 
-             (id, budget_id, category_id, kind, amount_minor, currency_code, entry_date, description, created_by, transfer_id) 
-             VALUES (?, ?, ?, 'expense', ?, ?, ?, ?, ?, ?)"
+            "UPDATE budget_transfers
+             SET amount_minor = ?, to_amount_minor = ?, exchange_rate = ?, fee_minor = ?, transfer_date = ?, note = ?, updated_at = ?
+             WHERE id = ?"
         )
-        .bind(&from_entry_id)
-        .bind(&req.from_budget_id)
-        .bind(&req.from_category_id)
-        .bind(req.amount_minor)
-        .bind(&from_budget.currency_code)
-        .bind(req.transfer_date)
-        .bind(&transfer_note)
-        .bind(user_id)
-        .bind(&transfer_id)
+        .bind(amount_minor)
+        .bind(received.base_amount_minor)
+        .bind(received.exchange_rate)
+        .bind(fee_minor)
+        .bind(transfer_date)
+        .bind(&note)
+        .bind(now)
+        .bind(transfer_id)
         .execute(&mut *tx)
         .await?;
 
+        let mut changes = vec![
+            (&from_leg.entry_id, from_category_id, amount_minor, &transfer_note),
+            (&to_leg.entry_id, to_category_id, received.base_amount_minor, &receive_note),
+        ];
+        match fee_leg {
+            Some(fee_leg) if fee_minor > 0 => changes.push((&fee_leg.entry_id, fee_category_id, fee_minor, &fee_note)),
+            Some(fee_leg) => {
+                sqlx::query("UPDATE entries SET deleted_at = ?, updated_by = ?, updated_at = ? WHERE id = ?")
+                    .bind(now)
+                    .bind(user_id)
+                    .bind(now)
+                    .bind(&fee_leg.entry_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            None if fee_minor > 0 => {
+                Self::insert_leg(&mut tx, transfer_id, transfer_date, user_id, NewLeg {
+                    entry_id: &uuid::Uuid::new_v4().to_string(),
+                    budget_id: &transfer.from_budget_id,
+                    category_id: fee_category_id,
+                    kind: "expense",
+                    leg: "fee",
+                    amount_minor: fee_minor,
+                    currency_code: &from_budget.currency_code,
+                    description: &fee_note,
+                }).await?;
+            }
+            None => {}
+        }
+        for (entry_id, category_id, amount_minor, description) in changes {
+            sqlx::query(
+                "UPDATE entries
+                 SET category_id = ?, amount_minor = ?, base_amount_minor = ?, entry_date = ?, description = ?, updated_by = ?, updated_at = ?
+                 WHERE id = ?"
+            )
+            .bind(category_id)
+            .bind(amount_minor)
+            .bind(amount_minor)
+            .bind(transfer_date)
+            .bind(description)
+            .bind(user_id)
+            .bind(now)
+            .bind(entry_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        let since = transfer.transfer_date.min(transfer_date);
+        CategoryLimitService::invalidate_from(pool, &transfer.from_budget_id, since).await?;
+        CategoryLimitService::invalidate_from(pool, &transfer.to_budget_id, since).await?;
+
+        let transfer = TransferRepo::get_by_id(pool, transfer_id).await?;
+        Self::with_entries(pool, transfer).await
+    }
+
+    /// Reverse a transfer: the row and both legs (plus any fee) are soft-deleted together.
+    pub async fn delete(pool: &DbPool, user_id: &str, transfer_id: &str) -> Result<(), AppError> {
+        let transfer = TransferRepo::get_by_id(pool, transfer_id).await?;
+        Self::ensure_both(pool, &transfer, user_id).await?;
+        TransferRepo::delete(pool, transfer_id, user_id).await?;
+        CategoryLimitService::invalidate_from(pool, &transfer.from_budget_id, transfer.transfer_date).await?;
+        CategoryLimitService::invalidate_from(pool, &transfer.to_budget_id, transfer.transfer_date).await
+    }
+
+    /// A leg category must belong to the leg's budget, match its kind and,
+    /// unless `allow_hidden`, not be archived.
+    pub async fn validate_category(pool: &DbPool, budget_id: &str, category_id: &str, kind: &str, allow_hidden: bool) -> Result<(), AppError> {
+        let category = CategoryRepo::get_by_id(pool, budget_id, category_id).await
+            .map_err(|_| AppError::BadRequest(format!("Category {} not found in its budget", category_id)))?;
+        if category.kind != kind {
+            return Err(AppError::BadRequest(format!("Category '{}' is for {} entries", category.name, category.kind)));
+        }
+        if category.is_hidden && !allow_hidden {
+            return Err(AppError::BadRequest(format!("Category '{}' is archived", category.name)));
+        }
+        Ok(())
+    }
+
+    async fn ensure_both(pool: &DbPool, transfer: &BudgetTransfer, user_id: &str) -> Result<(), AppError> {
+        for budget_id in [&transfer.from_budget_id, &transfer.to_budget_id] {
+            crate::manager::biz::authz::ensure_role(pool, budget_id, user_id, Role::Contributor).await?;
+        }
+        Ok(())
+    }
+
+    /// Validate the amounts and convert the sent amount into the destination
+    /// budget currency.
+    async fn received(
+        pool: &DbPool,
+        from_budget: &Budget,
+        to_budget: &Budget,
+        amount_minor: i64,
+        fee_minor: i64,
+        on: chrono::NaiveDate,
+        exchange_rate: Option<f64>,
+    ) -> Result<Conversion, AppError> {
+        if amount_minor <= 0 {
+            return Err(AppError::BadRequest("Transfer amount must be positive".to_string()));
+        }
+        if fee_minor < 0 {
+            return Err(AppError::BadRequest("Transfer fee cannot be negative".to_string()));
+        }
+        if exchange_rate.is_some_and(|r| !r.is_finite() || r <= 0.0) {
+            return Err(AppError::BadRequest("Exchange rate must be positive".to_string()));
+        }
+        let received = ExchangeRateService::convert(pool, to_budget, &from_budget.currency_code, amount_minor, on, exchange_rate)
+            .await?
+            .ok_or_else(|| AppError::BadRequest(format!(
+                "No exchange rate from {} to {} on or before {}; add one or send exchange_rate",
+                from_budget.currency_code, to_budget.currency_code, on
+            )))?;
+        if received.base_amount_minor <= 0 {
+            return Err(AppError::BadRequest("Transfer amount is too small to convert".to_string()));
+        }
+        Ok(received)
+    }
+
+    /// Entry descriptions of the sending, receiving and fee legs.
+    fn descriptions(note: Option<&str>, from_budget: &Budget, to_budget: &Budget) -> (String, String, String) {
+        (
+            note.map(str::to_string).unwrap_or_else(|| format!("Transfer to {}", to_budget.name)),
+            format!("Transfer from {}", from_budget.name),
+            format!("Transfer fee to {}", to_budget.name),
+        )
+    }
+
+    async fn insert_leg(conn: &mut MySqlConnection, transfer_id: &str, entry_date: chrono::NaiveDate, user_id: &str, leg: NewLeg<'_>) -> Result<(), AppError> {
         sqlx::query(
-            "INSERT INTO entries 
-             (id, budget_id, category_id, kind, amount_minor, currency_code, entry_date, description, created_by, transfer_id) 
-             VALUES (?, ?, ?, 'income', ?, ?, ?, ?, ?, ?)"
+            "INSERT INTO entries
+             (id, budget_id, category_id, kind, amount_minor, currency_code, base_amount_minor, entry_date, description, created_by, transfer_id, transfer_leg)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
-        .bind(&to_entry_id)
-        .bind(&req.to_budget_id)
-        .bind(&req.to_category_id)
-        .bind(req.amount_minor)
-        .bind(&to_budget.currency_code)
-        .bind(req.transfer_date)
-        .bind(&receive_note)
+        .bind(leg.entry_id)
+        .bind(leg.budget_id)
+        .bind(leg.category_id)
+        .bind(leg.kind)
+        .bind(leg.amount_minor)
+        .bind(leg.currency_code)
+        .bind(leg.amount_minor)
+        .bind(entry_date)
+        .bind(leg.description)
         .bind(user_id)
-        .bind(&transfer_id)
-        .execute(&mut *tx)
+        .bind(transfer_id)
+        .bind(leg.leg)
+        .execute(conn)
         .await?;
+        Ok(())
+    }
 
-        tx.commit().await?;
-
-        let transfer = TransferRepo::get_by_id(pool, &transfer_id).await?;
-
+    async fn with_entries(pool: &DbPool, transfer: BudgetTransfer) -> Result<TransferWithEntries, AppError> {
+        let legs = TransferRepo::legs(pool, &transfer.id).await?;
+        let entry_id = |name: &str| legs.iter().find(|l| l.leg(&transfer) == name).map(|l| l.entry_id.clone());
+        let (from_entry_id, to_entry_id, fee_entry_id) = (entry_id("from"), entry_id("to"), entry_id("fee"));
+        let from_budget = BudgetRepo::get(pool, &transfer.from_budget_id).await?;
+        let to_budget = BudgetRepo::get(pool, &transfer.to_budget_id).await?;
         Ok(TransferWithEntries {
+            from_entry_id: from_entry_id.unwrap_or_default(),
+            to_entry_id: to_entry_id.unwrap_or_default(),
+            fee_entry_id,
             transfer,
-            from_entry_id,
-            to_entry_id,
             from_budget_name: from_budget.name,
             to_budget_name: to_budget.name,
         })
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Bump when the archive layout changes in a way older restores cannot read.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Self-contained copy of one budget. Ids are the source instance's and only
+/// serve as references inside the archive; users are referenced by email.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BudgetArchive {
+    pub version: u32,
+    pub exported_at: chrono::NaiveDateTime,
+    pub budget: ArchivedBudget,
+    pub members: Vec<ArchivedMember>,
+    pub categories: Vec<ArchivedCategory>,
+    #[serde(default)]
+    pub category_limits: Vec<ArchivedCategoryLimit>,
+    #[serde(default)]
+    pub payees: Vec<ArchivedPayee>,
+    #[serde(default)]
+    pub payee_aliases: Vec<ArchivedPayeeAlias>,
+    #[serde(default)]
+    pub recurring_entries: Vec<ArchivedRecurringEntry>,
+    pub entries: Vec<ArchivedEntry>,
+    #[serde(default)]
+    pub entry_splits: Vec<ArchivedEntrySplit>,
+    #[serde(default)]
+    pub tags: Vec<ArchivedTag>,
+    #[serde(default)]
+    pub entry_tags: Vec<ArchivedEntryTag>,
+    #[serde(default)]
+    pub transfers: Vec<ArchivedTransfer>,
+    #[serde(default)]
+    pub comments: Vec<ArchivedComment>,
+    #[serde(default)]
+    pub mentions: Vec<ArchivedMention>,
+    #[serde(default)]
+    pub attachments: Vec<ArchivedAttachment>,
+    #[serde(default)]
+    pub import_keys: Vec<ArchivedImportKey>,
+    #[serde(default)]
+    pub rules: Vec<ArchivedRule>,
+    #[serde(default)]
+    pub rule_tags: Vec<ArchivedRuleTag>,
+    #[serde(default)]
+    pub exchange_rates: Vec<ArchivedExchangeRate>,
+    #[serde(default)]
+    pub savings_goals: Vec<ArchivedSavingsGoal>,
+    #[serde(default)]
+    pub savings_goal_categories: Vec<ArchivedSavingsGoalCategory>,
+    #[serde(default)]
+    pub debts: Vec<ArchivedDebt>,
+    #[serde(default)]
+    pub holdings: Vec<ArchivedHolding>,
+    #[serde(default)]
+    pub investment_transactions: Vec<ArchivedInvestmentTransaction>,
+    #[serde(default)]
+    pub price_snapshots: Vec<ArchivedPriceSnapshot>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedBudget {
+    pub id: String,
+    pub name: String,
+    pub currency_code: String,
+    pub budget_type: String,
+    pub description: Option<String>,
+    pub archived: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedMember {
+    pub email: String,
+    pub role: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedCategory {
+    pub id: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    pub name: String,
+    pub kind: String,
+    pub is_hidden: bool,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub rollover_policy: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedCategoryLimit {
+    pub id: String,
+    pub category_id: String,
+    pub period_start: chrono::NaiveDate,
+    pub planned_minor: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedPayee {
+    pub id: String,
+    pub name: String,
+    pub default_category_id: Option<String>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedPayeeAlias {
+    pub payee_id: String,
+    pub alias: String,
+    pub normalized: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedRecurringEntry {
+    pub id: String,
+    pub category_id: String,
+    pub kind: String,
+    pub amount_minor: i64,
+    pub currency_code: String,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+    pub frequency: String,
+    pub interval_count: i32,
+    pub by_month_day: Option<i32>,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub occurrence_count: Option<i32>,
+    pub generated_through: Option<chrono::NaiveDate>,
+    pub active: bool,
+    pub created_by_email: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedEntry {
+    pub id: String,
+    pub category_id: String,
+    pub kind: String,
+    pub amount_minor: i64,
+    pub currency_code: String,
+    #[serde(default)]
+    pub base_amount_minor: Option<i64>, // missing in archives made before multi-currency
+    #[serde(default)]
+    pub exchange_rate: Option<f64>,
+    pub entry_date: chrono::NaiveDate,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+    #[serde(default)]
+    pub payee_id: Option<String>,
+    pub transfer_id: Option<String>,
+    pub recurring_id: Option<String>,
+    pub recurring_date: Option<chrono::NaiveDate>,
+    pub comment_count: Option<i32>,
+    pub attachment_count: Option<i32>,
+    pub created_by_email: String,
+    pub updated_by_email: Option<String>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedEntrySplit {
+    pub entry_id: String,
+    pub category_id: String,
+    pub amount_minor: i64,
+    #[serde(default)]
+    pub base_amount_minor: Option<i64>,
+    pub note: Option<String>,
+    pub position: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedTag {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedEntryTag {
+    pub entry_id: String,
+    pub tag_id: String,
+}
+
+/// Transfers reference a budget outside the archive, so they are kept for the
+/// record and their entry legs are restored as plain entries.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedTransfer {
+    pub id: String,
+    pub direction: String, // "out" or "in", seen from the archived budget
+    pub counterpart_budget_id: String,
+    pub counterpart_budget_name: String,
+    pub amount_minor: i64, // sent amount
+    pub currency_code: String,
+    #[serde(default)]
+    pub to_amount_minor: Option<i64>, // received amount; missing in archives made before cross-currency transfers
+    #[serde(default)]
+    pub to_currency_code: Option<String>,
+    #[serde(default)]
+    pub exchange_rate: Option<f64>,
+    #[serde(default)]
+    pub fee_minor: Option<i64>,
+    pub transfer_date: chrono::NaiveDate,
+    pub note: Option<String>,
+    pub created_by_email: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedComment {
+    pub id: String,
+    pub entry_id: String,
+    pub user_email: String,
+    pub comment_text: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedMention {
+    pub comment_id: String,
+    pub user_email: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedAttachment {
+    pub id: String,
+    pub entry_id: String,
+    pub comment_id: Option<String>,
+    pub user_email: String,
+    pub file_url: String,
+    pub file_name: String,
+    pub file_size: i32,
+    pub mime_type: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+    #[sqlx(default)]
+    pub blob: Option<String>, // path of the file inside a zip archive
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedImportKey {
+    pub dedupe_key: String,
+    pub entry_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedRule {
+    pub id: String,
+    pub name: String,
+    pub priority: i32,
+    pub active: bool,
+    pub counterparty_pattern: Option<String>,
+    pub counterparty_match: String,
+    pub description_regex: Option<String>,
+    pub min_amount_minor: Option<i64>,
+    pub max_amount_minor: Option<i64>,
+    pub kind: Option<String>,
+    pub set_category_id: Option<String>,
+    pub set_counterparty: Option<String>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedRuleTag {
+    pub rule_id: String,
+    pub tag_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedExchangeRate {
+    pub rate_date: chrono::NaiveDate,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: f64,
+    pub source: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedSavingsGoal {
+    pub id: String,
+    pub name: String,
+    pub target_amount_minor: i64,
+    pub target_date: Option<chrono::NaiveDate>,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+    pub created_by_email: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedSavingsGoalCategory {
+    pub goal_id: String,
+    pub category_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedDebt {
+    pub id: String,
+    pub name: String,
+    pub principal_minor: i64,
+    pub apr: f64,
+    pub minimum_payment_minor: i64,
+    pub due_day: i32,
+    pub category_id: Option<String>,
+    pub opened_on: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+    pub created_by_email: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedHolding {
+    pub id: String,
+    pub symbol: String,
+    pub name: Option<String>,
+    pub cost_method: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+/// `entry_id` is the cash entry of the trade, restored linked so it stays read-only.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedInvestmentTransaction {
+    pub id: String,
+    pub holding_id: String,
+    pub kind: String,
+    pub trade_date: chrono::NaiveDate,
+    pub quantity: f64,
+    pub price_minor: i64,
+    pub fee_minor: i64,
+    pub amount_minor: i64,
+    pub entry_id: Option<String>,
+    pub note: Option<String>,
+    pub created_by_email: String,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedPriceSnapshot {
+    pub symbol: String,
+    pub price_date: chrono::NaiveDate,
+    pub price_minor: i64,
+    pub source: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BackupQuery {
+    pub format: Option<String>, // "json" (default) or "zip" (with attachment files)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestoreReq {
+    pub file_data: String, // base64 encoded .json or .zip archive
+    pub name: Option<String>, // defaults to the archived budget name
+    pub dry_run: Option<bool>,
+}
+
+/// Old archive ids mapped to fresh ones and member emails resolved to local users.
+pub struct RestorePlan {
+    pub budget_id: String,
+    pub owner_id: String,
+    pub name: String,
+    pub ids: HashMap<String, String>,
+    pub users: HashMap<String, String>,
+    pub file_urls: HashMap<String, String>, // attachment id -> re-uploaded file url
+}
+
+impl RestorePlan {
+    pub fn id(&self, old: &str) -> String {
+        self.ids.get(old).cloned().unwrap_or_else(|| old.to_string())
+    }
+
+    /// Local user for an archived email; rows by unknown users go to the restoring owner.
+    pub fn user(&self, email: &str) -> String {
+        self.users.get(&email.to_lowercase()).cloned().unwrap_or_else(|| self.owner_id.clone())
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RestoreConflicts {
+    pub unknown_member_emails: Vec<String>,
+    pub reassigned_rows: usize,   // rows authored by unknown users, now owned by you
+    pub skipped_mentions: usize,  // mentions of unknown users
+    pub unlinked_transfers: usize, // transfer legs restored as plain entries
+    pub missing_attachment_files: usize, // attachments still pointing at the source storage
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreReport {
+    pub dry_run: bool,
+    pub budget_id: Option<String>,
+    pub budget_name: String,
+    pub categories: usize,
+    pub entries: usize,
+    pub comments: usize,
+    pub attachments: usize,
+    pub conflicts: RestoreConflicts,
+}
@@ -82,5 +82,6 @@ pub struct CreateBudgetReq {
     pub currency_code: Option<String>,
     pub budget_type: Option<BudgetType>,
     pub description: Option<String>,
+    pub template_id: Option<String>, // "builtin" or the id of a saved category template
 }
 
@@ -2,15 +2,68 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc};
 
+/// How a category's leftover (or overspent) amount carries into the next month.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RolloverPolicy {
+    None,
+    CarryPositive,
+    CarryBoth,
+}
+
+impl RolloverPolicy {
+    pub fn carry(&self, available_minor: i64) -> i64 {
+        match self {
+            RolloverPolicy::None => 0,
+            RolloverPolicy::CarryPositive => available_minor.max(0),
+            RolloverPolicy::CarryBoth => available_minor,
+        }
+    }
+}
+
+impl std::fmt::Display for RolloverPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RolloverPolicy::None => write!(f, "none"),
+            RolloverPolicy::CarryPositive => write!(f, "carry_positive"),
+            RolloverPolicy::CarryBoth => write!(f, "carry_both"),
+        }
+    }
+}
+
+impl std::str::FromStr for RolloverPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(RolloverPolicy::None),
+            "carry_positive" => Ok(RolloverPolicy::CarryPositive),
+            "carry_both" => Ok(RolloverPolicy::CarryBoth),
+            _ => Err(format!("Invalid rollover policy: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for RolloverPolicy {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
 #[derive(Debug, Serialize, FromRow)]
 pub struct Category {
     pub id: String,
     pub budget_id: String,
+    pub parent_id: Option<String>,
     pub name: String,
     pub kind: String,
     pub is_hidden: bool,
     pub color: Option<String>,
     pub icon: Option<String>,
+    #[sqlx(try_from = "String")]
+    pub rollover_policy: RolloverPolicy,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -19,16 +72,58 @@ pub struct Category {
 pub struct CreateCategoryReq { 
     pub name: String, 
     pub kind: String, 
+    pub parent_id: Option<String>, // must have the same kind
     pub is_hidden: Option<bool>,
     pub color: Option<String>,
     pub icon: Option<String>,
+    pub rollover_policy: Option<RolloverPolicy>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateCategoryReq { 
     pub name: Option<String>,
     pub kind: Option<String>, // Allow changing category type
+    pub parent_id: Option<String>, // "" moves the category to the top level
     pub is_hidden: Option<bool>,
     pub color: Option<String>,
     pub icon: Option<String>,
-}
\ No newline at end of file
+    pub rollover_policy: Option<RolloverPolicy>,
+}
+
+/// A category with its subcategories, as returned by the tree endpoint.
+#[derive(Debug, Serialize)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    pub category: Category,
+    pub children: Vec<CategoryNode>,
+}
+
+/// Income and expense booked directly on a category, and the totals including
+/// all of its subcategories.
+#[derive(Debug, Serialize)]
+pub struct CategorySummaryRow {
+    pub category_id: String,
+    pub category_name: String,
+    pub kind: String,
+    pub parent_id: Option<String>,
+    pub income_minor: i64,
+    pub expense_minor: i64,
+    pub total_income_minor: i64,
+    pub total_expense_minor: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeCategoryReq {
+    pub target_category_id: String,
+}
+
+/// What moved from the source category into the target before the source was removed.
+#[derive(Debug, Default, Serialize)]
+pub struct MergeCategoryReport {
+    pub target_category_id: String,
+    pub entries_moved: u64, // including soft-deleted entries
+    pub splits_moved: u64,
+    pub recurring_moved: u64,
+    pub subcategories_moved: u64,
+    pub limits_merged: u64,
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::manager::models::category::RolloverPolicy;
+
+/// Planned amount for a category. A limit applies from `period_start` (first day
+/// of a month) onwards until a later limit for the same category supersedes it.
+#[derive(Debug, Serialize, FromRow)]
+pub struct CategoryLimit {
+    pub id: String,
+    pub budget_id: String,
+    pub category_id: String,
+    pub period_start: chrono::NaiveDate,
+    pub planned_minor: i64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCategoryLimitReq {
+    pub period_start: chrono::NaiveDate,
+    pub planned_minor: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCategoryLimitReq {
+    pub period_start: Option<chrono::NaiveDate>,
+    pub planned_minor: Option<i64>,
+}
+
+/// Envelope result for one category in one month. Closed months are cached
+/// in `category_month_snapshots` so their rollover chain is not recomputed.
+#[derive(Debug, Clone, FromRow)]
+pub struct CategoryMonthSnapshot {
+    pub budget_id: String,
+    pub category_id: String,
+    pub month_start: chrono::NaiveDate,
+    pub planned_minor: i64,
+    pub spent_minor: i64,
+    pub rollover_minor: i64,
+    pub available_minor: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BudgetVsActualRow {
+    pub category_id: String,
+    pub category_name: String,
+    pub kind: String,
+    pub parent_id: Option<String>,
+    pub rollover_policy: RolloverPolicy,
+    pub planned_minor: i64,
+    pub spent_minor: i64,
+    pub remaining_minor: i64,
+    pub percent_used: Option<f64>,
+    pub rollover_minor: i64,
+    pub available_minor: i64,
+    // Including all subcategories
+    pub total_planned_minor: i64,
+    pub total_spent_minor: i64,
+    pub total_available_minor: i64,
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::manager::models::budget::BudgetType;
+
+/// A named list of categories used to seed new budgets. Built-in templates
+/// exist for every budget type in English and Vietnamese; users can save
+/// their own from an existing budget.
+#[derive(Debug, Serialize, FromRow)]
+pub struct CategoryTemplate {
+    pub id: String, // "builtin" for the built-in template of `budget_type`
+    pub name: String,
+    pub budget_type: Option<String>,
+    #[sqlx(default)]
+    pub builtin: bool,
+    pub created_at: Option<chrono::NaiveDateTime>,
+    #[sqlx(skip)]
+    pub categories: Vec<TemplateCategory>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TemplateCategory {
+    pub id: String,
+    #[serde(skip)]
+    pub template_id: String,
+    pub parent_id: Option<String>, // id of another category in the same template
+    pub name: String,
+    pub kind: String,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub rollover_policy: String,
+    pub position: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateFilter {
+    pub budget_type: Option<BudgetType>,
+    pub locale: Option<String>, // defaults to the user's locale
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveTemplateReq {
+    pub name: String,
+}
+
+struct BuiltinCategory {
+    en: &'static str,
+    vi: &'static str,
+    kind: &'static str,
+    color: &'static str,
+    icon: &'static str,
+}
+
+const fn cat(en: &'static str, vi: &'static str, kind: &'static str, color: &'static str, icon: &'static str) -> BuiltinCategory {
+    BuiltinCategory { en, vi, kind, color, icon }
+}
+
+const STANDARD: &[BuiltinCategory] = &[
+    cat("Salary", "Lương", "income", "#66BB6A", "Money"),
+    cat("Bonus", "Thưởng", "income", "#26A69A", "Award"),
+    cat("Other income", "Thu nhập khác", "income", "#9CCC65", "Business"),
+    cat("Food & Dining", "Ăn uống", "expense", "#FF7043", "Food"),
+    cat("Groceries", "Đi chợ", "expense", "#8BC34A", "Shopping"),
+    cat("Transport", "Đi lại", "expense", "#42A5F5", "Car"),
+    cat("Housing", "Nhà ở", "expense", "#8D6E63", "Home"),
+    cat("Utilities", "Điện nước", "expense", "#FFCA28", "Utilities"),
+    cat("Health", "Sức khỏe", "expense", "#EF5350", "Health"),
+    cat("Entertainment", "Giải trí", "expense", "#AB47BC", "Entertainment"),
+    cat("Shopping", "Mua sắm", "expense", "#EC407A", "ShoppingBag"),
+    cat("Education", "Giáo dục", "expense", "#5C6BC0", "Education"),
+];
+
+const SAVING: &[BuiltinCategory] = &[
+    cat("Deposits", "Tiền gửi vào", "income", "#66BB6A", "Savings"),
+    cat("Interest", "Tiền lãi", "income", "#26A69A", "TrendingUp"),
+    cat("Withdrawals", "Rút tiền", "expense", "#FFA726", "Banking"),
+    cat("Fees", "Phí", "expense", "#BDBDBD", "FileText"),
+];
+
+const DEBT: &[BuiltinCategory] = &[
+    cat("Borrowed", "Khoản vay", "income", "#42A5F5", "Banking"),
+    cat("Principal repayment", "Trả nợ gốc", "expense", "#EF5350", "Money"),
+    cat("Interest", "Trả lãi", "expense", "#FF7043", "TrendingUp"),
+    cat("Fees", "Phí", "expense", "#BDBDBD", "FileText"),
+];
+
+const INVEST: &[BuiltinCategory] = &[
+    cat("Dividends", "Cổ tức", "income", "#66BB6A", "Money"),
+    cat("Interest", "Tiền lãi", "income", "#26A69A", "Savings"),
+    cat("Capital gains", "Lãi vốn", "income", "#9CCC65", "Investment"),
+    cat("Contributions", "Nạp tiền đầu tư", "expense", "#42A5F5", "Investment"),
+    cat("Trading fees", "Phí giao dịch", "expense", "#BDBDBD", "FileText"),
+    cat("Taxes", "Thuế", "expense", "#EF5350", "Business"),
+];
+
+const SHARING: &[BuiltinCategory] = &[
+    cat("Contributions", "Góp quỹ", "income", "#66BB6A", "Family"),
+    cat("Rent", "Tiền nhà", "expense", "#8D6E63", "Home"),
+    cat("Utilities", "Điện nước", "expense", "#FFCA28", "Utilities"),
+    cat("Groceries", "Đi chợ", "expense", "#8BC34A", "Shopping"),
+    cat("Household", "Đồ dùng gia đình", "expense", "#78909C", "Package"),
+    cat("Eating out", "Ăn ngoài", "expense", "#FF7043", "Food"),
+];
+
+impl CategoryTemplate {
+    /// Built-in template for a budget type. Any locale other than "vi" gets English names.
+    pub fn builtin(budget_type: &BudgetType, locale: &str) -> CategoryTemplate {
+        let vi = locale.to_lowercase().starts_with("vi");
+        let (items, name) = match budget_type {
+            BudgetType::Standard => (STANDARD, if vi { "Ngân sách chuẩn" } else { "Standard budget" }),
+            BudgetType::Saving => (SAVING, if vi { "Tiết kiệm" } else { "Savings" }),
+            BudgetType::Debt => (DEBT, if vi { "Khoản nợ" } else { "Debt" }),
+            BudgetType::Invest => (INVEST, if vi { "Đầu tư" } else { "Investments" }),
+            BudgetType::Sharing => (SHARING, if vi { "Chi tiêu chung" } else { "Shared expenses" }),
+        };
+        CategoryTemplate {
+            id: "builtin".to_string(),
+            name: name.to_string(),
+            budget_type: Some(budget_type.to_string()),
+            builtin: true,
+            created_at: None,
+            categories: items.iter()
+                .enumerate()
+                .map(|(i, c)| TemplateCategory {
+                    id: format!("builtin-{}", i + 1),
+                    template_id: "builtin".to_string(),
+                    parent_id: None,
+                    name: (if vi { c.vi } else { c.en }).to_string(),
+                    kind: c.kind.to_string(),
+                    color: Some(c.color.to_string()),
+                    icon: Some(c.icon.to_string()),
+                    rollover_policy: "none".to_string(),
+                    position: i as i32,
+                })
+                .collect(),
+        }
+    }
+}
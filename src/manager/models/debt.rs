@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Debt recorded in a debt budget. Expense entries in `category_id` (and its
+/// subcategories) from `opened_on` on count as payments; income entries in
+/// it, such as new charges, add back to the balance.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Debt {
+    pub id: String,
+    pub budget_id: String,
+    pub name: String,
+    pub principal_minor: i64, // in the budget currency
+    pub apr: f64,
+    pub minimum_payment_minor: i64,
+    pub due_day: i32,
+    pub category_id: Option<String>,
+    pub opened_on: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+    #[sqlx(skip)]
+    pub paid_minor: i64,
+    #[sqlx(skip)]
+    pub balance_minor: i64,
+    pub created_by: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDebtReq {
+    pub name: String,
+    pub principal_minor: i64,
+    pub apr: f64,
+    pub minimum_payment_minor: Option<i64>,
+    pub due_day: Option<i32>, // default 1
+    pub category_id: Option<String>,
+    pub opened_on: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDebtReq {
+    pub name: Option<String>,
+    pub principal_minor: Option<i64>,
+    pub apr: Option<f64>,
+    pub minimum_payment_minor: Option<i64>,
+    pub due_day: Option<i32>,
+    pub category_id: Option<String>,
+    pub opened_on: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PayoffStrategy {
+    /// Extra money goes to the smallest balance first.
+    Snowball,
+    /// Extra money goes to the highest interest rate first.
+    Avalanche,
+}
+
+/// `extra_monthly_minor` is paid on top of the minimums; without `strategy`
+/// both strategies are simulated.
+#[derive(Debug, Deserialize)]
+pub struct PayoffPlanQuery {
+    pub extra_monthly_minor: Option<i64>,
+    pub strategy: Option<PayoffStrategy>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayoffPlan {
+    pub currency_code: String,
+    pub extra_monthly_minor: i64,
+    pub monthly_payment_minor: i64, // all minimums plus the extra amount
+    pub starting_balance_minor: i64,
+    pub strategies: Vec<StrategyPlan>,
+}
+
+/// `paid_off` is false when the payments do not outpace the interest, or the
+/// debts are not cleared within the simulated horizon.
+#[derive(Debug, Serialize)]
+pub struct StrategyPlan {
+    pub strategy: PayoffStrategy,
+    pub paid_off: bool,
+    pub months: u32,
+    pub payoff_date: Option<chrono::NaiveDate>,
+    pub total_interest_minor: i64,
+    pub total_paid_minor: i64,
+    pub debts: Vec<DebtPayoff>,
+    pub schedule: Vec<PlanMonth>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DebtPayoff {
+    pub debt_id: String,
+    pub name: String,
+    pub starting_balance_minor: i64,
+    pub interest_minor: i64,
+    pub paid_minor: i64,
+    pub payoff_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlanMonth {
+    pub month_start: chrono::NaiveDate,
+    pub payments: Vec<PlanPayment>,
+    pub remaining_balance_minor: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlanPayment {
+    pub debt_id: String,
+    pub due_date: chrono::NaiveDate,
+    pub payment_minor: i64,
+    pub interest_minor: i64,
+    pub principal_minor: i64,
+    pub balance_minor: i64,
+}
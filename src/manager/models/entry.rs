@@ -1,6 +1,7 @@
 
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::manager::models::tag::EntryTag;
 
 #[derive(Debug, Serialize, FromRow)]
 pub struct Entry {
@@ -8,11 +9,15 @@ pub struct Entry {
     pub budget_id: String,
     pub category_id: String,
     pub kind: String,
-    pub amount_minor: i64,
+    pub amount_minor: i64, // in currency_code
     pub currency_code: String,
+    pub base_amount_minor: i64, // in the budget currency; what reports add up
+    pub exchange_rate: Option<f64>, // budget currency units per currency_code unit
     pub entry_date: chrono::NaiveDate,
     pub description: Option<String>,
     pub counterparty: Option<String>,
+    pub payee_id: Option<String>,
+    pub transfer_id: Option<String>, // set on transfer legs, which change only through the transfer
     pub created_by: String,
     pub updated_by: Option<String>,
     pub created_at: chrono::NaiveDateTime,
@@ -25,6 +30,29 @@ pub struct Entry {
     // Comment and attachment counts
     pub comment_count: Option<i32>,
     pub attachment_count: Option<i32>,
+    // Line items when the entry is split across categories
+    #[sqlx(skip)]
+    pub splits: Vec<EntrySplit>,
+    #[sqlx(skip)]
+    pub tags: Vec<EntryTag>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct EntrySplit {
+    pub id: String,
+    pub entry_id: String,
+    pub category_id: String,
+    pub amount_minor: i64,
+    pub base_amount_minor: i64,
+    pub note: Option<String>,
+    pub position: i32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EntrySplitReq {
+    pub category_id: String,
+    pub amount_minor: i64,
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,10 +60,38 @@ pub struct CreateEntryReq {
     pub category_id: Option<String>,
     pub kind: String,
     pub amount_minor: i64,
-    pub currency_code: Option<String>,
+    pub currency_code: Option<String>, // defaults to the budget currency
+    pub exchange_rate: Option<f64>, // overrides the budget's rate table for a foreign currency
     pub entry_date: chrono::NaiveDate,
     pub description: Option<String>,
     pub counterparty: Option<String>,
+    pub splits: Option<Vec<EntrySplitReq>>, // amounts must sum to amount_minor
+    pub tag_ids: Option<Vec<String>>,
+    #[serde(skip_deserializing)]
+    pub payee_id: Option<String>, // resolved from counterparty
+    #[serde(skip_deserializing)]
+    pub base_amount_minor: i64,
     #[serde(skip_deserializing)]
     pub created_by: String,
-}
\ No newline at end of file
+}
+
+/// Move every non-deleted entry matching the filter to `target_category_id`.
+/// Only entries of the target category's kind are touched.
+#[derive(Debug, Deserialize)]
+pub struct RecategorizeReq {
+    pub target_category_id: String,
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+    pub category_id: Option<String>,
+    pub member_id: Option<String>,
+    pub search: Option<String>,
+    pub tags: Option<String>, // comma-separated tag ids
+    pub tag_match: Option<String>, // "any" (default), "all"
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecategorizeReport {
+    pub moved: u64,
+    pub split_lines_moved: u64,
+    pub skipped_split: u64, // split entries without a line in the filtered category
+}
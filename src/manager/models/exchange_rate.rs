@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One unit of `base_currency` is worth `rate` units of `quote_currency` on `rate_date`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ExchangeRate {
+    pub id: String,
+    pub budget_id: String,
+    pub rate_date: chrono::NaiveDate,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: f64,
+    pub source: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Adds the rate, or replaces the one already stored for the same pair and date.
+#[derive(Debug, Deserialize)]
+pub struct SaveExchangeRateReq {
+    pub rate_date: chrono::NaiveDate,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExchangeRateFilter {
+    pub base: Option<String>,
+    pub quote: Option<String>,
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+}
+
+/// `file_data` is base64. CSV files need `date,base,quote,rate` columns (header
+/// row required); "ecb" takes the ECB euro reference rates XML (daily, 90 day or
+/// historical feed).
+#[derive(Debug, Deserialize)]
+pub struct ImportExchangeRatesReq {
+    pub format: String, // "csv", "ecb"
+    pub file_data: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportExchangeRatesReport {
+    pub imported: usize,
+    pub currencies: Vec<String>,
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+}
+
+/// A parsed rate before it is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateRow {
+    pub rate_date: chrono::NaiveDate,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: f64,
+}
+
+/// Amount of an entry in the budget currency and the rate used to get it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conversion {
+    pub base_amount_minor: i64,
+    pub exchange_rate: Option<f64>, // None when the entry is in the budget currency
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "xlsx" => Ok(ExportFormat::Xlsx),
+            _ => Err(format!("Invalid export format: {}", s)),
+        }
+    }
+}
+
+/// Same filters as the entry list, without pagination: exports cover every match.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<String>, // "csv" (default), "json", "xlsx"
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+    pub kind: Option<String>,
+    pub category_id: Option<String>,
+    pub member_id: Option<String>,
+    pub search: Option<String>,
+    pub tags: Option<String>, // comma-separated tag ids
+    pub tag_match: Option<String>, // "any" (default), "all"
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ExportRow {
+    pub id: String,
+    pub entry_date: chrono::NaiveDate,
+    pub kind: String,
+    pub category_id: String,
+    pub category_name: Option<String>,
+    pub amount_minor: i64,
+    #[sqlx(skip)]
+    pub amount: String, // decimal rendering of amount_minor in the entry's currency
+    pub currency_code: String,
+    pub base_amount_minor: i64, // in the budget currency
+    pub exchange_rate: Option<f64>,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+    #[serde(rename = "member_id")]
+    pub created_by: String,
+    pub member_name: String,
+    pub comment_count: Option<i32>,
+    pub attachment_count: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalFormat {
+    Hledger,
+    Beancount,
+}
+
+impl JournalFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            JournalFormat::Hledger => "journal",
+            JournalFormat::Beancount => "beancount",
+        }
+    }
+}
+
+impl std::str::FromStr for JournalFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hledger" | "ledger" | "journal" => Ok(JournalFormat::Hledger),
+            "beancount" => Ok(JournalFormat::Beancount),
+            _ => Err(format!("Invalid journal format: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JournalQuery {
+    pub format: Option<String>, // "hledger" (default), "beancount"
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+}
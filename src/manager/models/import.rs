@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+/// Column mapping for CSV imports. Columns are referenced by header name,
+/// or by zero-based index when the file has no header row.
+#[derive(Debug, Deserialize)]
+pub struct CsvColumnMapping {
+    pub has_header: Option<bool>,
+    pub delimiter: Option<char>,
+    pub date_column: String,
+    pub date_format: Option<String>, // chrono format, default "%Y-%m-%d"
+    pub amount_column: Option<String>, // signed amount: negative is expense
+    pub debit_column: Option<String>,  // expense amounts
+    pub credit_column: Option<String>, // income amounts
+    pub kind_column: Option<String>,   // "income"/"expense" (or credit/debit), overrides the sign
+    pub decimal_separator: Option<char>,
+    pub description_column: Option<String>,
+    pub counterparty_column: Option<String>,
+    pub category_column: Option<String>, // category name, matched case-insensitively
+    pub id_column: Option<String>,       // bank transaction id, used to skip re-imported rows
+    pub default_category_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CsvImportReq {
+    pub file_data: String, // base64 encoded CSV
+    pub mapping: CsvColumnMapping,
+    pub dry_run: Option<bool>,
+}
+
+/// OFX/QFX and QIF uploads. `date_format` and `decimal_separator` only apply
+/// to QIF, whose dates and amounts follow the exporting application's locale.
+#[derive(Debug, Deserialize)]
+pub struct StatementImportReq {
+    pub file_data: String, // base64 encoded statement
+    pub date_format: Option<String>, // chrono format, default "%m/%d/%Y"
+    pub decimal_separator: Option<char>,
+    pub default_category_id: Option<String>,
+    pub dry_run: Option<bool>,
+}
+
+/// One statement line after parsing, before it is validated against the budget.
+#[derive(Debug, Clone)]
+pub struct ParsedTransaction {
+    pub row: usize,
+    pub entry_date: chrono::NaiveDate,
+    pub kind: String,
+    pub amount_minor: i64,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+    pub category_name: Option<String>,
+    pub dedupe_key: Option<String>, // e.g. "ofx:<account>:<fitid>"
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportRowError {
+    pub row: usize,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ImportRowError {
+    pub fn new(row: usize, field: Option<&str>, message: impl Into<String>) -> Self {
+        Self { row, field: field.map(|f| f.to_string()), message: message.into() }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportedRow {
+    pub row: usize,
+    pub entry_date: chrono::NaiveDate,
+    pub kind: String,
+    pub amount_minor: i64,
+    pub category_id: String,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+    pub tag_ids: Vec<String>,
+    pub rule_ids: Vec<String>, // rules that matched the row
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub committed: bool,
+    pub total_rows: usize,
+    pub valid_rows: usize,
+    pub imported: usize,
+    pub rows: Vec<ImportedRow>,
+    pub duplicates: Vec<usize>, // rows skipped because they were imported before
+    pub errors: Vec<ImportRowError>,
+}
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// How the cost of sold units is taken out of a holding's cost basis.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CostMethod {
+    /// Oldest lots are sold first.
+    Fifo,
+    /// Every unit carries the average cost of the units held.
+    Average,
+}
+
+impl std::fmt::Display for CostMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CostMethod::Fifo => write!(f, "fifo"),
+            CostMethod::Average => write!(f, "average"),
+        }
+    }
+}
+
+impl std::str::FromStr for CostMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fifo" => Ok(CostMethod::Fifo),
+            "average" => Ok(CostMethod::Average),
+            _ => Err(format!("Invalid cost method: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for CostMethod {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeKind {
+    Buy,
+    Sell,
+    Dividend,
+}
+
+impl std::fmt::Display for TradeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradeKind::Buy => write!(f, "buy"),
+            TradeKind::Sell => write!(f, "sell"),
+            TradeKind::Dividend => write!(f, "dividend"),
+        }
+    }
+}
+
+impl std::str::FromStr for TradeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "buy" => Ok(TradeKind::Buy),
+            "sell" => Ok(TradeKind::Sell),
+            "dividend" => Ok(TradeKind::Dividend),
+            _ => Err(format!("Invalid transaction kind: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for TradeKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// A security held in an invest budget. Amounts are in the budget currency.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Holding {
+    pub id: String,
+    pub budget_id: String,
+    pub symbol: String,
+    pub name: Option<String>,
+    #[sqlx(try_from = "String")]
+    pub cost_method: CostMethod,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+impl Holding {
+    /// Symbols are stored trimmed and uppercased; `None` when empty, too long or
+    /// containing whitespace.
+    pub fn normalize_symbol(raw: &str) -> Option<String> {
+        let symbol = raw.trim().to_uppercase();
+        (!symbol.is_empty() && symbol.len() <= 32 && !symbol.chars().any(char::is_whitespace)).then_some(symbol)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateHoldingReq {
+    pub symbol: String,
+    pub name: Option<String>,
+    pub cost_method: Option<CostMethod>, // default fifo
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateHoldingReq {
+    pub symbol: Option<String>,
+    pub name: Option<String>,
+    pub cost_method: Option<CostMethod>,
+}
+
+/// `amount_minor` is the cash that moved: paid for a buy, received for a sell
+/// or dividend, fees included. `entry_id` is that cash as an entry of the budget.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct InvestmentTransaction {
+    pub id: String,
+    pub budget_id: String,
+    pub holding_id: String,
+    #[sqlx(try_from = "String")]
+    pub kind: TradeKind,
+    pub trade_date: chrono::NaiveDate,
+    pub quantity: f64,
+    pub price_minor: i64, // per unit
+    pub fee_minor: i64,
+    pub amount_minor: i64,
+    pub entry_id: Option<String>,
+    pub note: Option<String>,
+    pub created_by: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Buys and sells need `quantity` and `price_minor`; dividends need
+/// `amount_minor`, the gross payout. `category_id` files the cash entry: an
+/// expense category for buys, an income category for sells and dividends.
+#[derive(Debug, Deserialize)]
+pub struct CreateInvestmentTransactionReq {
+    pub kind: TradeKind,
+    pub trade_date: chrono::NaiveDate,
+    pub quantity: Option<f64>,
+    pub price_minor: Option<i64>,
+    pub amount_minor: Option<i64>,
+    pub fee_minor: Option<i64>,
+    pub category_id: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PriceSnapshot {
+    pub id: String,
+    pub budget_id: String,
+    pub symbol: String,
+    pub price_date: chrono::NaiveDate,
+    pub price_minor: i64,
+    pub source: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Adds the price, or replaces the one already stored for the symbol and date.
+#[derive(Debug, Deserialize)]
+pub struct SavePriceReq {
+    pub symbol: String,
+    pub price_date: chrono::NaiveDate,
+    pub price_minor: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceFilter {
+    pub symbol: Option<String>,
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+}
+
+/// `file_data` is base64 CSV with `date,symbol,price` columns (header row
+/// required); prices are decimals in the budget currency.
+#[derive(Debug, Deserialize)]
+pub struct ImportPricesReq {
+    pub file_data: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportPricesReport {
+    pub imported: usize,
+    pub symbols: Vec<String>,
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+}
+
+/// A parsed price before it is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceRow {
+    pub price_date: chrono::NaiveDate,
+    pub symbol: String,
+    pub price_minor: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortfolioQuery {
+    pub as_of: Option<chrono::NaiveDate>, // default today
+}
+
+/// Units held and what they cost, after replaying a holding's transactions.
+#[derive(Debug, Clone, Default)]
+pub struct Position {
+    pub quantity: f64,
+    pub cost_basis_minor: i64,
+    pub realized_pnl_minor: i64,
+    pub dividends_minor: i64,
+    pub fees_minor: i64,
+}
+
+/// Market values use the latest price on or before `as_of`; holdings without
+/// one have no market value and are listed in `unpriced_symbols`.
+#[derive(Debug, Serialize)]
+pub struct PortfolioSummary {
+    pub currency_code: String,
+    pub as_of: chrono::NaiveDate,
+    pub cost_basis_minor: i64,
+    pub market_value_minor: i64,
+    pub unrealized_pnl_minor: i64,
+    pub realized_pnl_minor: i64,
+    pub dividends_minor: i64,
+    pub fees_minor: i64,
+    pub unpriced_symbols: Vec<String>,
+    pub holdings: Vec<HoldingPosition>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HoldingPosition {
+    pub holding_id: String,
+    pub symbol: String,
+    pub name: Option<String>,
+    pub cost_method: CostMethod,
+    pub quantity: f64,
+    pub cost_basis_minor: i64,
+    pub average_cost_minor: Option<i64>, // per unit
+    pub price_minor: Option<i64>,
+    pub price_date: Option<chrono::NaiveDate>,
+    pub market_value_minor: Option<i64>,
+    pub unrealized_pnl_minor: Option<i64>,
+    pub realized_pnl_minor: i64, // sells net of fees, less the cost of the units sold
+    pub dividends_minor: i64, // net of fees
+    pub fees_minor: i64,
+}
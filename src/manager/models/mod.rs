@@ -1,10 +1,25 @@
 pub mod user;
 pub mod budget;
 pub mod category;
+pub mod debt;
 pub mod entry;
 pub mod role;
 pub mod member;
 pub mod google_auth;
 pub mod comment;
 pub mod notification;
-pub mod transfer;
\ No newline at end of file
+pub mod transfer;
+pub mod category_limit;
+pub mod recurrence;
+pub mod recurring;
+pub mod savings_goal;
+pub mod import;
+pub mod export;
+pub mod backup;
+pub mod tag;
+pub mod category_template;
+pub mod rule;
+pub mod payee;
+pub mod exchange_rate;
+pub mod investment;
+pub mod scheduled_transfer;
\ No newline at end of file
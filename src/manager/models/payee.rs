@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A counterparty of the budget. Free-text counterparties on entries resolve
+/// to a payee through its aliases, so "Grab", "GRAB*VN" and "grab food" can
+/// all be one payee.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Payee {
+    pub id: String,
+    pub budget_id: String,
+    pub name: String,
+    pub default_category_id: Option<String>, // used when a new entry has no category
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    #[sqlx(skip)]
+    pub aliases: Vec<PayeeAlias>,
+}
+
+/// Alternative spelling of a payee. The payee's own name is always one of them.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PayeeAlias {
+    pub id: String,
+    #[serde(skip)]
+    pub payee_id: String,
+    pub alias: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePayeeReq {
+    pub name: String,
+    pub default_category_id: Option<String>,
+    pub aliases: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePayeeReq {
+    pub name: Option<String>, // the old name stays as an alias
+    pub default_category_id: Option<String>, // "" clears it
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddAliasReq {
+    pub alias: String,
+}
+
+/// Fold the source payee into `target_payee_id`: its entries and aliases move over.
+#[derive(Debug, Deserialize)]
+pub struct MergePayeeReq {
+    pub target_payee_id: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MergePayeeReport {
+    pub target_payee_id: String,
+    pub entries_moved: u64,
+    pub aliases_moved: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayeeSearch {
+    pub q: Option<String>,
+    pub limit: Option<u32>, // default 10, at most 50
+}
+
+/// Autocomplete match, most used payees first.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PayeeSuggestion {
+    pub id: String,
+    pub name: String,
+    pub default_category_id: Option<String>,
+    pub entry_count: i64,
+}
+
+/// Totals of the non-deleted entries of one payee within the report range.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PayeeSummaryRow {
+    pub payee_id: String,
+    pub name: String,
+    pub entry_count: i64,
+    pub income_minor: i64,
+    pub expense_minor: i64,
+    pub last_seen: Option<chrono::NaiveDate>,
+}
+
+impl Payee {
+    /// Key aliases are matched on: trimmed, lowercased, inner whitespace collapsed.
+    pub fn normalize(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+    }
+}
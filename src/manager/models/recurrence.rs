@@ -0,0 +1,125 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl std::fmt::Display for Frequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Frequency::Daily => write!(f, "daily"),
+            Frequency::Weekly => write!(f, "weekly"),
+            Frequency::Monthly => write!(f, "monthly"),
+            Frequency::Yearly => write!(f, "yearly"),
+        }
+    }
+}
+
+impl std::str::FromStr for Frequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(Frequency::Daily),
+            "weekly" => Ok(Frequency::Weekly),
+            "monthly" => Ok(Frequency::Monthly),
+            "yearly" => Ok(Frequency::Yearly),
+            _ => Err(format!("Invalid frequency: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Frequency {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// RRULE-like recurrence: FREQ, INTERVAL, BYMONTHDAY and UNTIL/COUNT.
+/// Month days past the end of a month (e.g. 31 in April) fall on the last day.
+#[derive(Debug, Clone)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_month_day: Option<u32>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub count: Option<u32>,
+}
+
+impl RecurrenceRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval == 0 {
+            return Err("Interval must be at least 1".to_string());
+        }
+        if let Some(day) = self.by_month_day {
+            if !(1..=31).contains(&day) {
+                return Err("by_month_day must be between 1 and 31".to_string());
+            }
+            if !matches!(self.frequency, Frequency::Monthly | Frequency::Yearly) {
+                return Err("by_month_day only applies to monthly or yearly rules".to_string());
+            }
+        }
+        if let Some(end) = self.end_date {
+            if end < self.start_date {
+                return Err("End date must not be before start date".to_string());
+            }
+        }
+        if self.count == Some(0) {
+            return Err("Count must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Occurrences falling within `from..=to`, honouring the end date and count.
+    pub fn occurrences_between(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        self.iter().skip_while(|d| *d < from).take_while(|d| *d <= to).collect()
+    }
+
+    /// The next `n` occurrences on or after `from`.
+    pub fn next_occurrences(&self, from: NaiveDate, n: usize) -> Vec<NaiveDate> {
+        self.iter().skip_while(|d| *d < from).take(n).collect()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        let start = self.start_date;
+        (0u32..)
+            .map_while(move |k| self.nth_candidate(k))
+            .filter(move |d| *d >= start)
+            .take_while(move |d| self.end_date.is_none_or(|end| *d <= end))
+            .take(self.count.map_or(usize::MAX, |c| c as usize))
+    }
+
+    fn nth_candidate(&self, k: u32) -> Option<NaiveDate> {
+        let step = k.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => self.start_date.checked_add_days(chrono::Days::new(step as u64)),
+            Frequency::Weekly => self.start_date.checked_add_days(chrono::Days::new(step as u64 * 7)),
+            Frequency::Monthly => {
+                let months = self.start_date.month0().checked_add(step)?;
+                let year = self.start_date.year() + (months / 12) as i32;
+                Self::clamped_date(year, months % 12 + 1, self.day())
+            }
+            Frequency::Yearly => {
+                let year = self.start_date.year().checked_add(step as i32)?;
+                Self::clamped_date(year, self.start_date.month(), self.day())
+            }
+        }
+    }
+
+    fn day(&self) -> u32 {
+        self.by_month_day.unwrap_or(self.start_date.day())
+    }
+
+    fn clamped_date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        (1..=day).rev().find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+    }
+}
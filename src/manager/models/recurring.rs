@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::manager::models::recurrence::{Frequency, RecurrenceRule};
+
+/// Template that the scheduler materializes into `entries` rows.
+#[derive(Debug, Serialize, FromRow)]
+pub struct RecurringEntry {
+    pub id: String,
+    pub budget_id: String,
+    pub category_id: String,
+    pub kind: String,
+    pub amount_minor: i64,
+    pub currency_code: String,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+    #[sqlx(try_from = "String")]
+    pub frequency: Frequency,
+    pub interval_count: i32,
+    pub by_month_day: Option<i32>,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub occurrence_count: Option<i32>,
+    pub generated_through: Option<chrono::NaiveDate>,
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl RecurringEntry {
+    pub fn rule(&self) -> RecurrenceRule {
+        RecurrenceRule {
+            frequency: self.frequency,
+            interval: self.interval_count.max(1) as u32,
+            by_month_day: self.by_month_day.map(|d| d.max(0) as u32),
+            start_date: self.start_date,
+            end_date: self.end_date,
+            count: self.occurrence_count.map(|c| c.max(0) as u32),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRecurringEntryReq {
+    pub category_id: String,
+    pub kind: String,
+    pub amount_minor: i64,
+    pub currency_code: Option<String>,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+    pub frequency: Frequency,
+    pub interval_count: Option<i32>,
+    pub by_month_day: Option<i32>,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub occurrence_count: Option<i32>,
+    #[serde(skip_deserializing)]
+    pub created_by: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRecurringEntryReq {
+    pub category_id: Option<String>,
+    pub kind: Option<String>,
+    pub amount_minor: Option<i64>,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+    pub frequency: Option<Frequency>,
+    pub interval_count: Option<i32>,
+    pub by_month_day: Option<i32>,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub occurrence_count: Option<i32>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecurringPreview {
+    pub recurring_id: String,
+    pub occurrences: Vec<chrono::NaiveDate>,
+}
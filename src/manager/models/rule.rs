@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::manager::models::category::Category;
+
+/// Auto-categorization rule of a budget. Every condition that is set must
+/// match; a matching rule sets the category, adds tags and/or replaces the
+/// counterparty with a normalized name. Rules run by ascending `priority`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Rule {
+    pub id: String,
+    pub budget_id: String,
+    pub name: String,
+    pub priority: i32,
+    pub active: bool,
+    pub counterparty_pattern: Option<String>,
+    pub counterparty_match: String, // "contains", "equals", "starts_with"; case-insensitive
+    pub description_regex: Option<String>, // case-insensitive
+    pub min_amount_minor: Option<i64>,
+    pub max_amount_minor: Option<i64>,
+    pub kind: Option<String>,
+    pub set_category_id: Option<String>,
+    pub set_counterparty: Option<String>,
+    #[sqlx(skip)]
+    pub set_tag_ids: Vec<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Full definition of a rule, used for both create and replace.
+#[derive(Debug, Deserialize)]
+pub struct SaveRuleReq {
+    pub name: String,
+    pub priority: Option<i32>, // default 100
+    pub active: Option<bool>,
+    pub counterparty_pattern: Option<String>,
+    pub counterparty_match: Option<String>,
+    pub description_regex: Option<String>,
+    pub min_amount_minor: Option<i64>,
+    pub max_amount_minor: Option<i64>,
+    pub kind: Option<String>,
+    pub set_category_id: Option<String>,
+    pub set_counterparty: Option<String>,
+    pub set_tag_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct RuleTag {
+    pub rule_id: String,
+    pub tag_id: String,
+}
+
+/// Re-run the active rules over existing entries matching the filter.
+/// With `dry_run` the changes are only previewed.
+#[derive(Debug, Deserialize)]
+pub struct ApplyRulesReq {
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+    pub kind: Option<String>,
+    pub category_id: Option<String>,
+    pub member_id: Option<String>,
+    pub search: Option<String>,
+    pub tags: Option<String>, // comma-separated tag ids
+    pub tag_match: Option<String>,
+    pub rule_ids: Option<Vec<String>>, // only run these rules
+    pub dry_run: Option<bool>,
+}
+
+/// What applying the rules changes on one entry. `category_id` and
+/// `counterparty` are only present when they change.
+#[derive(Debug, Serialize)]
+pub struct RuleChange {
+    pub entry_id: String,
+    pub entry_date: chrono::NaiveDate,
+    pub rule_ids: Vec<String>,
+    pub previous_category_id: String,
+    pub category_id: Option<String>,
+    pub previous_counterparty: Option<String>,
+    pub counterparty: Option<String>,
+    pub added_tag_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApplyRulesReport {
+    pub dry_run: bool,
+    pub entries_checked: usize,
+    pub entries_changed: usize,
+    pub changes: Vec<RuleChange>,
+}
+
+/// The entry fields rules match on.
+pub struct RuleSubject<'a> {
+    pub kind: &'a str,
+    pub amount_minor: i64,
+    pub description: Option<&'a str>,
+    pub counterparty: Option<&'a str>,
+}
+
+/// Result of running a rule set over one entry. Empty when nothing matched.
+#[derive(Debug, Default)]
+pub struct RuleOutcome {
+    pub rule_ids: Vec<String>,
+    pub category_id: Option<String>,
+    pub counterparty: Option<String>,
+    pub tag_ids: Vec<String>,
+}
+
+struct CompiledRule {
+    rule: Rule,
+    description: Option<Regex>,
+    category_kind: Option<String>,
+}
+
+/// Active rules of a budget with their regexes compiled, in priority order.
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl Rule {
+    pub fn compile_regex(pattern: &str) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(pattern).case_insensitive(true).size_limit(1 << 20).build()
+    }
+}
+
+impl RuleSet {
+    /// `rules` must already be sorted by priority. Inactive rules and rules whose
+    /// regex no longer compiles are left out; a target category that is gone or
+    /// archived is ignored while the rule's other actions still apply.
+    pub fn new(rules: Vec<Rule>, categories: &[Category]) -> RuleSet {
+        let kinds: HashMap<&str, &Category> = categories.iter().map(|c| (c.id.as_str(), c)).collect();
+        let rules = rules.into_iter()
+            .filter(|r| r.active)
+            .filter_map(|rule| {
+                let description = match rule.description_regex.as_deref() {
+                    Some(pattern) => Some(Rule::compile_regex(pattern).ok()?),
+                    None => None,
+                };
+                let category_kind = rule.set_category_id.as_deref()
+                    .and_then(|id| kinds.get(id))
+                    .filter(|c| !c.is_hidden)
+                    .map(|c| c.kind.clone());
+                Some(CompiledRule { rule, description, category_kind })
+            })
+            .collect();
+        RuleSet { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Rules are evaluated in order against the entry as rewritten by the rules
+    /// before them, so a normalizing rule can feed a categorizing one. The first
+    /// matching rule to set a category or counterparty wins; tags accumulate.
+    pub fn apply(&self, subject: &RuleSubject) -> RuleOutcome {
+        let mut outcome = RuleOutcome::default();
+        for compiled in &self.rules {
+            let counterparty = outcome.counterparty.as_deref().or(subject.counterparty);
+            if !compiled.matches(subject, counterparty) {
+                continue;
+            }
+            let rule = &compiled.rule;
+            outcome.rule_ids.push(rule.id.clone());
+            if outcome.category_id.is_none() && compiled.category_kind.as_deref() == Some(subject.kind) {
+                outcome.category_id = rule.set_category_id.clone();
+            }
+            if outcome.counterparty.is_none() {
+                outcome.counterparty = rule.set_counterparty.clone();
+            }
+            for tag_id in &rule.set_tag_ids {
+                if !outcome.tag_ids.contains(tag_id) {
+                    outcome.tag_ids.push(tag_id.clone());
+                }
+            }
+        }
+        outcome
+    }
+}
+
+impl CompiledRule {
+    fn matches(&self, subject: &RuleSubject, counterparty: Option<&str>) -> bool {
+        let rule = &self.rule;
+        if rule.kind.as_deref().is_some_and(|k| k != subject.kind) {
+            return false;
+        }
+        if rule.min_amount_minor.is_some_and(|min| subject.amount_minor < min)
+            || rule.max_amount_minor.is_some_and(|max| subject.amount_minor > max) {
+            return false;
+        }
+        if let Some(pattern) = &rule.counterparty_pattern {
+            let Some(counterparty) = counterparty else { return false };
+            let (value, pattern) = (counterparty.trim().to_lowercase(), pattern.to_lowercase());
+            let matched = match rule.counterparty_match.as_str() {
+                "equals" => value == pattern,
+                "starts_with" => value.starts_with(&pattern),
+                _ => value.contains(&pattern),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(regex) = &self.description {
+            if !subject.description.is_some_and(|d| regex.is_match(d)) {
+                return false;
+            }
+        }
+        true
+    }
+}
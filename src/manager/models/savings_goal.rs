@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Amount a saving budget works towards. Progress counts the net of the
+/// linked categories (and their subcategories), or of the whole budget when
+/// none are linked, from `start_date` on.
+#[derive(Debug, Serialize, FromRow)]
+pub struct SavingsGoal {
+    pub id: String,
+    pub budget_id: String,
+    pub name: String,
+    pub target_amount_minor: i64, // in the budget currency
+    pub target_date: Option<chrono::NaiveDate>,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+    #[sqlx(skip)]
+    pub category_ids: Vec<String>,
+    pub created_by: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct SavingsGoalCategory {
+    pub goal_id: String,
+    pub category_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSavingsGoalReq {
+    pub name: String,
+    pub target_amount_minor: i64,
+    pub target_date: Option<chrono::NaiveDate>,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+    pub category_ids: Option<Vec<String>>,
+}
+
+/// `category_ids` replaces the linked categories; an empty list unlinks all.
+#[derive(Debug, Deserialize)]
+pub struct UpdateSavingsGoalReq {
+    pub name: Option<String>,
+    pub target_amount_minor: Option<i64>,
+    pub target_date: Option<chrono::NaiveDate>,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+    pub category_ids: Option<Vec<String>>,
+}
+
+/// `months`: how many complete months the average contribution looks back (default 3).
+#[derive(Debug, Deserialize)]
+pub struct GoalProgressQuery {
+    pub months: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalMonth {
+    pub month_start: chrono::NaiveDate,
+    pub contributed_minor: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SavingsGoalProgress {
+    pub goal_id: String,
+    pub currency_code: String,
+    pub target_amount_minor: i64,
+    pub saved_minor: i64,
+    pub from_entries_minor: i64,
+    pub from_transfers_minor: i64, // incoming transfers net of outgoing ones
+    pub remaining_minor: i64,
+    pub percent: f64,
+    pub achieved: bool,
+    pub target_date: Option<chrono::NaiveDate>,
+    pub months_left: Option<u32>, // counting the current month
+    pub required_monthly_minor: Option<i64>,
+    pub average_months: u32,
+    pub average_monthly_minor: i64,
+    pub projected_completion_date: Option<chrono::NaiveDate>, // None when already achieved or not saving
+    pub on_track: Option<bool>,
+    pub monthly: Vec<GoalMonth>,
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::manager::models::recurrence::{Frequency, RecurrenceRule};
+
+/// Template that the scheduler executes into `budget_transfers` rows, on behalf
+/// of `created_by`.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ScheduledTransfer {
+    pub id: String,
+    pub from_budget_id: String,
+    pub to_budget_id: String,
+    pub from_category_id: String,
+    pub to_category_id: String,
+    pub amount_minor: i64, // in the source budget currency
+    pub exchange_rate: Option<f64>,
+    pub fee_minor: i64,
+    pub fee_category_id: Option<String>,
+    pub note: Option<String>,
+    #[sqlx(try_from = "String")]
+    pub frequency: Frequency,
+    pub interval_count: i32,
+    pub by_month_day: Option<i32>,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub occurrence_count: Option<i32>,
+    pub generated_through: Option<chrono::NaiveDate>,
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl ScheduledTransfer {
+    pub fn rule(&self) -> RecurrenceRule {
+        RecurrenceRule {
+            frequency: self.frequency,
+            interval: self.interval_count.max(1) as u32,
+            by_month_day: self.by_month_day.map(|d| d.max(0) as u32),
+            start_date: self.start_date,
+            end_date: self.end_date,
+            count: self.occurrence_count.map(|c| c.max(0) as u32),
+        }
+    }
+}
+
+/// `exchange_rate` fixes the rate between budgets in different currencies;
+/// without it each run uses the stored rate for its date.
+#[derive(Debug, Deserialize)]
+pub struct CreateScheduledTransferReq {
+    pub from_budget_id: String,
+    pub to_budget_id: String,
+    pub from_category_id: String,
+    pub to_category_id: String,
+    pub amount_minor: i64,
+    pub exchange_rate: Option<f64>,
+    pub fee_minor: Option<i64>,
+    pub fee_category_id: Option<String>,
+    pub note: Option<String>,
+    pub frequency: Frequency,
+    pub interval_count: Option<i32>,
+    pub by_month_day: Option<i32>,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub occurrence_count: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateScheduledTransferReq {
+    pub from_category_id: Option<String>,
+    pub to_category_id: Option<String>,
+    pub amount_minor: Option<i64>,
+    pub exchange_rate: Option<f64>,
+    pub fee_minor: Option<i64>,
+    pub fee_category_id: Option<String>,
+    pub note: Option<String>,
+    pub frequency: Option<Frequency>,
+    pub interval_count: Option<i32>,
+    pub by_month_day: Option<i32>,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub occurrence_count: Option<i32>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduledTransferPreview {
+    pub scheduled_transfer_id: String,
+    pub occurrences: Vec<chrono::NaiveDate>,
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::utils::error::error::AppError;
+
+/// A label from the budget's tag vocabulary. Tags cut across categories.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Tag {
+    pub id: String,
+    pub budget_id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTagReq {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTagReq {
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+/// Tag as attached to an entry.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct EntryTag {
+    #[serde(skip)]
+    pub entry_id: String,
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+}
+
+/// Entry filter on tag ids. `match_all` requires every tag, otherwise any one is enough.
+#[derive(Debug, Clone)]
+pub struct TagFilter {
+    pub tag_ids: Vec<String>,
+    pub match_all: bool,
+}
+
+impl TagFilter {
+    /// Parse the `tags` (comma-separated ids) and `tag_match` ("any" default, "all") query params.
+    pub fn parse(tags: Option<&str>, tag_match: Option<&str>) -> Result<Option<TagFilter>, AppError> {
+        let mut tag_ids: Vec<String> = tags.unwrap_or_default()
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        tag_ids.sort();
+        tag_ids.dedup();
+        let match_all = match tag_match {
+            None | Some("any") => false,
+            Some("all") => true,
+            Some(other) => return Err(AppError::BadRequest(format!("Invalid tag_match: {}. Use any or all", other))),
+        };
+        if tag_ids.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(TagFilter { tag_ids, match_all }))
+    }
+
+    /// SQL condition on entry alias `e`; bind `tag_ids` in order after it.
+    pub fn sql_condition(&self) -> String {
+        let placeholders = vec!["?"; self.tag_ids.len()].join(", ");
+        if self.match_all {
+            format!(
+                " AND (SELECT COUNT(*) FROM entry_tags et WHERE et.entry_id = e.id AND et.tag_id IN ({})) = {}",
+                placeholders,
+                self.tag_ids.len()
+            )
+        } else {
+            format!(" AND EXISTS (SELECT 1 FROM entry_tags et WHERE et.entry_id = e.id AND et.tag_id IN ({}))", placeholders)
+        }
+    }
+}
+
+/// Income and expense of the entries carrying one tag. An entry with several
+/// tags counts fully towards each of them.
+#[derive(Debug, Serialize, FromRow)]
+pub struct TagSummaryRow {
+    pub tag_id: String,
+    pub tag_name: String,
+    pub color: Option<String>,
+    pub entry_count: i64,
+    pub income_minor: i64,
+    pub expense_minor: i64,
+    pub net_minor: i64,
+}
@@ -8,12 +8,22 @@ pub struct BudgetTransfer {
     pub to_budget_id: String,
     pub amount_minor: i64,
     pub currency_code: String,
+    pub to_amount_minor: i64,
+    pub to_currency_code: String,
+    pub exchange_rate: Option<f64>,
+    pub fee_minor: i64,
     pub transfer_date: chrono::NaiveDate,
     pub note: Option<String>,
     pub created_by: String,
     pub created_at: chrono::NaiveDateTime,
+    pub updated_at: Option<chrono::NaiveDateTime>,
 }
 
+/// `amount_minor` leaves the source budget in its currency. Between budgets in
+/// different currencies the received amount uses `exchange_rate` (units of the
+/// destination currency per unit of the source currency) or, when absent, the
+/// stored rate for the transfer date. `fee_minor` is booked as a separate expense
+/// in the source budget, under `fee_category_id` or else `from_category_id`.
 #[derive(Debug, Deserialize)]
 pub struct CreateTransferReq {
     pub from_budget_id: String,
@@ -24,6 +34,45 @@ pub struct CreateTransferReq {
     pub note: Option<String>,
     pub from_category_id: String,
     pub to_category_id: String,
+    pub exchange_rate: Option<f64>,
+    pub fee_minor: Option<i64>,
+    pub fee_category_id: Option<String>,
+    #[serde(skip_deserializing)]
+    pub scheduled_transfer_id: Option<String>, // set by the scheduler; the occurrence is `transfer_date`
+}
+
+/// Partial edit of a transfer; both legs and the fee entry follow. An empty
+/// `note` clears it. The stored exchange rate is kept while the date stays the
+/// same, otherwise the received amount is converted again.
+#[derive(Debug, Deserialize)]
+pub struct UpdateTransferReq {
+    pub amount_minor: Option<i64>,
+    pub transfer_date: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+    pub from_category_id: Option<String>,
+    pub to_category_id: Option<String>,
+    pub exchange_rate: Option<f64>,
+    pub fee_minor: Option<i64>,
+    pub fee_category_id: Option<String>,
+}
+
+/// A live entry generated by a transfer.
+#[derive(Debug, FromRow)]
+pub struct TransferLeg {
+    pub entry_id: String,
+    pub budget_id: String,
+    pub category_id: String,
+    pub transfer_leg: Option<String>, // NULL on transfers made before legs were tagged
+}
+
+impl TransferLeg {
+    pub fn leg(&self, transfer: &BudgetTransfer) -> &str {
+        match self.transfer_leg.as_deref() {
+            Some(leg) => leg,
+            None if self.budget_id == transfer.to_budget_id => "to",
+            None => "from",
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -31,6 +80,7 @@ pub struct TransferWithEntries {
     pub transfer: BudgetTransfer,
     pub from_entry_id: String,
     pub to_entry_id: String,
+    pub fee_entry_id: Option<String>,
     pub from_budget_name: String,
     pub to_budget_name: String,
 }
@@ -0,0 +1,701 @@
+use crate::manager::models::backup::{
+    ArchivedAttachment, ArchivedBudget, ArchivedCategory, ArchivedCategoryLimit, ArchivedComment, ArchivedEntry, ArchivedEntrySplit, ArchivedEntryTag, ArchivedPayee, ArchivedPayeeAlias, ArchivedTag,
+    ArchivedImportKey, ArchivedMember, ArchivedMention, ArchivedRecurringEntry, ArchivedTransfer, BudgetArchive, RestorePlan,
+    ArchivedDebt, ArchivedExchangeRate, ArchivedHolding, ArchivedInvestmentTransaction, ArchivedPriceSnapshot, ArchivedRule, ArchivedRuleTag,
+    ArchivedSavingsGoal, ArchivedSavingsGoalCategory,
+};
+use crate::manager::models::role::Role;
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct BackupRepo;
+
+impl BackupRepo {
+    pub async fn budget(pool: &DbPool, budget_id: &str) -> Result<ArchivedBudget, AppError> {
+        sqlx::query_as::<_, ArchivedBudget>(
+            "SELECT id, name, currency_code, budget_type, description, archived, created_at FROM budgets WHERE id = ?"
+        )
+        .bind(budget_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::NotFound)
+    }
+
+    pub async fn members(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedMember>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedMember>(
+            "SELECT u.email, bm.role FROM budget_members bm INNER JOIN users u ON bm.user_id = u.id WHERE bm.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn categories(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedCategory>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedCategory>(
+            "SELECT id, parent_id, name, kind, is_hidden, color, icon, rollover_policy, created_at FROM categories WHERE budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn category_limits(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedCategoryLimit>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedCategoryLimit>(
+            "SELECT id, category_id, period_start, planned_minor FROM category_limits WHERE budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn recurring_entries(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedRecurringEntry>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedRecurringEntry>(
+            "SELECT r.id, r.category_id, r.kind, r.amount_minor, r.currency_code, r.description, r.counterparty, \
+                    r.frequency, r.interval_count, r.by_month_day, r.start_date, r.end_date, r.occurrence_count, \
+                    r.generated_through, r.active, u.email as created_by_email, r.created_at \
+             FROM recurring_entries r INNER JOIN users u ON r.created_by = u.id \
+             WHERE r.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    /// Includes soft-deleted entries so comments and attachments on them survive.
+    pub async fn entries(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedEntry>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedEntry>(
+            "SELECT e.id, e.category_id, e.kind, e.amount_minor, e.currency_code, e.base_amount_minor, e.exchange_rate, e.entry_date, e.description, \
+                    e.counterparty, e.payee_id, e.transfer_id, e.recurring_id, e.recurring_date, e.comment_count, e.attachment_count, \
+                    cu.email as created_by_email, uu.email as updated_by_email, e.created_at, e.updated_at, e.deleted_at \
+             FROM entries e \
+             INNER JOIN users cu ON e.created_by = cu.id \
+             LEFT JOIN users uu ON e.updated_by = uu.id \
+             WHERE e.budget_id = ? \
+             ORDER BY e.entry_date ASC, e.created_at ASC"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn entry_splits(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedEntrySplit>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedEntrySplit>(
+            "SELECT entry_id, category_id, amount_minor, base_amount_minor, note, position FROM entry_splits WHERE budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn payees(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedPayee>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedPayee>("SELECT id, name, default_category_id, created_at FROM payees WHERE budget_id = ?")
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?)
+    }
+
+    pub async fn payee_aliases(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedPayeeAlias>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedPayeeAlias>("SELECT payee_id, alias, normalized FROM payee_aliases WHERE budget_id = ?")
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?)
+    }
+
+    pub async fn tags(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedTag>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedTag>("SELECT id, name, color, created_at FROM tags WHERE budget_id = ?")
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?)
+    }
+
+    pub async fn entry_tags(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedEntryTag>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedEntryTag>(
+            "SELECT et.entry_id, et.tag_id FROM entry_tags et INNER JOIN tags t ON t.id = et.tag_id WHERE t.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn transfers(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedTransfer>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedTransfer>(
+            "SELECT t.id, \
+                    CASE WHEN t.from_budget_id = ? THEN 'out' ELSE 'in' END as direction, \
+                    b.id as counterpart_budget_id, b.name as counterpart_budget_name, \
+                    t.amount_minor, t.currency_code, t.to_amount_minor, t.to_currency_code, t.exchange_rate, t.fee_minor, \
+                    t.transfer_date, t.note, u.email as created_by_email, t.created_at \
+             FROM budget_transfers t \
+             INNER JOIN budgets b ON b.id = CASE WHEN t.from_budget_id = ? THEN t.to_budget_id ELSE t.from_budget_id END \
+             INNER JOIN users u ON t.created_by = u.id \
+             WHERE t.from_budget_id = ? OR t.to_budget_id = ?"
+        )
+        .bind(budget_id)
+        .bind(budget_id)
+        .bind(budget_id)
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn comments(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedComment>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedComment>(
+            "SELECT c.id, c.entry_id, u.email as user_email, c.comment_text, c.created_at, c.updated_at, c.deleted_at \
+             FROM entry_comments c \
+             INNER JOIN entries e ON c.entry_id = e.id \
+             INNER JOIN users u ON c.user_id = u.id \
+             WHERE e.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn mentions(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedMention>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedMention>(
+            "SELECT m.comment_id, u.email as user_email, m.created_at \
+             FROM comment_mentions m \
+             INNER JOIN entry_comments c ON m.comment_id = c.id \
+             INNER JOIN entries e ON c.entry_id = e.id \
+             INNER JOIN users u ON m.mentioned_user_id = u.id \
+             WHERE e.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn attachments(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedAttachment>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedAttachment>(
+            "SELECT a.id, a.entry_id, a.comment_id, u.email as user_email, a.file_url, a.file_name, a.file_size, \
+                    a.mime_type, a.created_at, a.deleted_at \
+             FROM entry_attachments a \
+             INNER JOIN entries e ON a.entry_id = e.id \
+             INNER JOIN users u ON a.user_id = u.id \
+             WHERE e.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    /// Keys outlive their entries, so keys of purged entries are archived too.
+    pub async fn import_keys(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedImportKey>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedImportKey>(
+            "SELECT dedupe_key, entry_id FROM import_dedupe_keys WHERE budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn rules(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedRule>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedRule>(
+            "SELECT id, name, priority, active, counterparty_pattern, counterparty_match, description_regex, \
+                    min_amount_minor, max_amount_minor, kind, set_category_id, set_counterparty, created_at \
+             FROM entry_rules WHERE budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn rule_tags(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedRuleTag>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedRuleTag>(
+            "SELECT rt.rule_id, rt.tag_id FROM entry_rule_tags rt INNER JOIN entry_rules r ON r.id = rt.rule_id WHERE r.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn exchange_rates(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedExchangeRate>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedExchangeRate>(
+            "SELECT rate_date, base_currency, quote_currency, rate, source FROM exchange_rates WHERE budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn savings_goals(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedSavingsGoal>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedSavingsGoal>(
+            "SELECT g.id, g.name, g.target_amount_minor, g.target_date, g.start_date, g.note, u.email as created_by_email, g.created_at \
+             FROM savings_goals g INNER JOIN users u ON g.created_by = u.id \
+             WHERE g.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn savings_goal_categories(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedSavingsGoalCategory>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedSavingsGoalCategory>(
+            "SELECT gc.goal_id, gc.category_id FROM savings_goal_categories gc INNER JOIN savings_goals g ON g.id = gc.goal_id WHERE g.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn debts(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedDebt>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedDebt>(
+            "SELECT d.id, d.name, d.principal_minor, d.apr, d.minimum_payment_minor, d.due_day, d.category_id, d.opened_on, d.note, \
+                    u.email as created_by_email, d.created_at \
+             FROM debts d INNER JOIN users u ON d.created_by = u.id \
+             WHERE d.budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn holdings(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedHolding>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedHolding>("SELECT id, symbol, name, cost_method, created_at FROM holdings WHERE budget_id = ?")
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?)
+    }
+
+    pub async fn investment_transactions(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedInvestmentTransaction>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedInvestmentTransaction>(
+            "SELECT t.id, t.holding_id, t.kind, t.trade_date, t.quantity, t.price_minor, t.fee_minor, t.amount_minor, t.entry_id, t.note, \
+                    u.email as created_by_email, t.created_at \
+             FROM investment_transactions t INNER JOIN users u ON t.created_by = u.id \
+             WHERE t.budget_id = ? \
+             ORDER BY t.trade_date ASC, t.created_at ASC"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn price_snapshots(pool: &DbPool, budget_id: &str) -> Result<Vec<ArchivedPriceSnapshot>, AppError> {
+        Ok(sqlx::query_as::<_, ArchivedPriceSnapshot>(
+            "SELECT symbol, price_date, price_minor, source FROM price_snapshots WHERE budget_id = ?"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    /// Recreate an archive under the plan's fresh ids in a single transaction.
+    /// Mentions of users that do not exist locally are dropped.
+    pub async fn restore(pool: &DbPool, archive: &BudgetArchive, plan: &RestorePlan) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO budgets (id, owner_id, name, currency_code, budget_type, description, archived, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&plan.budget_id)
+        .bind(&plan.owner_id)
+        .bind(&plan.name)
+        .bind(&archive.budget.currency_code)
+        .bind(&archive.budget.budget_type)
+        .bind(&archive.budget.description)
+        .bind(archive.budget.archived)
+        .bind(archive.budget.created_at)
+        .execute(&mut *tx)
+        .await?;
+
+        for member in &archive.members {
+            if let Some(user_id) = plan.users.get(&member.email.to_lowercase()) {
+                if *user_id == plan.owner_id {
+                    continue;
+                }
+                // The restoring user is the only owner; archived owners come back as managers
+                let role = if Role::from_str(&member.role) == Some(Role::Owner) { "manager" } else { member.role.as_str() };
+                sqlx::query("INSERT INTO budget_members (budget_id, user_id, role) VALUES (?, ?, ?)")
+                    .bind(&plan.budget_id)
+                    .bind(user_id)
+                    .bind(role)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        sqlx::query("INSERT INTO budget_members (budget_id, user_id, role) VALUES (?, ?, 'owner')")
+            .bind(&plan.budget_id)
+            .bind(&plan.owner_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for c in &archive.categories {
+            sqlx::query(
+                "INSERT INTO categories (id, budget_id, name, kind, is_hidden, color, icon, rollover_policy, created_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))"
+            )
+            .bind(plan.id(&c.id))
+            .bind(&plan.budget_id)
+            .bind(&c.name)
+            .bind(&c.kind)
+            .bind(c.is_hidden)
+            .bind(&c.color)
+            .bind(&c.icon)
+            .bind(&c.rollover_policy)
+            .bind(c.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        // Parents are linked once every category exists, whatever the archive order
+        for c in archive.categories.iter().filter(|c| c.parent_id.is_some()) {
+            sqlx::query("UPDATE categories SET parent_id = ? WHERE id = ? AND budget_id = ?")
+                .bind(c.parent_id.as_deref().map(|id| plan.id(id)))
+                .bind(plan.id(&c.id))
+                .bind(&plan.budget_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for l in &archive.category_limits {
+            sqlx::query(
+                "INSERT INTO category_limits (id, budget_id, category_id, period_start, planned_minor) VALUES (?, ?, ?, ?, ?)"
+            )
+            .bind(plan.id(&l.id))
+            .bind(&plan.budget_id)
+            .bind(plan.id(&l.category_id))
+            .bind(l.period_start)
+            .bind(l.planned_minor)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for p in &archive.payees {
+            sqlx::query("INSERT INTO payees (id, budget_id, name, default_category_id, created_at) VALUES (?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))")
+                .bind(plan.id(&p.id))
+                .bind(&plan.budget_id)
+                .bind(&p.name)
+                .bind(p.default_category_id.as_deref().map(|id| plan.id(id)))
+                .bind(p.created_at)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for a in &archive.payee_aliases {
+            sqlx::query("INSERT INTO payee_aliases (id, budget_id, payee_id, alias, normalized) VALUES (?, ?, ?, ?, ?)")
+                .bind(uuid::Uuid::new_v4().to_string())
+                .bind(&plan.budget_id)
+                .bind(plan.id(&a.payee_id))
+                .bind(&a.alias)
+                .bind(&a.normalized)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for r in &archive.recurring_entries {
+            sqlx::query(r#"
+                INSERT INTO recurring_entries
+                (id, budget_id, category_id, kind, amount_minor, currency_code, description, counterparty,
+                 frequency, interval_count, by_month_day, start_date, end_date, occurrence_count,
+                 generated_through, active, created_by, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))
+            "#)
+            .bind(plan.id(&r.id))
+            .bind(&plan.budget_id)
+            .bind(plan.id(&r.category_id))
+            .bind(&r.kind)
+            .bind(r.amount_minor)
+            .bind(&r.currency_code)
+            .bind(&r.description)
+            .bind(&r.counterparty)
+            .bind(&r.frequency)
+            .bind(r.interval_count)
+            .bind(r.by_month_day)
+            .bind(r.start_date)
+            .bind(r.end_date)
+            .bind(r.occurrence_count)
+            .bind(r.generated_through)
+            .bind(r.active)
+            .bind(plan.user(&r.created_by_email))
+            .bind(r.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for e in &archive.entries {
+            sqlx::query(r#"
+                INSERT INTO entries
+                (id, budget_id, category_id, kind, amount_minor, currency_code, base_amount_minor, exchange_rate, entry_date,
+                 description, counterparty, payee_id, recurring_id, recurring_date, comment_count, attachment_count,
+                 created_by, updated_by, created_at, updated_at, deleted_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), COALESCE(?, CURRENT_TIMESTAMP), ?)
+            "#)
+            .bind(plan.id(&e.id))
+            .bind(&plan.budget_id)
+            .bind(plan.id(&e.category_id))
+            .bind(&e.kind)
+            .bind(e.amount_minor)
+            .bind(&e.currency_code)
+            .bind(e.base_amount_minor.unwrap_or(e.amount_minor))
+            .bind(e.exchange_rate)
+            .bind(e.entry_date)
+            .bind(&e.description)
+            .bind(&e.counterparty)
+            .bind(e.payee_id.as_deref().map(|id| plan.id(id)))
+            .bind(e.recurring_id.as_deref().map(|id| plan.id(id)))
+            .bind(e.recurring_date)
+            .bind(e.comment_count.unwrap_or(0))
+            .bind(e.attachment_count.unwrap_or(0))
+            .bind(plan.user(&e.created_by_email))
+            .bind(e.updated_by_email.as_deref().map(|email| plan.user(email)))
+            .bind(e.created_at)
+            .bind(e.updated_at)
+            .bind(e.deleted_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for s in &archive.entry_splits {
+            sqlx::query(
+                "INSERT INTO entry_splits (id, entry_id, budget_id, category_id, amount_minor, base_amount_minor, note, position) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(plan.id(&s.entry_id))
+            .bind(&plan.budget_id)
+            .bind(plan.id(&s.category_id))
+            .bind(s.amount_minor)
+            .bind(s.base_amount_minor.unwrap_or(s.amount_minor))
+            .bind(&s.note)
+            .bind(s.position)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for t in &archive.tags {
+            sqlx::query("INSERT INTO tags (id, budget_id, name, color, created_at) VALUES (?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))")
+                .bind(plan.id(&t.id))
+                .bind(&plan.budget_id)
+                .bind(&t.name)
+                .bind(&t.color)
+                .bind(t.created_at)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for et in &archive.entry_tags {
+            sqlx::query("INSERT INTO entry_tags (entry_id, tag_id) VALUES (?, ?)")
+                .bind(plan.id(&et.entry_id))
+                .bind(plan.id(&et.tag_id))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for c in &archive.comments {
+            sqlx::query(
+                "INSERT INTO entry_comments (id, entry_id, user_id, comment_text, created_at, updated_at, deleted_at) \
+                 VALUES (?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), COALESCE(?, CURRENT_TIMESTAMP), ?)"
+            )
+            .bind(plan.id(&c.id))
+            .bind(plan.id(&c.entry_id))
+            .bind(plan.user(&c.user_email))
+            .bind(&c.comment_text)
+            .bind(c.created_at)
+            .bind(c.updated_at)
+            .bind(c.deleted_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for m in &archive.mentions {
+            let Some(user_id) = plan.users.get(&m.user_email.to_lowercase()) else { continue };
+            sqlx::query(
+                "INSERT IGNORE INTO comment_mentions (id, comment_id, mentioned_user_id, created_at) \
+                 VALUES (?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(plan.id(&m.comment_id))
+            .bind(user_id)
+            .bind(m.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for a in &archive.attachments {
+            let file_url = plan.file_urls.get(&a.id).unwrap_or(&a.file_url);
+            sqlx::query(
+                "INSERT INTO entry_attachments \
+                 (id, entry_id, comment_id, user_id, file_url, file_name, file_size, mime_type, created_at, deleted_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?)"
+            )
+            .bind(plan.id(&a.id))
+            .bind(plan.id(&a.entry_id))
+            .bind(a.comment_id.as_deref().map(|id| plan.id(id)))
+            .bind(plan.user(&a.user_email))
+            .bind(file_url)
+            .bind(&a.file_name)
+            .bind(a.file_size)
+            .bind(&a.mime_type)
+            .bind(a.created_at)
+            .bind(a.deleted_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for k in &archive.import_keys {
+            // Keys of purged entries still block re-imports; their entry_id is a placeholder
+            let entry_id = plan.ids.get(&k.entry_id).cloned().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+            sqlx::query("INSERT INTO import_dedupe_keys (budget_id, dedupe_key, entry_id) VALUES (?, ?, ?)")
+                .bind(&plan.budget_id)
+                .bind(&k.dedupe_key)
+                .bind(&entry_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for r in &archive.rules {
+            sqlx::query(r#"
+                INSERT INTO entry_rules
+                (id, budget_id, name, priority, active, counterparty_pattern, counterparty_match, description_regex,
+                 min_amount_minor, max_amount_minor, kind, set_category_id, set_counterparty, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))
+            "#)
+            .bind(plan.id(&r.id))
+            .bind(&plan.budget_id)
+            .bind(&r.name)
+            .bind(r.priority)
+            .bind(r.active)
+            .bind(&r.counterparty_pattern)
+            .bind(&r.counterparty_match)
+            .bind(&r.description_regex)
+            .bind(r.min_amount_minor)
+            .bind(r.max_amount_minor)
+            .bind(&r.kind)
+            .bind(r.set_category_id.as_deref().map(|id| plan.id(id)))
+            .bind(&r.set_counterparty)
+            .bind(r.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for rt in &archive.rule_tags {
+            sqlx::query("INSERT INTO entry_rule_tags (rule_id, tag_id) VALUES (?, ?)")
+                .bind(plan.id(&rt.rule_id))
+                .bind(plan.id(&rt.tag_id))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for r in &archive.exchange_rates {
+            sqlx::query(
+                "INSERT INTO exchange_rates (id, budget_id, rate_date, base_currency, quote_currency, rate, source) VALUES (?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&plan.budget_id)
+            .bind(r.rate_date)
+            .bind(&r.base_currency)
+            .bind(&r.quote_currency)
+            .bind(r.rate)
+            .bind(&r.source)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for g in &archive.savings_goals {
+            sqlx::query(
+                "INSERT INTO savings_goals (id, budget_id, name, target_amount_minor, target_date, start_date, note, created_by, created_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))"
+            )
+            .bind(plan.id(&g.id))
+            .bind(&plan.budget_id)
+            .bind(&g.name)
+            .bind(g.target_amount_minor)
+            .bind(g.target_date)
+            .bind(g.start_date)
+            .bind(&g.note)
+            .bind(plan.user(&g.created_by_email))
+            .bind(g.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for gc in &archive.savings_goal_categories {
+            sqlx::query("INSERT INTO savings_goal_categories (goal_id, category_id) VALUES (?, ?)")
+                .bind(plan.id(&gc.goal_id))
+                .bind(plan.id(&gc.category_id))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for d in &archive.debts {
+            sqlx::query(r#"
+                INSERT INTO debts
+                (id, budget_id, name, principal_minor, apr, minimum_payment_minor, due_day, category_id, opened_on, note, created_by, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))
+            "#)
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&plan.budget_id)
+            .bind(&d.name)
+            .bind(d.principal_minor)
+            .bind(d.apr)
+            .bind(d.minimum_payment_minor)
+            .bind(d.due_day)
+            .bind(d.category_id.as_deref().map(|id| plan.id(id)))
+            .bind(d.opened_on)
+            .bind(&d.note)
+            .bind(plan.user(&d.created_by_email))
+            .bind(d.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for h in &archive.holdings {
+            sqlx::query(
+                "INSERT INTO holdings (id, budget_id, symbol, name, cost_method, created_at) VALUES (?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))"
+            )
+            .bind(plan.id(&h.id))
+            .bind(&plan.budget_id)
+            .bind(&h.symbol)
+            .bind(&h.name)
+            .bind(&h.cost_method)
+            .bind(h.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for t in &archive.investment_transactions {
+            sqlx::query(r#"
+                INSERT INTO investment_transactions
+                (id, budget_id, holding_id, kind, trade_date, quantity, price_minor, fee_minor, amount_minor, entry_id, note, created_by, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))
+            "#)
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&plan.budget_id)
+            .bind(plan.id(&t.holding_id))
+            .bind(&t.kind)
+            .bind(t.trade_date)
+            .bind(t.quantity)
+            .bind(t.price_minor)
+            .bind(t.fee_minor)
+            .bind(t.amount_minor)
+            .bind(t.entry_id.as_deref().map(|id| plan.id(id)))
+            .bind(&t.note)
+            .bind(plan.user(&t.created_by_email))
+            .bind(t.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for p in &archive.price_snapshots {
+            sqlx::query("INSERT INTO price_snapshots (id, budget_id, symbol, price_date, price_minor, source) VALUES (?, ?, ?, ?, ?, ?)")
+                .bind(uuid::Uuid::new_v4().to_string())
+                .bind(&plan.budget_id)
+                .bind(&p.symbol)
+                .bind(p.price_date)
+                .bind(p.price_minor)
+                .bind(&p.source)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Local user ids for the given emails, keyed by lowercased email.
+    pub async fn user_ids_by_email(pool: &DbPool, emails: &[String]) -> Result<Vec<(String, String)>, AppError> {
+        if emails.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; emails.len()].join(", ");
+        let sql = format!("SELECT LOWER(email), id FROM users WHERE LOWER(email) IN ({})", placeholders);
+        let mut query = sqlx::query_as::<_, (String, String)>(&sql);
+        for email in emails {
+            query = query.bind(email.to_lowercase());
+        }
+        Ok(query.fetch_all(pool).await?)
+    }
+}
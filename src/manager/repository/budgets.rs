@@ -1,5 +1,7 @@
-use crate::manager::{models::budget::{Budget, BudgetWithRole, CreateBudgetReq, BudgetType}};
+use std::collections::HashMap;
+use crate::manager::{models::budget::{Budget, BudgetWithRole, CreateBudgetReq, BudgetType}, models::category_template::TemplateCategory};
 use crate::utils::{database::database::DbPool, error::error::AppError};
+use crate::manager::repository::entries::ENTRY_LINES;
 use sqlx::Row;
 pub struct BudgetRepo;
 
@@ -107,14 +109,30 @@ impl BudgetRepo {
         sqlx::query_as::<_, Budget>("SELECT * FROM budgets WHERE id = ?").bind(id).fetch_optional(pool).await?
             .ok_or(AppError::NotFound)
     }
-    pub async fn create(pool: &DbPool, req: CreateBudgetReq) -> Result<Budget, AppError> {
+    /// Create the budget with its owner membership and the seed categories in one transaction.
+    pub async fn create(pool: &DbPool, req: CreateBudgetReq, categories: &[TemplateCategory]) -> Result<Budget, AppError> {
         let id = uuid::Uuid::new_v4().to_string();
         let currency = req.currency_code.unwrap_or_else(|| "USD".to_string());
         let budget_type = req.budget_type.unwrap_or(BudgetType::Standard);
+        let mut tx = pool.begin().await?;
         sqlx::query("INSERT INTO budgets (id, owner_id, name, currency_code, budget_type, description) VALUES (?, ?, ?, ?, ?, ?)")
-            .bind(&id).bind(&req.owner_id).bind(&req.name).bind(&currency).bind(budget_type.to_string()).bind(&req.description).execute(pool).await?;
+            .bind(&id).bind(&req.owner_id).bind(&req.name).bind(&currency).bind(budget_type.to_string()).bind(&req.description).execute(&mut *tx).await?;
         sqlx::query("INSERT INTO budget_members (budget_id, user_id, role) VALUES (?, ?, 'owner')")
-            .bind(&id).bind(&req.owner_id).execute(pool).await?;
+            .bind(&id).bind(&req.owner_id).execute(&mut *tx).await?;
+
+        let category_ids: HashMap<&str, String> = categories.iter().map(|c| (c.id.as_str(), uuid::Uuid::new_v4().to_string())).collect();
+        for c in categories {
+            sqlx::query("INSERT INTO categories (id, budget_id, name, kind, color, icon, rollover_policy) VALUES (?, ?, ?, ?, ?, ?, ?)")
+                .bind(&category_ids[c.id.as_str()]).bind(&id).bind(&c.name).bind(&c.kind).bind(&c.color).bind(&c.icon).bind(&c.rollover_policy)
+                .execute(&mut *tx).await?;
+        }
+        // Parents are linked once every category exists, whatever the template order
+        for c in categories {
+            let Some(parent_id) = c.parent_id.as_deref().and_then(|p| category_ids.get(p)) else { continue };
+            sqlx::query("UPDATE categories SET parent_id = ? WHERE id = ?")
+                .bind(parent_id).bind(&category_ids[c.id.as_str()]).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
         Self::get(pool, &id).await
     }
     
@@ -144,15 +162,16 @@ impl BudgetRepo {
     }
     
     pub async fn get_balance(pool: &DbPool, id: &str) -> Result<(i64, i64), AppError> {
-        let result = sqlx::query_as::<_, (i64, i64)>(
+        let result = sqlx::query_as::<_, (i64, i64)>(&format!(
             r#"
             SELECT 
                 CAST(COALESCE(SUM(CASE WHEN kind = 'income' THEN amount_minor ELSE 0 END), 0) AS SIGNED) as income,
                 CAST(COALESCE(SUM(CASE WHEN kind = 'expense' THEN amount_minor ELSE 0 END), 0) AS SIGNED) as expense
-            FROM entries
-            WHERE budget_id = ? AND deleted_at IS NULL
-            "#
-        )
+            FROM ({}) lines
+            WHERE budget_id = ?
+            "#,
+            ENTRY_LINES
+        ))
         .bind(id)
         .fetch_one(pool)
         .await?;
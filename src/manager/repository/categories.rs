@@ -1,4 +1,4 @@
-use crate::manager::{models::category::{Category, CreateCategoryReq}};
+use crate::manager::{models::category::{Category, CreateCategoryReq, MergeCategoryReport, RolloverPolicy}};
 use crate::utils::{database::database::DbPool, error::error::AppError};
 
 pub struct CategoryRepo;
@@ -6,10 +6,10 @@ pub struct CategoryRepo;
 impl CategoryRepo {
     pub async fn list(pool: &DbPool, budget_id: &str, kind: Option<String>) -> Result<Vec<Category>, AppError> {
         let rows = if let Some(k) = kind {
-            sqlx::query_as::<_, Category>("SELECT id, budget_id, name, kind, is_hidden, color, icon, created_at, updated_at FROM categories WHERE budget_id = ? AND kind = ? ORDER BY name ASC")
+            sqlx::query_as::<_, Category>("SELECT id, budget_id, parent_id, name, kind, is_hidden, color, icon, rollover_policy, created_at, updated_at FROM categories WHERE budget_id = ? AND kind = ? ORDER BY name ASC")
                 .bind(budget_id).bind(k).fetch_all(pool).await?
         } else {
-            sqlx::query_as::<_, Category>("SELECT id, budget_id, name, kind, is_hidden, color, icon, created_at, updated_at FROM categories WHERE budget_id = ? ORDER BY name ASC")
+            sqlx::query_as::<_, Category>("SELECT id, budget_id, parent_id, name, kind, is_hidden, color, icon, rollover_policy, created_at, updated_at FROM categories WHERE budget_id = ? ORDER BY name ASC")
                 .bind(budget_id).fetch_all(pool).await?
         };
         Ok(rows)
@@ -17,21 +17,24 @@ impl CategoryRepo {
     pub async fn create(pool: &DbPool, budget_id: &str, req: CreateCategoryReq) -> Result<Category, AppError> {
         let id = uuid::Uuid::new_v4().to_string();
         let hidden = req.is_hidden.unwrap_or(false);
+        let rollover_policy = req.rollover_policy.unwrap_or(RolloverPolicy::None);
         let now = chrono::Utc::now();
         
-        sqlx::query("INSERT INTO categories (id, budget_id, name, kind, is_hidden, color, icon, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)")
+        sqlx::query("INSERT INTO categories (id, budget_id, parent_id, name, kind, is_hidden, color, icon, rollover_policy, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)")
             .bind(&id)
             .bind(budget_id)
+            .bind(&req.parent_id)
             .bind(&req.name)
             .bind(&req.kind)
             .bind(hidden)
             .bind(&req.color)
             .bind(&req.icon)
+            .bind(rollover_policy.to_string())
             .bind(&now)
             .bind(&now)
             .execute(pool).await?;
             
-        Ok(sqlx::query_as::<_, Category>("SELECT id, budget_id, name, kind, is_hidden, color, icon, created_at, updated_at FROM categories WHERE id = ?")
+        Ok(sqlx::query_as::<_, Category>("SELECT id, budget_id, parent_id, name, kind, is_hidden, color, icon, rollover_policy, created_at, updated_at FROM categories WHERE id = ?")
             .bind(&id).fetch_one(pool).await?)
     }
 
@@ -44,22 +47,30 @@ impl CategoryRepo {
         // Use current values as defaults for fields not being updated
         let name = req.name.as_ref().unwrap_or(&current.name);
         let kind = req.kind.as_ref().unwrap_or(&current.kind);
+        let parent_id = match req.parent_id.as_deref() {
+            Some("") => None,
+            Some(parent_id) => Some(parent_id),
+            None => current.parent_id.as_deref(),
+        };
         let is_hidden = req.is_hidden.unwrap_or(current.is_hidden);
         let color = req.color.as_ref().or(current.color.as_ref());
         let icon = req.icon.as_ref().or(current.icon.as_ref());
+        let rollover_policy = req.rollover_policy.unwrap_or(current.rollover_policy);
         
-        sqlx::query("UPDATE categories SET name = ?, kind = ?, is_hidden = ?, color = ?, icon = ?, updated_at = ? WHERE id = ? AND budget_id = ?")
+        sqlx::query("UPDATE categories SET name = ?, kind = ?, parent_id = ?, is_hidden = ?, color = ?, icon = ?, rollover_policy = ?, updated_at = ? WHERE id = ? AND budget_id = ?")
             .bind(name)
             .bind(kind)
+            .bind(parent_id)
             .bind(is_hidden)
             .bind(color)
             .bind(icon)
+            .bind(rollover_policy.to_string())
             .bind(&now)
             .bind(category_id)
             .bind(budget_id)
             .execute(pool).await?;
         
-        Ok(sqlx::query_as::<_, Category>("SELECT id, budget_id, name, kind, is_hidden, color, icon, created_at, updated_at FROM categories WHERE id = ? AND budget_id = ?")
+        Ok(sqlx::query_as::<_, Category>("SELECT id, budget_id, parent_id, name, kind, is_hidden, color, icon, rollover_policy, created_at, updated_at FROM categories WHERE id = ? AND budget_id = ?")
             .bind(category_id).bind(budget_id).fetch_one(pool).await?)
     }
 
@@ -74,8 +85,75 @@ impl CategoryRepo {
         Ok(())
     }
 
+    pub async fn set_hidden(pool: &DbPool, budget_id: &str, category_id: &str, hidden: bool) -> Result<Category, AppError> {
+        let result = sqlx::query("UPDATE categories SET is_hidden = ?, updated_at = ? WHERE id = ? AND budget_id = ?")
+            .bind(hidden).bind(chrono::Utc::now()).bind(category_id).bind(budget_id).execute(pool).await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Self::get_by_id(pool, budget_id, category_id).await
+    }
+
+    /// Move the direct children of a category under `new_parent_id` (None = top level).
+    pub async fn reparent_children(pool: &DbPool, budget_id: &str, category_id: &str, new_parent_id: Option<&str>) -> Result<u64, AppError> {
+        let result = sqlx::query("UPDATE categories SET parent_id = ?, updated_at = ? WHERE parent_id = ? AND budget_id = ?")
+            .bind(new_parent_id).bind(chrono::Utc::now()).bind(category_id).bind(budget_id).execute(pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Re-point everything that references `source_id` (soft-deleted entries too) to
+    /// `target_id`, replace the target's limits with `limits` and remove the source,
+    /// all in one transaction.
+    pub async fn merge(pool: &DbPool, budget_id: &str, source_id: &str, target_id: &str, limits: &[(chrono::NaiveDate, i64)]) -> Result<MergeCategoryReport, AppError> {
+        let mut tx = pool.begin().await?;
+        let mut report = MergeCategoryReport { target_category_id: target_id.to_string(), ..Default::default() };
+
+        report.entries_moved = sqlx::query("UPDATE entries SET category_id = ? WHERE category_id = ? AND budget_id = ?")
+            .bind(target_id).bind(source_id).bind(budget_id).execute(&mut *tx).await?.rows_affected();
+        report.splits_moved = sqlx::query("UPDATE entry_splits SET category_id = ? WHERE category_id = ? AND budget_id = ?")
+            .bind(target_id).bind(source_id).bind(budget_id).execute(&mut *tx).await?.rows_affected();
+        report.recurring_moved = sqlx::query("UPDATE recurring_entries SET category_id = ? WHERE category_id = ? AND budget_id = ?")
+            .bind(target_id).bind(source_id).bind(budget_id).execute(&mut *tx).await?.rows_affected();
+        report.subcategories_moved = sqlx::query("UPDATE categories SET parent_id = ? WHERE parent_id = ? AND budget_id = ?")
+            .bind(target_id).bind(source_id).bind(budget_id).execute(&mut *tx).await?.rows_affected();
+        sqlx::query("UPDATE payees SET default_category_id = ? WHERE default_category_id = ? AND budget_id = ?")
+            .bind(target_id).bind(source_id).bind(budget_id).execute(&mut *tx).await?;
+        sqlx::query("UPDATE entry_rules SET set_category_id = ? WHERE set_category_id = ? AND budget_id = ?")
+            .bind(target_id).bind(source_id).bind(budget_id).execute(&mut *tx).await?;
+        sqlx::query("UPDATE scheduled_transfers SET from_category_id = ? WHERE from_category_id = ?")
+            .bind(target_id).bind(source_id).execute(&mut *tx).await?;
+        sqlx::query("UPDATE scheduled_transfers SET to_category_id = ? WHERE to_category_id = ?")
+            .bind(target_id).bind(source_id).execute(&mut *tx).await?;
+        sqlx::query("UPDATE scheduled_transfers SET fee_category_id = ? WHERE fee_category_id = ?")
+            .bind(target_id).bind(source_id).execute(&mut *tx).await?;
+        // A goal may already track the target, so links are copied before the source ones go
+        sqlx::query("INSERT IGNORE INTO savings_goal_categories (goal_id, category_id) SELECT goal_id, ? FROM savings_goal_categories WHERE category_id = ?")
+            .bind(target_id).bind(source_id).execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM savings_goal_categories WHERE category_id = ?")
+            .bind(source_id).execute(&mut *tx).await?;
+        sqlx::query("UPDATE debts SET category_id = ? WHERE category_id = ? AND budget_id = ?")
+            .bind(target_id).bind(source_id).bind(budget_id).execute(&mut *tx).await?;
+
+        report.limits_merged = sqlx::query("DELETE FROM category_limits WHERE category_id = ? AND budget_id = ?")
+            .bind(source_id).bind(budget_id).execute(&mut *tx).await?.rows_affected();
+        if report.limits_merged > 0 {
+            sqlx::query("DELETE FROM category_limits WHERE category_id = ? AND budget_id = ?")
+                .bind(target_id).bind(budget_id).execute(&mut *tx).await?;
+            for (period_start, planned_minor) in limits {
+                sqlx::query("INSERT INTO category_limits (id, budget_id, category_id, period_start, planned_minor) VALUES (?, ?, ?, ?, ?)")
+                    .bind(uuid::Uuid::new_v4().to_string()).bind(budget_id).bind(target_id).bind(period_start).bind(planned_minor)
+                    .execute(&mut *tx).await?;
+            }
+        }
+
+        sqlx::query("DELETE FROM categories WHERE id = ? AND budget_id = ?")
+            .bind(source_id).bind(budget_id).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(report)
+    }
+
     pub async fn get_by_id(pool: &DbPool, budget_id: &str, category_id: &str) -> Result<Category, AppError> {
-        Ok(sqlx::query_as::<_, Category>("SELECT id, budget_id, name, kind, is_hidden, color, icon, created_at, updated_at FROM categories WHERE id = ? AND budget_id = ?")
+        Ok(sqlx::query_as::<_, Category>("SELECT id, budget_id, parent_id, name, kind, is_hidden, color, icon, rollover_policy, created_at, updated_at FROM categories WHERE id = ? AND budget_id = ?")
             .bind(category_id).bind(budget_id).fetch_one(pool).await?)
     }
 }
@@ -0,0 +1,74 @@
+use crate::manager::models::category_limit::{CategoryLimit, CreateCategoryLimitReq};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct CategoryLimitRepo;
+
+impl CategoryLimitRepo {
+    pub async fn list_by_category(pool: &DbPool, budget_id: &str, category_id: &str) -> Result<Vec<CategoryLimit>, AppError> {
+        Ok(sqlx::query_as::<_, CategoryLimit>(
+            "SELECT * FROM category_limits WHERE budget_id = ? AND category_id = ? ORDER BY period_start DESC"
+        )
+        .bind(budget_id)
+        .bind(category_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn list_by_budget(pool: &DbPool, budget_id: &str) -> Result<Vec<CategoryLimit>, AppError> {
+        Ok(sqlx::query_as::<_, CategoryLimit>(
+            "SELECT * FROM category_limits WHERE budget_id = ? ORDER BY period_start ASC"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn get_by_id(pool: &DbPool, budget_id: &str, category_id: &str, limit_id: &str) -> Result<CategoryLimit, AppError> {
+        sqlx::query_as::<_, CategoryLimit>(
+            "SELECT * FROM category_limits WHERE id = ? AND budget_id = ? AND category_id = ?"
+        )
+        .bind(limit_id)
+        .bind(budget_id)
+        .bind(category_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::NotFound)
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, category_id: &str, req: &CreateCategoryLimitReq) -> Result<CategoryLimit, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query("INSERT INTO category_limits (id, budget_id, category_id, period_start, planned_minor) VALUES (?, ?, ?, ?, ?)")
+            .bind(&id)
+            .bind(budget_id)
+            .bind(category_id)
+            .bind(req.period_start)
+            .bind(req.planned_minor)
+            .execute(pool)
+            .await?;
+        Self::get_by_id(pool, budget_id, category_id, &id).await
+    }
+
+    pub async fn update(pool: &DbPool, limit: &CategoryLimit) -> Result<CategoryLimit, AppError> {
+        sqlx::query("UPDATE category_limits SET period_start = ?, planned_minor = ? WHERE id = ? AND budget_id = ?")
+            .bind(limit.period_start)
+            .bind(limit.planned_minor)
+            .bind(&limit.id)
+            .bind(&limit.budget_id)
+            .execute(pool)
+            .await?;
+        Self::get_by_id(pool, &limit.budget_id, &limit.category_id, &limit.id).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, category_id: &str, limit_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM category_limits WHERE id = ? AND budget_id = ? AND category_id = ?")
+            .bind(limit_id)
+            .bind(budget_id)
+            .bind(category_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+}
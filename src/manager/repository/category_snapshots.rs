@@ -0,0 +1,77 @@
+use crate::manager::models::category_limit::CategoryMonthSnapshot;
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct CategorySnapshotRepo;
+
+impl CategorySnapshotRepo {
+    pub async fn list_for_month(pool: &DbPool, budget_id: &str, month_start: chrono::NaiveDate) -> Result<Vec<CategoryMonthSnapshot>, AppError> {
+        Ok(sqlx::query_as::<_, CategoryMonthSnapshot>(
+            "SELECT budget_id, category_id, month_start, planned_minor, spent_minor, rollover_minor, available_minor \
+             FROM category_month_snapshots WHERE budget_id = ? AND month_start = ?"
+        )
+        .bind(budget_id)
+        .bind(month_start)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    /// Latest cached month on or before `month_start`, if any.
+    pub async fn latest_cached_month(pool: &DbPool, budget_id: &str, month_start: chrono::NaiveDate) -> Result<Option<chrono::NaiveDate>, AppError> {
+        Ok(sqlx::query_scalar::<_, Option<chrono::NaiveDate>>(
+            "SELECT MAX(month_start) FROM category_month_snapshots WHERE budget_id = ? AND month_start <= ?"
+        )
+        .bind(budget_id)
+        .bind(month_start)
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn save_month(pool: &DbPool, budget_id: &str, month_start: chrono::NaiveDate, snapshots: &[CategoryMonthSnapshot]) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM category_month_snapshots WHERE budget_id = ? AND month_start = ?")
+            .bind(budget_id)
+            .bind(month_start)
+            .execute(&mut *tx)
+            .await?;
+
+        for s in snapshots {
+            sqlx::query(
+                "INSERT INTO category_month_snapshots \
+                 (budget_id, category_id, month_start, planned_minor, spent_minor, rollover_minor, available_minor) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(&s.budget_id)
+            .bind(&s.category_id)
+            .bind(s.month_start)
+            .bind(s.planned_minor)
+            .bind(s.spent_minor)
+            .bind(s.rollover_minor)
+            .bind(s.available_minor)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Drop cached months from `month_start` onwards; anything after a changed
+    /// month depends on it through the rollover chain.
+    pub async fn invalidate_from(pool: &DbPool, budget_id: &str, month_start: chrono::NaiveDate) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM category_month_snapshots WHERE budget_id = ? AND month_start >= ?")
+            .bind(budget_id)
+            .bind(month_start)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn invalidate_all(pool: &DbPool, budget_id: &str) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM category_month_snapshots WHERE budget_id = ?")
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
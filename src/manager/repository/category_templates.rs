@@ -0,0 +1,86 @@
+use crate::manager::models::category_template::{CategoryTemplate, TemplateCategory};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct CategoryTemplateRepo;
+
+impl CategoryTemplateRepo {
+    pub async fn list_for_owner(pool: &DbPool, owner_id: &str) -> Result<Vec<CategoryTemplate>, AppError> {
+        Ok(sqlx::query_as::<_, CategoryTemplate>(
+            "SELECT id, name, budget_type, created_at FROM category_templates WHERE owner_id = ? ORDER BY name ASC"
+        )
+        .bind(owner_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn get(pool: &DbPool, owner_id: &str, template_id: &str) -> Result<CategoryTemplate, AppError> {
+        sqlx::query_as::<_, CategoryTemplate>(
+            "SELECT id, name, budget_type, created_at FROM category_templates WHERE id = ? AND owner_id = ?"
+        )
+        .bind(template_id)
+        .bind(owner_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::NotFound)
+    }
+
+    pub async fn items(pool: &DbPool, template_ids: &[String]) -> Result<Vec<TemplateCategory>, AppError> {
+        if template_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; template_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT id, template_id, parent_id, name, kind, color, icon, rollover_policy, position \
+             FROM category_template_items WHERE template_id IN ({}) ORDER BY template_id, position ASC",
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, TemplateCategory>(&sql);
+        for id in template_ids {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(pool).await?)
+    }
+
+    /// Items must already carry the new template's ids.
+    pub async fn create(pool: &DbPool, template_id: &str, owner_id: &str, name: &str, budget_type: &str, items: &[TemplateCategory]) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("INSERT INTO category_templates (id, owner_id, name, budget_type) VALUES (?, ?, ?, ?)")
+            .bind(template_id)
+            .bind(owner_id)
+            .bind(name)
+            .bind(budget_type)
+            .execute(&mut *tx)
+            .await?;
+        for item in items {
+            sqlx::query(
+                "INSERT INTO category_template_items (id, template_id, parent_id, name, kind, color, icon, rollover_policy, position) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(&item.id)
+            .bind(template_id)
+            .bind(&item.parent_id)
+            .bind(&item.name)
+            .bind(&item.kind)
+            .bind(&item.color)
+            .bind(&item.icon)
+            .bind(&item.rollover_policy)
+            .bind(item.position)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &DbPool, owner_id: &str, template_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM category_templates WHERE id = ? AND owner_id = ?")
+            .bind(template_id)
+            .bind(owner_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use crate::manager::models::debt::{CreateDebtReq, Debt};
+use crate::manager::repository::entries::ENTRY_LINES;
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct DebtRepo;
+
+impl DebtRepo {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<Debt>, AppError> {
+        Ok(sqlx::query_as::<_, Debt>("SELECT * FROM debts WHERE budget_id = ? ORDER BY name ASC, created_at ASC")
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?)
+    }
+
+    pub async fn get_by_id(pool: &DbPool, budget_id: &str, debt_id: &str) -> Result<Debt, AppError> {
+        sqlx::query_as::<_, Debt>("SELECT * FROM debts WHERE id = ? AND budget_id = ?")
+            .bind(debt_id)
+            .bind(budget_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// `req` must be validated.
+    pub async fn create(pool: &DbPool, budget_id: &str, req: &CreateDebtReq, user_id: &str) -> Result<Debt, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO debts (id, budget_id, name, principal_minor, apr, minimum_payment_minor, due_day, category_id, opened_on, note, created_by) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+            .bind(&id)
+            .bind(budget_id)
+            .bind(&req.name)
+            .bind(req.principal_minor)
+            .bind(req.apr)
+            .bind(req.minimum_payment_minor.unwrap_or(0))
+            .bind(req.due_day.unwrap_or(1))
+            .bind(&req.category_id)
+            .bind(req.opened_on)
+            .bind(&req.note)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Self::get_by_id(pool, budget_id, &id).await
+    }
+
+    pub async fn update(pool: &DbPool, debt: &Debt) -> Result<Debt, AppError> {
+        sqlx::query(
+            "UPDATE debts SET name = ?, principal_minor = ?, apr = ?, minimum_payment_minor = ?, due_day = ?, category_id = ?, \
+             opened_on = ?, note = ?, updated_at = ? WHERE id = ? AND budget_id = ?"
+        )
+            .bind(&debt.name)
+            .bind(debt.principal_minor)
+            .bind(debt.apr)
+            .bind(debt.minimum_payment_minor)
+            .bind(debt.due_day)
+            .bind(&debt.category_id)
+            .bind(debt.opened_on)
+            .bind(&debt.note)
+            .bind(chrono::Utc::now().naive_utc())
+            .bind(&debt.id)
+            .bind(&debt.budget_id)
+            .execute(pool)
+            .await?;
+        Self::get_by_id(pool, &debt.budget_id, &debt.id).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, debt_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM debts WHERE id = ? AND budget_id = ?")
+            .bind(debt_id)
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Expenses minus income in the categories, from `from` on.
+    pub async fn payments(pool: &DbPool, budget_id: &str, category_ids: &[String], from: Option<chrono::NaiveDate>) -> Result<i64, AppError> {
+        if category_ids.is_empty() {
+            return Ok(0);
+        }
+        let sql = format!(r#"
+            SELECT CAST(COALESCE(SUM(CASE WHEN kind='expense' THEN amount_minor ELSE -amount_minor END), 0) AS SIGNED)
+            FROM ({}) lines
+            WHERE budget_id = ?
+              AND (? IS NULL OR entry_date >= ?)
+              AND category_id IN ({})
+        "#, ENTRY_LINES, vec!["?"; category_ids.len()].join(", "));
+
+        let mut query = sqlx::query_scalar::<_, i64>(&sql)
+            .bind(budget_id)
+            .bind(from)
+            .bind(from);
+        for id in category_ids {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_one(pool).await?)
+    }
+}
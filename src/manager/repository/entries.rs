@@ -1,8 +1,69 @@
-use crate::manager::{models::entry::{Entry, CreateEntryReq}};
+use crate::manager::{models::entry::{Entry, CreateEntryReq, RecategorizeReq, RecategorizeReport}, models::export::{ExportQuery, ExportRow}, repository::entry_splits::EntrySplitRepo, repository::tags::TagRepo, models::tag::TagFilter};
 use crate::handler::entries::UpdateEntryReq;
 use crate::utils::{database::database::DbPool, error::error::AppError};
+use sqlx::{mysql::MySqlArguments, query::QueryAs, MySql, MySqlConnection};
 pub struct EntryRepo;
 
+/// Amount lines per category for reports: split entries contribute their line
+/// items instead of the parent, every other entry contributes itself.
+/// `amount_minor` is in the budget currency.
+pub(crate) const ENTRY_LINES: &str = "SELECT e.budget_id, e.entry_date, e.kind, e.transfer_id, \
+    COALESCE(s.category_id, e.category_id) AS category_id, \
+    COALESCE(s.base_amount_minor, e.base_amount_minor) AS amount_minor \
+    FROM entries e LEFT JOIN entry_splits s ON s.entry_id = e.id \
+    WHERE e.deleted_at IS NULL";
+
+/// Entry conditions shared by listing, export and bulk updates. Conditions
+/// are appended to a query on `entries e` and bound in the same order.
+struct EntryFilter<'a> {
+    kind: Option<&'a str>,
+    category_id: Option<&'a str>,
+    member_id: Option<&'a str>,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+    search: Option<&'a str>,
+    tags: Option<&'a TagFilter>,
+    /// Leave out transfer legs and investment cash entries, which bulk edits
+    /// must not touch.
+    editable_only: bool,
+}
+
+impl EntryFilter<'_> {
+    fn push_conditions(&self, q: &mut String) {
+        if self.kind.is_some() { q.push_str(" AND e.kind = ?"); }
+        if self.category_id.is_some() {
+            q.push_str(" AND (e.category_id = ? OR EXISTS (SELECT 1 FROM entry_splits s WHERE s.entry_id = e.id AND s.category_id = ?))");
+        }
+        if self.member_id.is_some() { q.push_str(" AND e.created_by = ?"); }
+        if self.from.is_some() { q.push_str(" AND e.entry_date >= ?"); }
+        if self.to.is_some() { q.push_str(" AND e.entry_date <= ?"); }
+        // Search in description (supports Vietnamese with approximate matching)
+        if self.search.is_some() {
+            q.push_str(" AND (e.description LIKE ? OR e.counterparty LIKE ?)");
+        }
+        if let Some(t) = self.tags { q.push_str(&t.sql_condition()); }
+        if self.editable_only {
+            q.push_str(" AND e.transfer_id IS NULL AND NOT EXISTS (SELECT 1 FROM investment_transactions it WHERE it.entry_id = e.id)");
+        }
+    }
+
+    fn bind<'q, O>(&self, mut query: QueryAs<'q, MySql, O, MySqlArguments>) -> QueryAs<'q, MySql, O, MySqlArguments> {
+        if let Some(k) = self.kind { query = query.bind(k.to_string()); }
+        if let Some(c) = self.category_id { query = query.bind(c.to_string()).bind(c.to_string()); }
+        if let Some(m) = self.member_id { query = query.bind(m.to_string()); }
+        if let Some(f) = self.from { query = query.bind(f); }
+        if let Some(t) = self.to { query = query.bind(t); }
+        if let Some(s) = self.search {
+            let pattern = format!("%{}%", s);
+            query = query.bind(pattern.clone()).bind(pattern);
+        }
+        if let Some(t) = self.tags {
+            for id in &t.tag_ids { query = query.bind(id.clone()); }
+        }
+        query
+    }
+}
+
 impl EntryRepo {
     pub async fn list(
         pool: &DbPool,
@@ -13,14 +74,15 @@ impl EntryRepo {
         category_id: Option<String>,
         member_id: Option<String>,
         search: Option<String>,
+        tags: Option<&TagFilter>,
         sort_by: Option<String>,
         sort_order: Option<String>,
         page: Option<u32>,
         per_page: Option<u32>,
     ) -> Result<Vec<Entry>, AppError> {
         let mut q = String::from(
-            "SELECT e.id, e.budget_id, e.category_id, e.kind, e.amount_minor, e.currency_code, \
-             e.entry_date, e.description, e.counterparty, e.created_by, e.updated_by, \
+            "SELECT e.id, e.budget_id, e.category_id, e.kind, e.amount_minor, e.currency_code, e.base_amount_minor, e.exchange_rate, \
+             e.entry_date, e.description, e.counterparty, e.payee_id, e.transfer_id, e.created_by, e.updated_by, \
              e.created_at, e.updated_at, e.deleted_at, e.comment_count, e.attachment_count, \
              u.name as member_name, u.email as member_email, u.avatar as member_avatar \
              FROM entries e \
@@ -29,20 +91,21 @@ impl EntryRepo {
         );
         
         // Filters
-        if kind.is_some() { q.push_str(" AND e.kind = ?"); }
-        if category_id.is_some() { q.push_str(" AND e.category_id = ?"); }
-        if member_id.is_some() { q.push_str(" AND e.created_by = ?"); }
-        if from.is_some() { q.push_str(" AND e.entry_date >= ?"); }
-        if to.is_some() { q.push_str(" AND e.entry_date <= ?"); }
-        
-        // Search in description (supports Vietnamese with approximate matching)
-        if search.is_some() {
-            q.push_str(" AND (e.description LIKE ? OR e.counterparty LIKE ?)");
-        }
+        let filter = EntryFilter {
+            kind: kind.as_deref(),
+            category_id: category_id.as_deref(),
+            member_id: member_id.as_deref(),
+            from,
+            to,
+            search: search.as_deref(),
+            tags,
+            editable_only: false,
+        };
+        filter.push_conditions(&mut q);
         
         // Sorting
         let sort_field = match sort_by.as_deref() {
-            Some("amount") => "e.amount_minor",
+            Some("amount") => "e.base_amount_minor",
             Some("description") => "e.description",
             _ => "e.entry_date", // default to date
         };
@@ -59,33 +122,165 @@ impl EntryRepo {
         q.push_str(&format!(" LIMIT {} OFFSET {}", per_page, offset));
         
         // Build query with bindings
-        let search_pattern = search.as_ref().map(|s| format!("%{}%", s));
-        
-        let mut query = sqlx::query_as::<_, Entry>(&q).bind(budget_id);
-        if let Some(k) = kind { query = query.bind(k); }
-        if let Some(c) = category_id { query = query.bind(c); }
-        if let Some(m) = member_id { query = query.bind(m); }
-        if let Some(f) = from { query = query.bind(f); }
-        if let Some(t) = to { query = query.bind(t); }
-        if let Some(ref pattern) = search_pattern {
-            query = query.bind(pattern).bind(pattern);
-        }
-        
+        let query = filter.bind(sqlx::query_as::<_, Entry>(&q).bind(budget_id));
         Ok(query.fetch_all(pool).await?)
     }
-    pub async fn create(pool: &DbPool, budget_id: &str, req: CreateEntryReq, default_currency: &str) -> Result<Entry, AppError> {
-        let id = uuid::Uuid::new_v4().to_string();
-        let currency = req.currency_code.unwrap_or_else(|| default_currency.to_string());
+    /// One page of non-deleted entries for export, with category and member names.
+    /// `editable_only` skips transfer legs and investment cash entries.
+    pub async fn export_page(pool: &DbPool, budget_id: &str, filter: &ExportQuery, editable_only: bool, limit: u32, offset: u32) -> Result<Vec<ExportRow>, AppError> {
+        let tags = TagFilter::parse(filter.tags.as_deref(), filter.tag_match.as_deref())?;
+        let mut q = String::from(
+            "SELECT e.id, e.entry_date, e.kind, e.category_id, c.name as category_name, e.amount_minor, \
+             e.currency_code, e.base_amount_minor, e.exchange_rate, e.description, e.counterparty, e.created_by, u.name as member_name, \
+             e.comment_count, e.attachment_count, e.created_at \
+             FROM entries e \
+             INNER JOIN users u ON e.created_by = u.id \
+             LEFT JOIN categories c ON e.category_id = c.id \
+             WHERE e.budget_id = ? AND e.deleted_at IS NULL"
+        );
+
+        let conditions = EntryFilter {
+            kind: filter.kind.as_deref(),
+            category_id: filter.category_id.as_deref(),
+            member_id: filter.member_id.as_deref(),
+            from: filter.from,
+            to: filter.to,
+            search: filter.search.as_deref(),
+            tags: tags.as_ref(),
+            editable_only,
+        };
+        conditions.push_conditions(&mut q);
+
+        let sort_field = match filter.sort_by.as_deref() {
+            Some("amount") => "e.base_amount_minor",
+            Some("description") => "e.description",
+            _ => "e.entry_date",
+        };
+        let order = match filter.sort_order.as_deref() {
+            Some("asc") => "ASC",
+            _ => "DESC",
+        };
+        // e.id keeps the order stable across pages
+        q.push_str(&format!(" ORDER BY {} {}, e.created_at DESC, e.id ASC LIMIT {} OFFSET {}", sort_field, order, limit, offset));
+
+        let query = conditions.bind(sqlx::query_as::<_, ExportRow>(&q).bind(budget_id));
+        Ok(query.fetch_all(pool).await?)
+    }
+
+    /// Move the non-deleted entries of `kind` matching the filter to the target category
+    /// in one transaction. Split entries only have their lines in the filtered
+    /// category moved; without a category filter they are left alone.
+    pub async fn recategorize(pool: &DbPool, budget_id: &str, req: &RecategorizeReq, kind: &str, tags: Option<&TagFilter>, user_id: &str) -> Result<RecategorizeReport, AppError> {
+        let mut q = String::from(
+            "SELECT e.id, (SELECT COUNT(*) FROM entry_splits s WHERE s.entry_id = e.id) AS split_count \
+             FROM entries e WHERE e.budget_id = ? AND e.deleted_at IS NULL"
+        );
+        let filter = EntryFilter {
+            kind: Some(kind),
+            category_id: req.category_id.as_deref(),
+            member_id: req.member_id.as_deref(),
+            from: req.from,
+            to: req.to,
+            search: req.search.as_deref(),
+            tags,
+            editable_only: true,
+        };
+        filter.push_conditions(&mut q);
+        q.push_str(" FOR UPDATE");
+
+        let query = filter.bind(sqlx::query_as::<_, (String, i64)>(&q).bind(budget_id));
+
+        let mut tx = pool.begin().await?;
+        let matched = query.fetch_all(&mut *tx).await?;
+        let (split, plain): (Vec<_>, Vec<_>) = matched.into_iter().partition(|(_, split_count)| *split_count > 0);
+        let now = chrono::Utc::now().naive_utc();
+        let mut report = RecategorizeReport { moved: 0, split_lines_moved: 0, skipped_split: 0 };
+
+        for chunk in plain.chunks(500) {
+            let sql = format!(
+                "UPDATE entries SET category_id = ?, updated_by = ?, updated_at = ? WHERE budget_id = ? AND id IN ({})",
+                vec!["?"; chunk.len()].join(", ")
+            );
+            let mut update = sqlx::query(&sql).bind(&req.target_category_id).bind(user_id).bind(now).bind(budget_id);
+            for (id, _) in chunk { update = update.bind(id); }
+            report.moved += update.execute(&mut *tx).await?.rows_affected();
+        }
+
+        match &req.category_id {
+            Some(source_id) => {
+                for chunk in split.chunks(500) {
+                    let placeholders = vec!["?"; chunk.len()].join(", ");
+                    let sql = format!("UPDATE entry_splits SET category_id = ? WHERE category_id = ? AND entry_id IN ({})", placeholders);
+                    let mut update = sqlx::query(&sql).bind(&req.target_category_id).bind(source_id);
+                    for (id, _) in chunk { update = update.bind(id); }
+                    report.split_lines_moved += update.execute(&mut *tx).await?.rows_affected();
+
+                    // The parent mirrors its first line for list views
+                    let sql = format!(
+                        "UPDATE entries SET category_id = ?, updated_by = ?, updated_at = ? WHERE category_id = ? AND budget_id = ? AND id IN ({})",
+                        placeholders
+                    );
+                    let mut update = sqlx::query(&sql).bind(&req.target_category_id).bind(user_id).bind(now).bind(source_id).bind(budget_id);
+                    for (id, _) in chunk { update = update.bind(id); }
+                    update.execute(&mut *tx).await?;
+                }
+            }
+            None => report.skipped_split = split.len() as u64,
+        }
+        tx.commit().await?;
+        Ok(report)
+    }
+
+    /// Non-deleted entries in date order for the journal export. Transfer legs are
+    /// left out; the journal renders each transfer as a single transaction.
+    pub async fn list_for_journal(pool: &DbPool, budget_id: &str, from: Option<chrono::NaiveDate>, to: Option<chrono::NaiveDate>) -> Result<Vec<ExportRow>, AppError> {
+        Ok(sqlx::query_as::<_, ExportRow>(
+            "SELECT e.id, e.entry_date, e.kind, e.category_id, c.name as category_name, e.amount_minor, \
+             e.currency_code, e.base_amount_minor, e.exchange_rate, e.description, e.counterparty, e.created_by, u.name as member_name, \
+             e.comment_count, e.attachment_count, e.created_at \
+             FROM entries e \
+             INNER JOIN users u ON e.created_by = u.id \
+             LEFT JOIN categories c ON e.category_id = c.id \
+             WHERE e.budget_id = ? AND e.deleted_at IS NULL AND e.transfer_id IS NULL \
+               AND (? IS NULL OR e.entry_date >= ?) AND (? IS NULL OR e.entry_date <= ?) \
+             ORDER BY e.entry_date ASC, e.created_at ASC, e.id ASC"
+        )
+        .bind(budget_id)
+        .bind(from)
+        .bind(from)
+        .bind(to)
+        .bind(to)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    /// Insert an entry row on an existing connection so callers can batch inserts in a transaction.
+    pub async fn insert(conn: &mut MySqlConnection, id: &str, budget_id: &str, req: &CreateEntryReq, default_currency: &str) -> Result<(), AppError> {
+        let currency = req.currency_code.clone().unwrap_or_else(|| default_currency.to_string());
         sqlx::query(r#"
-                INSERT INTO entries (id, budget_id, category_id, kind, amount_minor, currency_code, entry_date, description, counterparty, created_by)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                INSERT INTO entries (id, budget_id, category_id, kind, amount_minor, currency_code, base_amount_minor, exchange_rate,
+                                     entry_date, description, counterparty, payee_id, created_by)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#)
-            .bind(&id).bind(budget_id).bind(&req.category_id).bind(&req.kind).bind(req.amount_minor)
-            .bind(&currency).bind(req.entry_date).bind(&req.description).bind(&req.counterparty).bind(&req.created_by)
-            .execute(pool).await?;
+            .bind(id).bind(budget_id).bind(&req.category_id).bind(&req.kind).bind(req.amount_minor)
+            .bind(&currency).bind(req.base_amount_minor).bind(req.exchange_rate).bind(req.entry_date).bind(&req.description).bind(&req.counterparty).bind(&req.payee_id).bind(&req.created_by)
+            .execute(&mut *conn).await?;
+        if let Some(splits) = req.splits.as_deref().filter(|s| !s.is_empty()) {
+            EntrySplitRepo::replace(conn, budget_id, id, splits, req.base_amount_minor).await?;
+        }
+        if let Some(tag_ids) = req.tag_ids.as_deref().filter(|t| !t.is_empty()) {
+            TagRepo::replace_for_entry(conn, id, tag_ids).await?;
+        }
+        Ok(())
+    }
+    pub async fn create(pool: &DbPool, budget_id: &str, req: CreateEntryReq, default_currency: &str) -> Result<Entry, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let mut tx = pool.begin().await?;
+        Self::insert(&mut tx, &id, budget_id, &req, default_currency).await?;
+        tx.commit().await?;
         Ok(sqlx::query_as::<_, Entry>(
-            "SELECT e.id, e.budget_id, e.category_id, e.kind, e.amount_minor, e.currency_code, \
-             e.entry_date, e.description, e.counterparty, e.created_by, e.updated_by, \
+            "SELECT e.id, e.budget_id, e.category_id, e.kind, e.amount_minor, e.currency_code, e.base_amount_minor, e.exchange_rate, \
+             e.entry_date, e.description, e.counterparty, e.payee_id, e.transfer_id, e.created_by, e.updated_by, \
              e.created_at, e.updated_at, e.deleted_at, e.comment_count, e.attachment_count, \
              u.name as member_name, u.email as member_email, u.avatar as member_avatar \
              FROM entries e \
@@ -94,11 +289,28 @@ impl EntryRepo {
         ).bind(&id).fetch_one(pool).await?)
     }
     
+    pub async fn get_by_id(pool: &DbPool, budget_id: &str, entry_id: &str) -> Result<Entry, AppError> {
+        sqlx::query_as::<_, Entry>(
+            "SELECT e.id, e.budget_id, e.category_id, e.kind, e.amount_minor, e.currency_code, e.base_amount_minor, e.exchange_rate, \
+             e.entry_date, e.description, e.counterparty, e.payee_id, e.transfer_id, e.created_by, e.updated_by, \
+             e.created_at, e.updated_at, e.deleted_at, e.comment_count, e.attachment_count, \
+             u.name as member_name, u.email as member_email, u.avatar as member_avatar \
+             FROM entries e \
+             INNER JOIN users u ON e.created_by = u.id \
+             WHERE e.id = ? AND e.budget_id = ? AND e.deleted_at IS NULL"
+        )
+            .bind(entry_id)
+            .bind(budget_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+    
     pub async fn update(pool: &DbPool, budget_id: &str, entry_id: &str, req: UpdateEntryReq, user_id: &str) -> Result<Entry, AppError> {
         // First check if entry exists and belongs to the budget
         let mut entry = sqlx::query_as::<_, Entry>(
-            "SELECT e.id, e.budget_id, e.category_id, e.kind, e.amount_minor, e.currency_code, \
-             e.entry_date, e.description, e.counterparty, e.created_by, e.updated_by, \
+            "SELECT e.id, e.budget_id, e.category_id, e.kind, e.amount_minor, e.currency_code, e.base_amount_minor, e.exchange_rate, \
+             e.entry_date, e.description, e.counterparty, e.payee_id, e.transfer_id, e.created_by, e.updated_by, \
              e.created_at, e.updated_at, e.deleted_at, e.comment_count, e.attachment_count, \
              u.name as member_name, u.email as member_email, u.avatar as member_avatar \
              FROM entries e \
@@ -121,6 +333,14 @@ impl EntryRepo {
         if let Some(amount_minor) = req.amount_minor {
             entry.amount_minor = amount_minor;
         }
+        if let Some(currency_code) = req.currency_code {
+            entry.currency_code = currency_code.to_uppercase();
+        }
+        let rebased = req.base_amount_minor.is_some_and(|base| base != entry.base_amount_minor);
+        if let Some(base_amount_minor) = req.base_amount_minor {
+            entry.base_amount_minor = base_amount_minor;
+            entry.exchange_rate = req.exchange_rate;
+        }
         if let Some(entry_date) = req.entry_date {
             entry.entry_date = entry_date;
         }
@@ -129,28 +349,43 @@ impl EntryRepo {
         }
         if let Some(counterparty) = req.counterparty {
             entry.counterparty = Some(counterparty);
+            entry.payee_id = req.payee_id;
         }
         
         entry.updated_by = Some(user_id.to_string());
         entry.updated_at = Some(chrono::Utc::now().naive_utc());
         
+        let mut tx = pool.begin().await?;
         sqlx::query(r#"
             UPDATE entries 
-            SET category_id = ?, kind = ?, amount_minor = ?, entry_date = ?, description = ?, counterparty = ?, updated_by = ?, updated_at = ?
+            SET category_id = ?, kind = ?, amount_minor = ?, currency_code = ?, base_amount_minor = ?, exchange_rate = ?, entry_date = ?, description = ?, counterparty = ?, payee_id = ?, updated_by = ?, updated_at = ?
             WHERE id = ? AND budget_id = ?
         "#)
         .bind(&entry.category_id)
         .bind(&entry.kind)
         .bind(entry.amount_minor)
+        .bind(&entry.currency_code)
+        .bind(entry.base_amount_minor)
+        .bind(entry.exchange_rate)
         .bind(entry.entry_date)
         .bind(&entry.description)
         .bind(&entry.counterparty)
+        .bind(&entry.payee_id)
         .bind(&entry.updated_by)
         .bind(&entry.updated_at)
         .bind(entry_id)
         .bind(budget_id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+        if let Some(splits) = &req.splits {
+            EntrySplitRepo::replace(&mut tx, budget_id, entry_id, splits, entry.base_amount_minor).await?;
+        } else if rebased {
+            EntrySplitRepo::rebase(&mut tx, entry_id, entry.base_amount_minor).await?;
+        }
+        if let Some(tag_ids) = &req.tag_ids {
+            TagRepo::replace_for_entry(&mut tx, entry_id, tag_ids).await?;
+        }
+        tx.commit().await?;
         
         Ok(entry)
     }
@@ -179,16 +414,39 @@ impl EntryRepo {
     pub async fn monthly_summary(pool: &DbPool, budget_id: &str, from: chrono::NaiveDate, to: chrono::NaiveDate)
                                  -> Result<Vec<(chrono::NaiveDate, i64, i64, i64)>, AppError>
     {
-        Ok(sqlx::query_as::<_, (chrono::NaiveDate, i64, i64, i64)>(r#"
+        Ok(sqlx::query_as::<_, (chrono::NaiveDate, i64, i64, i64)>(&format!(r#"
             SELECT DATE_SUB(entry_date, INTERVAL DAY(entry_date)-1 DAY) as month_start,
                    CAST(SUM(CASE WHEN kind='income'  THEN amount_minor ELSE 0 END) AS SIGNED) AS income_minor,
                    CAST(SUM(CASE WHEN kind='expense' THEN amount_minor ELSE 0 END) AS SIGNED) AS expense_minor,
                    CAST(SUM(CASE WHEN kind='income'  THEN amount_minor ELSE -amount_minor END) AS SIGNED) AS net_minor
-            FROM entries
-            WHERE budget_id = ? AND deleted_at IS NULL
+            FROM ({}) lines
+            WHERE budget_id = ?
               AND entry_date BETWEEN ? AND ?
             GROUP BY month_start
             ORDER BY month_start
-        "#).bind(budget_id).bind(from).bind(to).fetch_all(pool).await?)
+        "#, ENTRY_LINES)).bind(budget_id).bind(from).bind(to).fetch_all(pool).await?)
+    }
+
+    pub async fn earliest_entry_date(pool: &DbPool, budget_id: &str) -> Result<Option<chrono::NaiveDate>, AppError> {
+        Ok(sqlx::query_scalar::<_, Option<chrono::NaiveDate>>(
+            "SELECT MIN(entry_date) FROM entries WHERE budget_id = ? AND deleted_at IS NULL"
+        ).bind(budget_id).fetch_one(pool).await?)
+    }
+
+    /// Same monthly aggregation as `monthly_summary`, broken down per category.
+    pub async fn monthly_category_summary(pool: &DbPool, budget_id: &str, from: chrono::NaiveDate, to: chrono::NaiveDate)
+                                          -> Result<Vec<(chrono::NaiveDate, String, i64, i64)>, AppError>
+    {
+        Ok(sqlx::query_as::<_, (chrono::NaiveDate, String, i64, i64)>(&format!(r#"
+            SELECT DATE_SUB(entry_date, INTERVAL DAY(entry_date)-1 DAY) as month_start,
+                   category_id,
+                   CAST(SUM(CASE WHEN kind='income'  THEN amount_minor ELSE 0 END) AS SIGNED) AS income_minor,
+                   CAST(SUM(CASE WHEN kind='expense' THEN amount_minor ELSE 0 END) AS SIGNED) AS expense_minor
+            FROM ({}) lines
+            WHERE budget_id = ?
+              AND entry_date BETWEEN ? AND ?
+            GROUP BY month_start, category_id
+            ORDER BY month_start
+        "#, ENTRY_LINES)).bind(budget_id).bind(from).bind(to).fetch_all(pool).await?)
     }
 }
@@ -0,0 +1,70 @@
+use sqlx::MySqlConnection;
+use crate::manager::models::entry::{EntrySplit, EntrySplitReq};
+use crate::utils::{database::database::DbPool, error::error::AppError, currency};
+
+pub struct EntrySplitRepo;
+
+impl EntrySplitRepo {
+    pub async fn list_for_entries(pool: &DbPool, entry_ids: &[String]) -> Result<Vec<EntrySplit>, AppError> {
+        if entry_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; entry_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT id, entry_id, category_id, amount_minor, base_amount_minor, note, position FROM entry_splits \
+             WHERE entry_id IN ({}) ORDER BY entry_id, position ASC",
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, EntrySplit>(&sql);
+        for id in entry_ids {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(pool).await?)
+    }
+
+    /// Replace all line items of an entry; an empty slice turns it back into a plain entry.
+    /// `base_amount_minor` of the entry is shared across the lines by amount.
+    pub async fn replace(conn: &mut MySqlConnection, budget_id: &str, entry_id: &str, splits: &[EntrySplitReq], base_amount_minor: i64) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM entry_splits WHERE entry_id = ?")
+            .bind(entry_id)
+            .execute(&mut *conn)
+            .await?;
+
+        let amounts: Vec<i64> = splits.iter().map(|s| s.amount_minor).collect();
+        let base_amounts = currency::allocate(base_amount_minor, &amounts);
+        for (position, (split, base)) in splits.iter().zip(base_amounts).enumerate() {
+            sqlx::query(
+                "INSERT INTO entry_splits (id, entry_id, budget_id, category_id, amount_minor, base_amount_minor, note, position) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(entry_id)
+            .bind(budget_id)
+            .bind(&split.category_id)
+            .bind(split.amount_minor)
+            .bind(base)
+            .bind(&split.note)
+            .bind(position as i32)
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Share a new `base_amount_minor` of the entry across its existing lines.
+    pub async fn rebase(conn: &mut MySqlConnection, entry_id: &str, base_amount_minor: i64) -> Result<(), AppError> {
+        let lines = sqlx::query_as::<_, (String, i64)>("SELECT id, amount_minor FROM entry_splits WHERE entry_id = ? ORDER BY position ASC")
+            .bind(entry_id)
+            .fetch_all(&mut *conn)
+            .await?;
+        let amounts: Vec<i64> = lines.iter().map(|(_, amount)| *amount).collect();
+        for ((id, _), base) in lines.iter().zip(currency::allocate(base_amount_minor, &amounts)) {
+            sqlx::query("UPDATE entry_splits SET base_amount_minor = ? WHERE id = ?")
+                .bind(base)
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(())
+    }
+}
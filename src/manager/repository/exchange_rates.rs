@@ -0,0 +1,113 @@
+use crate::manager::models::exchange_rate::{ExchangeRate, ExchangeRateFilter, RateRow};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct ExchangeRateRepo;
+
+impl ExchangeRateRepo {
+    pub async fn list(pool: &DbPool, budget_id: &str, filter: &ExchangeRateFilter) -> Result<Vec<ExchangeRate>, AppError> {
+        let mut q = String::from(
+            "SELECT id, budget_id, rate_date, base_currency, quote_currency, rate, source, created_at \
+             FROM exchange_rates WHERE budget_id = ?"
+        );
+        if filter.base.is_some() { q.push_str(" AND base_currency = ?"); }
+        if filter.quote.is_some() { q.push_str(" AND quote_currency = ?"); }
+        if filter.from.is_some() { q.push_str(" AND rate_date >= ?"); }
+        if filter.to.is_some() { q.push_str(" AND rate_date <= ?"); }
+        q.push_str(" ORDER BY rate_date DESC, base_currency ASC, quote_currency ASC LIMIT 1000");
+
+        let mut query = sqlx::query_as::<_, ExchangeRate>(&q).bind(budget_id);
+        if let Some(b) = &filter.base { query = query.bind(b.to_uppercase()); }
+        if let Some(c) = &filter.quote { query = query.bind(c.to_uppercase()); }
+        if let Some(f) = filter.from { query = query.bind(f); }
+        if let Some(t) = filter.to { query = query.bind(t); }
+        Ok(query.fetch_all(pool).await?)
+    }
+
+    /// Insert the rates, replacing stored ones for the same pair and date.
+    pub async fn upsert(pool: &DbPool, budget_id: &str, rows: &[RateRow], source: &str) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        for row in rows {
+            sqlx::query(
+                "INSERT INTO exchange_rates (id, budget_id, rate_date, base_currency, quote_currency, rate, source) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?) \
+                 ON DUPLICATE KEY UPDATE rate = VALUES(rate), source = VALUES(source)"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(budget_id)
+            .bind(row.rate_date)
+            .bind(&row.base_currency)
+            .bind(&row.quote_currency)
+            .bind(row.rate)
+            .bind(source)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn find(pool: &DbPool, budget_id: &str, rate_date: chrono::NaiveDate, base: &str, quote: &str) -> Result<ExchangeRate, AppError> {
+        sqlx::query_as::<_, ExchangeRate>(
+            "SELECT id, budget_id, rate_date, base_currency, quote_currency, rate, source, created_at \
+             FROM exchange_rates WHERE budget_id = ? AND rate_date = ? AND base_currency = ? AND quote_currency = ?"
+        )
+        .bind(budget_id)
+        .bind(rate_date)
+        .bind(base)
+        .bind(quote)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::NotFound)
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, rate_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM exchange_rates WHERE id = ? AND budget_id = ?")
+            .bind(rate_id)
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Units of `to` per unit of `from`, from the latest rate dated on or before
+    /// `on`. Tries the direct pair, then the inverse pair, then a cross rate
+    /// through any base currency quoted against both on the same day (the ECB
+    /// feed only has EUR-based rates).
+    pub async fn rate_on(pool: &DbPool, budget_id: &str, from: &str, to: &str, on: chrono::NaiveDate) -> Result<Option<f64>, AppError> {
+        let direct = sqlx::query_as::<_, (chrono::NaiveDate, f64)>(
+            "SELECT rate_date, rate FROM exchange_rates \
+             WHERE budget_id = ? AND base_currency = ? AND quote_currency = ? AND rate_date <= ? \
+             ORDER BY rate_date DESC LIMIT 1"
+        )
+        .bind(budget_id).bind(from).bind(to).bind(on)
+        .fetch_optional(pool).await?;
+        let inverse = sqlx::query_as::<_, (chrono::NaiveDate, f64)>(
+            "SELECT rate_date, 1 / rate FROM exchange_rates \
+             WHERE budget_id = ? AND base_currency = ? AND quote_currency = ? AND rate_date <= ? \
+             ORDER BY rate_date DESC LIMIT 1"
+        )
+        .bind(budget_id).bind(to).bind(from).bind(on)
+        .fetch_optional(pool).await?;
+        let cross = sqlx::query_as::<_, (chrono::NaiveDate, f64)>(
+            "SELECT a.rate_date, b.rate / a.rate FROM exchange_rates a \
+             INNER JOIN exchange_rates b ON b.budget_id = a.budget_id AND b.rate_date = a.rate_date \
+                 AND b.base_currency = a.base_currency AND b.quote_currency = ? \
+             WHERE a.budget_id = ? AND a.quote_currency = ? AND a.rate_date <= ? \
+             ORDER BY a.rate_date DESC LIMIT 1"
+        )
+        .bind(to).bind(budget_id).bind(from).bind(on)
+        .fetch_optional(pool).await?;
+
+        // The most recent of the three wins; ties prefer the direct quote
+        Ok([direct, inverse, cross].into_iter()
+            .flatten()
+            .fold(None, |best: Option<(chrono::NaiveDate, f64)>, candidate| match best {
+                Some(b) if b.0 >= candidate.0 => Some(b),
+                _ => Some(candidate),
+            })
+            .map(|(_, rate)| rate))
+    }
+}
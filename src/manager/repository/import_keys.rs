@@ -0,0 +1,41 @@
+use std::collections::HashSet;
+use sqlx::MySqlConnection;
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+/// Statement transaction ids (FITID etc.) already imported into a budget.
+/// Keys outlive their entries so that deleting an imported entry does not
+/// make it reappear on the next overlapping import.
+pub struct ImportKeyRepo;
+
+impl ImportKeyRepo {
+    pub async fn existing(pool: &DbPool, budget_id: &str, keys: &[String]) -> Result<HashSet<String>, AppError> {
+        let mut found = HashSet::new();
+        for chunk in keys.chunks(500) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let sql = format!(
+                "SELECT dedupe_key FROM import_dedupe_keys WHERE budget_id = ? AND dedupe_key IN ({})",
+                placeholders
+            );
+            let mut query = sqlx::query_scalar::<_, String>(&sql).bind(budget_id);
+            for key in chunk {
+                query = query.bind(key);
+            }
+            found.extend(query.fetch_all(pool).await?);
+        }
+        Ok(found)
+    }
+
+    /// Record a key for a new entry. Returns false when another import
+    /// claimed the same key first, in which case the entry must not be inserted.
+    pub async fn claim(conn: &mut MySqlConnection, budget_id: &str, key: &str, entry_id: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "INSERT IGNORE INTO import_dedupe_keys (budget_id, dedupe_key, entry_id) VALUES (?, ?, ?)"
+        )
+        .bind(budget_id)
+        .bind(key)
+        .bind(entry_id)
+        .execute(conn)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
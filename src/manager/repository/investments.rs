@@ -0,0 +1,199 @@
+use crate::manager::models::investment::{CostMethod, Holding, InvestmentTransaction};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct InvestmentRepo;
+
+/// The entry recording a transaction's cash in the budget.
+pub struct CashEntry<'a> {
+    pub category_id: &'a str,
+    pub kind: &'a str,
+    pub currency_code: &'a str,
+    pub description: &'a str,
+}
+
+impl InvestmentRepo {
+    pub async fn list_holdings(pool: &DbPool, budget_id: &str) -> Result<Vec<Holding>, AppError> {
+        Ok(sqlx::query_as::<_, Holding>("SELECT * FROM holdings WHERE budget_id = ? ORDER BY symbol ASC")
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?)
+    }
+
+    pub async fn get_holding(pool: &DbPool, budget_id: &str, holding_id: &str) -> Result<Holding, AppError> {
+        sqlx::query_as::<_, Holding>("SELECT * FROM holdings WHERE id = ? AND budget_id = ?")
+            .bind(holding_id)
+            .bind(budget_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    pub async fn find_holding_by_symbol(pool: &DbPool, budget_id: &str, symbol: &str) -> Result<Option<Holding>, AppError> {
+        Ok(sqlx::query_as::<_, Holding>("SELECT * FROM holdings WHERE budget_id = ? AND symbol = ?")
+            .bind(budget_id)
+            .bind(symbol)
+            .fetch_optional(pool)
+            .await?)
+    }
+
+    pub async fn create_holding(pool: &DbPool, budget_id: &str, symbol: &str, name: Option<&str>, cost_method: CostMethod) -> Result<Holding, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query("INSERT INTO holdings (id, budget_id, symbol, name, cost_method) VALUES (?, ?, ?, ?, ?)")
+            .bind(&id)
+            .bind(budget_id)
+            .bind(symbol)
+            .bind(name)
+            .bind(cost_method.to_string())
+            .execute(pool)
+            .await?;
+        Self::get_holding(pool, budget_id, &id).await
+    }
+
+    pub async fn update_holding(pool: &DbPool, holding: &Holding) -> Result<Holding, AppError> {
+        sqlx::query("UPDATE holdings SET symbol = ?, name = ?, cost_method = ?, updated_at = ? WHERE id = ? AND budget_id = ?")
+            .bind(&holding.symbol)
+            .bind(&holding.name)
+            .bind(holding.cost_method.to_string())
+            .bind(chrono::Utc::now().naive_utc())
+            .bind(&holding.id)
+            .bind(&holding.budget_id)
+            .execute(pool)
+            .await?;
+        Self::get_holding(pool, &holding.budget_id, &holding.id).await
+    }
+
+    /// Delete the holding with its transactions; their cash entries are soft-deleted.
+    pub async fn delete_holding(pool: &DbPool, budget_id: &str, holding_id: &str, user_id: &str) -> Result<(), AppError> {
+        let now = chrono::Utc::now().naive_utc();
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "UPDATE entries SET deleted_at = ?, updated_by = ?, updated_at = ? \
+             WHERE id IN (SELECT entry_id FROM investment_transactions WHERE holding_id = ?) AND deleted_at IS NULL"
+        )
+            .bind(now)
+            .bind(user_id)
+            .bind(now)
+            .bind(holding_id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM holdings WHERE id = ? AND budget_id = ?")
+            .bind(holding_id)
+            .bind(budget_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Transactions in the order they are replayed for cost basis.
+    pub async fn list_transactions(pool: &DbPool, budget_id: &str, holding_id: &str) -> Result<Vec<InvestmentTransaction>, AppError> {
+        Ok(sqlx::query_as::<_, InvestmentTransaction>(
+            "SELECT * FROM investment_transactions WHERE budget_id = ? AND holding_id = ? \
+             ORDER BY trade_date ASC, created_at ASC, id ASC"
+        )
+            .bind(budget_id)
+            .bind(holding_id)
+            .fetch_all(pool)
+            .await?)
+    }
+
+    /// Every transaction of the budget up to `as_of`, in replay order.
+    pub async fn list_budget_transactions(pool: &DbPool, budget_id: &str, as_of: chrono::NaiveDate) -> Result<Vec<InvestmentTransaction>, AppError> {
+        Ok(sqlx::query_as::<_, InvestmentTransaction>(
+            "SELECT * FROM investment_transactions WHERE budget_id = ? AND trade_date <= ? \
+             ORDER BY trade_date ASC, created_at ASC, id ASC"
+        )
+            .bind(budget_id)
+            .bind(as_of)
+            .fetch_all(pool)
+            .await?)
+    }
+
+    pub async fn get_transaction(pool: &DbPool, budget_id: &str, transaction_id: &str) -> Result<InvestmentTransaction, AppError> {
+        sqlx::query_as::<_, InvestmentTransaction>("SELECT * FROM investment_transactions WHERE id = ? AND budget_id = ?")
+            .bind(transaction_id)
+            .bind(budget_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Id of the transaction whose cash side is the entry, if any.
+    pub async fn transaction_id_for_entry(pool: &DbPool, entry_id: &str) -> Result<Option<String>, AppError> {
+        Ok(sqlx::query_scalar::<_, String>("SELECT id FROM investment_transactions WHERE entry_id = ?")
+            .bind(entry_id)
+            .fetch_optional(pool)
+            .await?)
+    }
+
+    /// Store the transaction together with its cash entry; `txn.entry_id` names the entry.
+    pub async fn create_transaction(pool: &DbPool, txn: &InvestmentTransaction, cash: CashEntry<'_>) -> Result<InvestmentTransaction, AppError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO entries (id, budget_id, category_id, kind, amount_minor, currency_code, base_amount_minor, entry_date, description, created_by) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+            .bind(&txn.entry_id)
+            .bind(&txn.budget_id)
+            .bind(cash.category_id)
+            .bind(cash.kind)
+            .bind(txn.amount_minor)
+            .bind(cash.currency_code)
+            .bind(txn.amount_minor)
+            .bind(txn.trade_date)
+            .bind(cash.description)
+            .bind(&txn.created_by)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO investment_transactions \
+             (id, budget_id, holding_id, kind, trade_date, quantity, price_minor, fee_minor, amount_minor, entry_id, note, created_by, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+            .bind(&txn.id)
+            .bind(&txn.budget_id)
+            .bind(&txn.holding_id)
+            .bind(txn.kind.to_string())
+            .bind(txn.trade_date)
+            .bind(txn.quantity)
+            .bind(txn.price_minor)
+            .bind(txn.fee_minor)
+            .bind(txn.amount_minor)
+            .bind(&txn.entry_id)
+            .bind(&txn.note)
+            .bind(&txn.created_by)
+            .bind(txn.created_at)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Self::get_transaction(pool, &txn.budget_id, &txn.id).await
+    }
+
+    /// Delete the transaction and soft-delete its cash entry.
+    pub async fn delete_transaction(pool: &DbPool, txn: &InvestmentTransaction, user_id: &str) -> Result<(), AppError> {
+        let now = chrono::Utc::now().naive_utc();
+        let mut tx = pool.begin().await?;
+        if let Some(entry_id) = &txn.entry_id {
+            sqlx::query("UPDATE entries SET deleted_at = ?, updated_by = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL")
+                .bind(now)
+                .bind(user_id)
+                .bind(now)
+                .bind(entry_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        let result = sqlx::query("DELETE FROM investment_transactions WHERE id = ? AND budget_id = ?")
+            .bind(&txn.id)
+            .bind(&txn.budget_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -1,9 +1,25 @@
 pub mod users;
 pub mod budgets;
 pub mod categories;
+pub mod debts;
 pub mod entries;
 pub mod members;
 pub mod comments;
 pub mod attachments;
 pub mod notifications;
-pub mod transfers;
\ No newline at end of file
+pub mod transfers;
+pub mod category_limits;
+pub mod category_snapshots;
+pub mod recurring;
+pub mod import_keys;
+pub mod backups;
+pub mod entry_splits;
+pub mod tags;
+pub mod category_templates;
+pub mod rules;
+pub mod savings_goals;
+pub mod payees;
+pub mod prices;
+pub mod exchange_rates;
+pub mod investments;
+pub mod scheduled_transfers;
\ No newline at end of file
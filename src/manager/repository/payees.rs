@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use sqlx::MySqlConnection;
+use crate::manager::models::payee::{MergePayeeReport, Payee, PayeeAlias, PayeeSuggestion, PayeeSummaryRow};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct PayeeRepo;
+
+impl PayeeRepo {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<Payee>, AppError> {
+        let mut payees = sqlx::query_as::<_, Payee>(
+            "SELECT id, budget_id, name, default_category_id, created_at, updated_at FROM payees WHERE budget_id = ? ORDER BY name ASC"
+        )
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?;
+
+        let mut aliases: HashMap<String, Vec<PayeeAlias>> = HashMap::new();
+        for alias in sqlx::query_as::<_, PayeeAlias>("SELECT id, payee_id, alias FROM payee_aliases WHERE budget_id = ? ORDER BY alias ASC")
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await? {
+            aliases.entry(alias.payee_id.clone()).or_default().push(alias);
+        }
+        for payee in payees.iter_mut() {
+            payee.aliases = aliases.remove(&payee.id).unwrap_or_default();
+        }
+        Ok(payees)
+    }
+
+    pub async fn get_by_id(pool: &DbPool, budget_id: &str, payee_id: &str) -> Result<Payee, AppError> {
+        let mut payee = sqlx::query_as::<_, Payee>(
+            "SELECT id, budget_id, name, default_category_id, created_at, updated_at FROM payees WHERE id = ? AND budget_id = ?"
+        )
+            .bind(payee_id)
+            .bind(budget_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        payee.aliases = sqlx::query_as::<_, PayeeAlias>("SELECT id, payee_id, alias FROM payee_aliases WHERE payee_id = ? ORDER BY alias ASC")
+            .bind(payee_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(payee)
+    }
+
+    /// Payee owning the alias with this normalized form, if any.
+    pub async fn find_by_alias(pool: &DbPool, budget_id: &str, normalized: &str) -> Result<Option<Payee>, AppError> {
+        Ok(sqlx::query_as::<_, Payee>(
+            "SELECT p.id, p.budget_id, p.name, p.default_category_id, p.created_at, p.updated_at \
+             FROM payee_aliases a INNER JOIN payees p ON p.id = a.payee_id \
+             WHERE a.budget_id = ? AND a.normalized = ?"
+        )
+            .bind(budget_id)
+            .bind(normalized)
+            .fetch_optional(pool)
+            .await?)
+    }
+
+    /// Create a payee with its name and `aliases`, given as `(alias, normalized)` pairs
+    /// that must not be taken yet.
+    pub async fn create(pool: &DbPool, budget_id: &str, name: &str, default_category_id: Option<&str>, aliases: &[(String, String)]) -> Result<Payee, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let mut tx = pool.begin().await?;
+        sqlx::query("INSERT INTO payees (id, budget_id, name, default_category_id) VALUES (?, ?, ?, ?)")
+            .bind(&id)
+            .bind(budget_id)
+            .bind(name)
+            .bind(default_category_id)
+            .execute(&mut *tx)
+            .await?;
+        for (alias, normalized) in aliases {
+            Self::insert_alias(&mut tx, budget_id, &id, alias, normalized).await?;
+        }
+        tx.commit().await?;
+        Self::get_by_id(pool, budget_id, &id).await
+    }
+
+    /// Save name and default category; `new_alias` is added for a new name.
+    pub async fn update(pool: &DbPool, payee: &Payee, new_alias: Option<(String, String)>) -> Result<Payee, AppError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("UPDATE payees SET name = ?, default_category_id = ?, updated_at = ? WHERE id = ? AND budget_id = ?")
+            .bind(&payee.name)
+            .bind(&payee.default_category_id)
+            .bind(chrono::Utc::now().naive_utc())
+            .bind(&payee.id)
+            .bind(&payee.budget_id)
+            .execute(&mut *tx)
+            .await?;
+        if let Some((alias, normalized)) = new_alias {
+            Self::insert_alias(&mut tx, &payee.budget_id, &payee.id, &alias, &normalized).await?;
+        }
+        // Linked entries show the payee's name
+        sqlx::query("UPDATE entries SET counterparty = ? WHERE payee_id = ? AND budget_id = ?")
+            .bind(&payee.name)
+            .bind(&payee.id)
+            .bind(&payee.budget_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Self::get_by_id(pool, &payee.budget_id, &payee.id).await
+    }
+
+    /// Entries keep their counterparty text; the link is cleared through the FK.
+    pub async fn delete(pool: &DbPool, budget_id: &str, payee_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM payees WHERE id = ? AND budget_id = ?")
+            .bind(payee_id)
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn add_alias(pool: &DbPool, budget_id: &str, payee_id: &str, alias: &str, normalized: &str) -> Result<(), AppError> {
+        let mut conn = pool.acquire().await?;
+        Self::insert_alias(&mut conn, budget_id, payee_id, alias, normalized).await
+    }
+
+    pub async fn delete_alias(pool: &DbPool, budget_id: &str, payee_id: &str, alias_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM payee_aliases WHERE id = ? AND payee_id = ? AND budget_id = ?")
+            .bind(alias_id)
+            .bind(payee_id)
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn insert_alias(conn: &mut MySqlConnection, budget_id: &str, payee_id: &str, alias: &str, normalized: &str) -> Result<(), AppError> {
+        sqlx::query("INSERT INTO payee_aliases (id, budget_id, payee_id, alias, normalized) VALUES (?, ?, ?, ?, ?)")
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(budget_id)
+            .bind(payee_id)
+            .bind(alias)
+            .bind(normalized)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Move entries (soft-deleted too) and aliases of `source` to `target`, then delete `source`.
+    pub async fn merge(pool: &DbPool, budget_id: &str, source_id: &str, target: &Payee) -> Result<MergePayeeReport, AppError> {
+        let mut tx = pool.begin().await?;
+        let mut report = MergePayeeReport { target_payee_id: target.id.clone(), ..Default::default() };
+
+        report.entries_moved = sqlx::query("UPDATE entries SET payee_id = ?, counterparty = ? WHERE payee_id = ? AND budget_id = ?")
+            .bind(&target.id).bind(&target.name).bind(source_id).bind(budget_id).execute(&mut *tx).await?.rows_affected();
+        report.aliases_moved = sqlx::query("UPDATE payee_aliases SET payee_id = ? WHERE payee_id = ? AND budget_id = ?")
+            .bind(&target.id).bind(source_id).bind(budget_id).execute(&mut *tx).await?.rows_affected();
+        sqlx::query("DELETE FROM payees WHERE id = ? AND budget_id = ?")
+            .bind(source_id).bind(budget_id).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(report)
+    }
+
+    /// Payees with a name or alias containing `normalized`; prefix matches come first.
+    pub async fn autocomplete(pool: &DbPool, budget_id: &str, normalized: &str, limit: u32) -> Result<Vec<PayeeSuggestion>, AppError> {
+        Ok(sqlx::query_as::<_, PayeeSuggestion>(&format!(r#"
+            SELECT p.id, p.name, p.default_category_id,
+                   (SELECT COUNT(*) FROM entries e WHERE e.payee_id = p.id AND e.deleted_at IS NULL) AS entry_count
+            FROM payees p
+            INNER JOIN payee_aliases a ON a.payee_id = p.id
+            WHERE p.budget_id = ? AND a.normalized LIKE ?
+            GROUP BY p.id, p.name, p.default_category_id
+            ORDER BY MAX(a.normalized LIKE ?) DESC, entry_count DESC, p.name ASC
+            LIMIT {}
+        "#, limit))
+        .bind(budget_id)
+        .bind(format!("%{}%", normalized))
+        .bind(format!("{}%", normalized))
+        .fetch_all(pool)
+        .await?)
+    }
+
+    /// Per-payee totals of non-deleted entries dated within `from..=to`; payees
+    /// without entries in the range are left out.
+    pub async fn summary(pool: &DbPool, budget_id: &str, from: chrono::NaiveDate, to: chrono::NaiveDate) -> Result<Vec<PayeeSummaryRow>, AppError> {
+        Ok(sqlx::query_as::<_, PayeeSummaryRow>(r#"
+            SELECT p.id AS payee_id, p.name,
+                   COUNT(e.id) AS entry_count,
+                   CAST(COALESCE(SUM(CASE WHEN e.kind='income'  THEN e.base_amount_minor ELSE 0 END), 0) AS SIGNED) AS income_minor,
+                   CAST(COALESCE(SUM(CASE WHEN e.kind='expense' THEN e.base_amount_minor ELSE 0 END), 0) AS SIGNED) AS expense_minor,
+                   MAX(e.entry_date) AS last_seen
+            FROM payees p
+            INNER JOIN entries e ON e.payee_id = p.id
+                AND e.deleted_at IS NULL
+                AND e.entry_date BETWEEN ? AND ?
+            WHERE p.budget_id = ?
+            GROUP BY p.id, p.name
+            ORDER BY expense_minor DESC, p.name ASC
+        "#)
+        .bind(from)
+        .bind(to)
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+}
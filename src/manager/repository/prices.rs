@@ -0,0 +1,87 @@
+use crate::manager::models::investment::{PriceFilter, PriceRow, PriceSnapshot};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct PriceRepo;
+
+impl PriceRepo {
+    pub async fn list(pool: &DbPool, budget_id: &str, filter: &PriceFilter) -> Result<Vec<PriceSnapshot>, AppError> {
+        let mut q = String::from(
+            "SELECT id, budget_id, symbol, price_date, price_minor, source, created_at \
+             FROM price_snapshots WHERE budget_id = ?"
+        );
+        if filter.symbol.is_some() { q.push_str(" AND symbol = ?"); }
+        if filter.from.is_some() { q.push_str(" AND price_date >= ?"); }
+        if filter.to.is_some() { q.push_str(" AND price_date <= ?"); }
+        q.push_str(" ORDER BY price_date DESC, symbol ASC LIMIT 1000");
+
+        let mut query = sqlx::query_as::<_, PriceSnapshot>(&q).bind(budget_id);
+        if let Some(s) = &filter.symbol { query = query.bind(s.trim().to_uppercase()); }
+        if let Some(f) = filter.from { query = query.bind(f); }
+        if let Some(t) = filter.to { query = query.bind(t); }
+        Ok(query.fetch_all(pool).await?)
+    }
+
+    /// Insert the prices, replacing stored ones for the same symbol and date.
+    pub async fn upsert(pool: &DbPool, budget_id: &str, rows: &[PriceRow], source: &str) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        for row in rows {
+            sqlx::query(
+                "INSERT INTO price_snapshots (id, budget_id, symbol, price_date, price_minor, source) \
+                 VALUES (?, ?, ?, ?, ?, ?) \
+                 ON DUPLICATE KEY UPDATE price_minor = VALUES(price_minor), source = VALUES(source)"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(budget_id)
+            .bind(&row.symbol)
+            .bind(row.price_date)
+            .bind(row.price_minor)
+            .bind(source)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn find(pool: &DbPool, budget_id: &str, symbol: &str, price_date: chrono::NaiveDate) -> Result<PriceSnapshot, AppError> {
+        sqlx::query_as::<_, PriceSnapshot>(
+            "SELECT id, budget_id, symbol, price_date, price_minor, source, created_at \
+             FROM price_snapshots WHERE budget_id = ? AND symbol = ? AND price_date = ?"
+        )
+        .bind(budget_id)
+        .bind(symbol)
+        .bind(price_date)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::NotFound)
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, price_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM price_snapshots WHERE id = ? AND budget_id = ?")
+            .bind(price_id)
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// The latest price of every symbol dated on or before `as_of`.
+    pub async fn latest(pool: &DbPool, budget_id: &str, as_of: chrono::NaiveDate) -> Result<Vec<PriceSnapshot>, AppError> {
+        Ok(sqlx::query_as::<_, PriceSnapshot>(
+            "SELECT p.id, p.budget_id, p.symbol, p.price_date, p.price_minor, p.source, p.created_at \
+             FROM price_snapshots p \
+             INNER JOIN (SELECT symbol, MAX(price_date) AS price_date FROM price_snapshots \
+                         WHERE budget_id = ? AND price_date <= ? GROUP BY symbol) latest \
+               ON latest.symbol = p.symbol AND latest.price_date = p.price_date \
+             WHERE p.budget_id = ?"
+        )
+        .bind(budget_id)
+        .bind(as_of)
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+}
@@ -0,0 +1,157 @@
+use crate::manager::models::payee::Payee;
+use crate::manager::models::exchange_rate::Conversion;
+use crate::manager::models::recurring::{CreateRecurringEntryReq, RecurringEntry};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct RecurringRepo;
+
+impl RecurringRepo {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<RecurringEntry>, AppError> {
+        Ok(sqlx::query_as::<_, RecurringEntry>(
+            "SELECT * FROM recurring_entries WHERE budget_id = ? ORDER BY start_date ASC, created_at ASC"
+        )
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn get_by_id(pool: &DbPool, budget_id: &str, recurring_id: &str) -> Result<RecurringEntry, AppError> {
+        sqlx::query_as::<_, RecurringEntry>("SELECT * FROM recurring_entries WHERE id = ? AND budget_id = ?")
+            .bind(recurring_id)
+            .bind(budget_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Active templates that may have occurrences on or before `today` not yet generated.
+    pub async fn list_due(pool: &DbPool, today: chrono::NaiveDate) -> Result<Vec<RecurringEntry>, AppError> {
+        Ok(sqlx::query_as::<_, RecurringEntry>(
+            "SELECT * FROM recurring_entries \
+             WHERE active = TRUE AND start_date <= ? \
+               AND (generated_through IS NULL OR generated_through < ?)"
+        )
+        .bind(today)
+        .bind(today)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, req: &CreateRecurringEntryReq, currency: &str) -> Result<RecurringEntry, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(r#"
+            INSERT INTO recurring_entries
+            (id, budget_id, category_id, kind, amount_minor, currency_code, description, counterparty,
+             frequency, interval_count, by_month_day, start_date, end_date, occurrence_count, created_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(&id)
+        .bind(budget_id)
+        .bind(&req.category_id)
+        .bind(&req.kind)
+        .bind(req.amount_minor)
+        .bind(currency)
+        .bind(&req.description)
+        .bind(&req.counterparty)
+        .bind(req.frequency.to_string())
+        .bind(req.interval_count.unwrap_or(1))
+        .bind(req.by_month_day)
+        .bind(req.start_date)
+        .bind(req.end_date)
+        .bind(req.occurrence_count)
+        .bind(&req.created_by)
+        .execute(pool)
+        .await?;
+        Self::get_by_id(pool, budget_id, &id).await
+    }
+
+    pub async fn update(pool: &DbPool, recurring: &RecurringEntry) -> Result<RecurringEntry, AppError> {
+        sqlx::query(r#"
+            UPDATE recurring_entries
+            SET category_id = ?, kind = ?, amount_minor = ?, description = ?, counterparty = ?,
+                frequency = ?, interval_count = ?, by_month_day = ?, end_date = ?, occurrence_count = ?, active = ?
+            WHERE id = ? AND budget_id = ?
+        "#)
+        .bind(&recurring.category_id)
+        .bind(&recurring.kind)
+        .bind(recurring.amount_minor)
+        .bind(&recurring.description)
+        .bind(&recurring.counterparty)
+        .bind(recurring.frequency.to_string())
+        .bind(recurring.interval_count)
+        .bind(recurring.by_month_day)
+        .bind(recurring.end_date)
+        .bind(recurring.occurrence_count)
+        .bind(recurring.active)
+        .bind(&recurring.id)
+        .bind(&recurring.budget_id)
+        .execute(pool)
+        .await?;
+        Self::get_by_id(pool, &recurring.budget_id, &recurring.id).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, recurring_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM recurring_entries WHERE id = ? AND budget_id = ?")
+            .bind(recurring_id)
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Insert one entry per occurrence date and advance `generated_through`.
+    /// The (recurring_id, recurring_date) unique key makes re-runs no-ops.
+    /// Returns the number of entries actually inserted.
+    pub async fn materialize(
+        pool: &DbPool,
+        recurring: &RecurringEntry,
+        dates: &[(chrono::NaiveDate, Conversion)],
+        generated_through: chrono::NaiveDate,
+        still_active: bool,
+    ) -> Result<u64, AppError> {
+        let mut tx = pool.begin().await?;
+        let mut inserted = 0;
+
+        for (date, conversion) in dates {
+            let result = sqlx::query(r#"
+                INSERT IGNORE INTO entries
+                (id, budget_id, category_id, kind, amount_minor, currency_code, base_amount_minor, exchange_rate,
+                 entry_date, description, counterparty, payee_id, created_by, recurring_id, recurring_date)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                        (SELECT a.payee_id FROM payee_aliases a WHERE a.budget_id = ? AND a.normalized = ?), ?, ?, ?)
+            "#)
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&recurring.budget_id)
+            .bind(&recurring.category_id)
+            .bind(&recurring.kind)
+            .bind(recurring.amount_minor)
+            .bind(&recurring.currency_code)
+            .bind(conversion.base_amount_minor)
+            .bind(conversion.exchange_rate)
+            .bind(date)
+            .bind(&recurring.description)
+            .bind(&recurring.counterparty)
+            .bind(&recurring.budget_id)
+            .bind(recurring.counterparty.as_deref().map(Payee::normalize))
+            .bind(&recurring.created_by)
+            .bind(&recurring.id)
+            .bind(date)
+            .execute(&mut *tx)
+            .await?;
+            inserted += result.rows_affected();
+        }
+
+        sqlx::query("UPDATE recurring_entries SET generated_through = ?, active = ? WHERE id = ?")
+            .bind(generated_through)
+            .bind(still_active)
+            .bind(&recurring.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use sqlx::MySqlConnection;
+use crate::manager::models::payee::Payee;
+use crate::manager::models::rule::{Rule, RuleChange, RuleTag, SaveRuleReq};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+const RULE_COLUMNS: &str = "id, budget_id, name, priority, active, counterparty_pattern, counterparty_match, \
+    description_regex, min_amount_minor, max_amount_minor, kind, set_category_id, set_counterparty, created_at, updated_at";
+
+pub struct RuleRepo;
+
+impl RuleRepo {
+    /// Rules of a budget in the order they run, with their tags.
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<Rule>, AppError> {
+        let mut rules = sqlx::query_as::<_, Rule>(&format!(
+            "SELECT {} FROM entry_rules WHERE budget_id = ? ORDER BY priority ASC, created_at ASC, id ASC",
+            RULE_COLUMNS
+        ))
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?;
+
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        for rt in sqlx::query_as::<_, RuleTag>(
+            "SELECT rt.rule_id, rt.tag_id FROM entry_rule_tags rt \
+             INNER JOIN entry_rules r ON r.id = rt.rule_id WHERE r.budget_id = ?"
+        )
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await? {
+            tags.entry(rt.rule_id).or_default().push(rt.tag_id);
+        }
+        for rule in rules.iter_mut() {
+            rule.set_tag_ids = tags.remove(&rule.id).unwrap_or_default();
+        }
+        Ok(rules)
+    }
+
+    pub async fn get_by_id(pool: &DbPool, budget_id: &str, rule_id: &str) -> Result<Rule, AppError> {
+        let mut rule = sqlx::query_as::<_, Rule>(&format!("SELECT {} FROM entry_rules WHERE id = ? AND budget_id = ?", RULE_COLUMNS))
+            .bind(rule_id)
+            .bind(budget_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        rule.set_tag_ids = sqlx::query_scalar::<_, String>("SELECT tag_id FROM entry_rule_tags WHERE rule_id = ?")
+            .bind(rule_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(rule)
+    }
+
+    /// `req` must be validated; `tag_ids` are the resolved tags.
+    pub async fn create(pool: &DbPool, budget_id: &str, req: &SaveRuleReq, tag_ids: &[String]) -> Result<Rule, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO entry_rules (id, budget_id, name, priority, active, counterparty_pattern, counterparty_match, \
+             description_regex, min_amount_minor, max_amount_minor, kind, set_category_id, set_counterparty) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+            .bind(&id)
+            .bind(budget_id)
+            .bind(&req.name)
+            .bind(req.priority.unwrap_or(100))
+            .bind(req.active.unwrap_or(true))
+            .bind(&req.counterparty_pattern)
+            .bind(req.counterparty_match.as_deref().unwrap_or("contains"))
+            .bind(&req.description_regex)
+            .bind(req.min_amount_minor)
+            .bind(req.max_amount_minor)
+            .bind(&req.kind)
+            .bind(&req.set_category_id)
+            .bind(&req.set_counterparty)
+            .execute(&mut *tx)
+            .await?;
+        Self::replace_tags(&mut tx, &id, tag_ids).await?;
+        tx.commit().await?;
+        Self::get_by_id(pool, budget_id, &id).await
+    }
+
+    /// Replace the whole definition of a rule.
+    pub async fn replace(pool: &DbPool, budget_id: &str, rule_id: &str, req: &SaveRuleReq, tag_ids: &[String]) -> Result<Rule, AppError> {
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE entry_rules SET name = ?, priority = ?, active = ?, counterparty_pattern = ?, counterparty_match = ?, \
+             description_regex = ?, min_amount_minor = ?, max_amount_minor = ?, kind = ?, set_category_id = ?, \
+             set_counterparty = ?, updated_at = ? WHERE id = ? AND budget_id = ?"
+        )
+            .bind(&req.name)
+            .bind(req.priority.unwrap_or(100))
+            .bind(req.active.unwrap_or(true))
+            .bind(&req.counterparty_pattern)
+            .bind(req.counterparty_match.as_deref().unwrap_or("contains"))
+            .bind(&req.description_regex)
+            .bind(req.min_amount_minor)
+            .bind(req.max_amount_minor)
+            .bind(&req.kind)
+            .bind(&req.set_category_id)
+            .bind(&req.set_counterparty)
+            .bind(chrono::Utc::now().naive_utc())
+            .bind(rule_id)
+            .bind(budget_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Self::replace_tags(&mut tx, rule_id, tag_ids).await?;
+        tx.commit().await?;
+        Self::get_by_id(pool, budget_id, rule_id).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, rule_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM entry_rules WHERE id = ? AND budget_id = ?")
+            .bind(rule_id)
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn replace_tags(conn: &mut MySqlConnection, rule_id: &str, tag_ids: &[String]) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM entry_rule_tags WHERE rule_id = ?")
+            .bind(rule_id)
+            .execute(&mut *conn)
+            .await?;
+        for tag_id in tag_ids {
+            sqlx::query("INSERT INTO entry_rule_tags (rule_id, tag_id) VALUES (?, ?)")
+                .bind(rule_id)
+                .bind(tag_id)
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Write the changes of a rule re-application in one transaction.
+    pub async fn apply_changes(pool: &DbPool, budget_id: &str, changes: &[RuleChange], user_id: &str) -> Result<(), AppError> {
+        let now = chrono::Utc::now();
+        let mut tx = pool.begin().await?;
+        for change in changes {
+            if change.category_id.is_some() || change.counterparty.is_some() {
+                // A new counterparty relinks the entry to the payee it is an alias of
+                sqlx::query(
+                    "UPDATE entries SET category_id = COALESCE(?, category_id), counterparty = COALESCE(?, counterparty), \
+                     payee_id = IF(? IS NULL, payee_id, (SELECT a.payee_id FROM payee_aliases a WHERE a.budget_id = ? AND a.normalized = ?)), \
+                     updated_by = ?, updated_at = ? WHERE id = ? AND budget_id = ?"
+                )
+                    .bind(&change.category_id)
+                    .bind(&change.counterparty)
+                    .bind(&change.counterparty)
+                    .bind(budget_id)
+                    .bind(change.counterparty.as_deref().map(Payee::normalize))
+                    .bind(user_id)
+                    .bind(now)
+                    .bind(&change.entry_id)
+                    .bind(budget_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            for tag_id in &change.added_tag_ids {
+                sqlx::query("INSERT IGNORE INTO entry_tags (entry_id, tag_id) VALUES (?, ?)")
+                    .bind(&change.entry_id)
+                    .bind(tag_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
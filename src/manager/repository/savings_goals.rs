@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use sqlx::MySqlConnection;
+use crate::manager::models::savings_goal::{CreateSavingsGoalReq, SavingsGoal, SavingsGoalCategory};
+use crate::manager::repository::entries::ENTRY_LINES;
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct SavingsGoalRepo;
+
+impl SavingsGoalRepo {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<SavingsGoal>, AppError> {
+        let mut goals = sqlx::query_as::<_, SavingsGoal>(
+            "SELECT * FROM savings_goals WHERE budget_id = ? ORDER BY target_date IS NULL, target_date ASC, created_at ASC"
+        )
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?;
+
+        let mut categories: HashMap<String, Vec<String>> = HashMap::new();
+        for gc in sqlx::query_as::<_, SavingsGoalCategory>(
+            "SELECT gc.goal_id, gc.category_id FROM savings_goal_categories gc \
+             INNER JOIN savings_goals g ON g.id = gc.goal_id WHERE g.budget_id = ?"
+        )
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await? {
+            categories.entry(gc.goal_id).or_default().push(gc.category_id);
+        }
+        for goal in goals.iter_mut() {
+            goal.category_ids = categories.remove(&goal.id).unwrap_or_default();
+        }
+        Ok(goals)
+    }
+
+    pub async fn get_by_id(pool: &DbPool, budget_id: &str, goal_id: &str) -> Result<SavingsGoal, AppError> {
+        let mut goal = sqlx::query_as::<_, SavingsGoal>("SELECT * FROM savings_goals WHERE id = ? AND budget_id = ?")
+            .bind(goal_id)
+            .bind(budget_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        goal.category_ids = sqlx::query_scalar::<_, String>("SELECT category_id FROM savings_goal_categories WHERE goal_id = ?")
+            .bind(goal_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(goal)
+    }
+
+    /// `req` must be validated; `category_ids` are the linked categories.
+    pub async fn create(pool: &DbPool, budget_id: &str, req: &CreateSavingsGoalReq, category_ids: &[String], user_id: &str) -> Result<SavingsGoal, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO savings_goals (id, budget_id, name, target_amount_minor, target_date, start_date, note, created_by) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+            .bind(&id)
+            .bind(budget_id)
+            .bind(&req.name)
+            .bind(req.target_amount_minor)
+            .bind(req.target_date)
+            .bind(req.start_date)
+            .bind(&req.note)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        Self::replace_categories(&mut tx, &id, category_ids).await?;
+        tx.commit().await?;
+        Self::get_by_id(pool, budget_id, &id).await
+    }
+
+    pub async fn update(pool: &DbPool, goal: &SavingsGoal) -> Result<SavingsGoal, AppError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "UPDATE savings_goals SET name = ?, target_amount_minor = ?, target_date = ?, start_date = ?, note = ?, updated_at = ? \
+             WHERE id = ? AND budget_id = ?"
+        )
+            .bind(&goal.name)
+            .bind(goal.target_amount_minor)
+            .bind(goal.target_date)
+            .bind(goal.start_date)
+            .bind(&goal.note)
+            .bind(chrono::Utc::now().naive_utc())
+            .bind(&goal.id)
+            .bind(&goal.budget_id)
+            .execute(&mut *tx)
+            .await?;
+        Self::replace_categories(&mut tx, &goal.id, &goal.category_ids).await?;
+        tx.commit().await?;
+        Self::get_by_id(pool, &goal.budget_id, &goal.id).await
+    }
+
+    pub async fn delete(pool: &DbPool, budget_id: &str, goal_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM savings_goals WHERE id = ? AND budget_id = ?")
+            .bind(goal_id)
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn replace_categories(conn: &mut MySqlConnection, goal_id: &str, category_ids: &[String]) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM savings_goal_categories WHERE goal_id = ?")
+            .bind(goal_id)
+            .execute(&mut *conn)
+            .await?;
+        for category_id in category_ids {
+            sqlx::query("INSERT INTO savings_goal_categories (goal_id, category_id) VALUES (?, ?)")
+                .bind(goal_id)
+                .bind(category_id)
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Net (income minus expense) per month up to `to`, split into entries
+    /// recorded in the budget and transfer legs. `category_ids` of `None`
+    /// counts every category.
+    pub async fn monthly_contributions(pool: &DbPool, budget_id: &str, category_ids: Option<&[String]>, from: Option<chrono::NaiveDate>, to: chrono::NaiveDate)
+                                       -> Result<Vec<(chrono::NaiveDate, i64, i64)>, AppError>
+    {
+        let category_filter = match category_ids {
+            Some([]) => return Ok(Vec::new()),
+            Some(ids) => format!("AND category_id IN ({})", vec!["?"; ids.len()].join(", ")),
+            None => String::new(),
+        };
+        let sql = format!(r#"
+            SELECT DATE_SUB(entry_date, INTERVAL DAY(entry_date)-1 DAY) as month_start,
+                   CAST(SUM(CASE WHEN transfer_id IS NOT NULL THEN 0 WHEN kind='income' THEN amount_minor ELSE -amount_minor END) AS SIGNED) AS entries_minor,
+                   CAST(SUM(CASE WHEN transfer_id IS NULL THEN 0 WHEN kind='income' THEN amount_minor ELSE -amount_minor END) AS SIGNED) AS transfers_minor
+            FROM ({}) lines
+            WHERE budget_id = ?
+              AND (? IS NULL OR entry_date >= ?)
+              AND entry_date <= ?
+              {}
+            GROUP BY month_start
+            ORDER BY month_start
+        "#, ENTRY_LINES, category_filter);
+
+        let mut query = sqlx::query_as::<_, (chrono::NaiveDate, i64, i64)>(&sql)
+            .bind(budget_id)
+            .bind(from)
+            .bind(from)
+            .bind(to);
+        for id in category_ids.unwrap_or_default() {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(pool).await?)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::manager::models::scheduled_transfer::{CreateScheduledTransferReq, ScheduledTransfer};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct ScheduledTransferRepo;
+
+impl ScheduledTransferRepo {
+    /// Schedules sending from or receiving into the budget.
+    pub async fn list_by_budget(pool: &DbPool, budget_id: &str) -> Result<Vec<ScheduledTransfer>, AppError> {
+        Ok(sqlx::query_as::<_, ScheduledTransfer>(
+            "SELECT * FROM scheduled_transfers WHERE from_budget_id = ? OR to_budget_id = ? ORDER BY start_date ASC, created_at ASC"
+        )
+        .bind(budget_id)
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn get_by_id(pool: &DbPool, scheduled_id: &str) -> Result<ScheduledTransfer, AppError> {
+        sqlx::query_as::<_, ScheduledTransfer>("SELECT * FROM scheduled_transfers WHERE id = ?")
+            .bind(scheduled_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Active schedules that may have occurrences on or before `today` not yet executed.
+    pub async fn list_due(pool: &DbPool, today: chrono::NaiveDate) -> Result<Vec<ScheduledTransfer>, AppError> {
+        Ok(sqlx::query_as::<_, ScheduledTransfer>(
+            "SELECT * FROM scheduled_transfers \
+             WHERE active = TRUE AND start_date <= ? \
+               AND (generated_through IS NULL OR generated_through < ?)"
+        )
+        .bind(today)
+        .bind(today)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn create(pool: &DbPool, req: &CreateScheduledTransferReq, user_id: &str) -> Result<ScheduledTransfer, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(r#"
+            INSERT INTO scheduled_transfers
+            (id, from_budget_id, to_budget_id, from_category_id, to_category_id, amount_minor, exchange_rate, fee_minor,
+             fee_category_id, note, frequency, interval_count, by_month_day, start_date, end_date, occurrence_count, created_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(&id)
+        .bind(&req.from_budget_id)
+        .bind(&req.to_budget_id)
+        .bind(&req.from_category_id)
+        .bind(&req.to_category_id)
+        .bind(req.amount_minor)
+        .bind(req.exchange_rate)
+        .bind(req.fee_minor.unwrap_or(0))
+        .bind(&req.fee_category_id)
+        .bind(&req.note)
+        .bind(req.frequency.to_string())
+        .bind(req.interval_count.unwrap_or(1))
+        .bind(req.by_month_day)
+        .bind(req.start_date)
+        .bind(req.end_date)
+        .bind(req.occurrence_count)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Self::get_by_id(pool, &id).await
+    }
+
+    pub async fn update(pool: &DbPool, scheduled: &ScheduledTransfer) -> Result<ScheduledTransfer, AppError> {
+        sqlx::query(r#"
+            UPDATE scheduled_transfers
+            SET from_category_id = ?, to_category_id = ?, amount_minor = ?, exchange_rate = ?, fee_minor = ?, fee_category_id = ?,
+                note = ?, frequency = ?, interval_count = ?, by_month_day = ?, end_date = ?, occurrence_count = ?, active = ?
+            WHERE id = ?
+        "#)
+        .bind(&scheduled.from_category_id)
+        .bind(&scheduled.to_category_id)
+        .bind(scheduled.amount_minor)
+        .bind(scheduled.exchange_rate)
+        .bind(scheduled.fee_minor)
+        .bind(&scheduled.fee_category_id)
+        .bind(&scheduled.note)
+        .bind(scheduled.frequency.to_string())
+        .bind(scheduled.interval_count)
+        .bind(scheduled.by_month_day)
+        .bind(scheduled.end_date)
+        .bind(scheduled.occurrence_count)
+        .bind(scheduled.active)
+        .bind(&scheduled.id)
+        .execute(pool)
+        .await?;
+        Self::get_by_id(pool, &scheduled.id).await
+    }
+
+    pub async fn delete(pool: &DbPool, scheduled_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM scheduled_transfers WHERE id = ?")
+            .bind(scheduled_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Record that every occurrence up to `generated_through` has been handled.
+    pub async fn advance(pool: &DbPool, scheduled_id: &str, generated_through: chrono::NaiveDate, still_active: bool) -> Result<(), AppError> {
+        sqlx::query("UPDATE scheduled_transfers SET generated_through = ?, active = ? WHERE id = ?")
+            .bind(generated_through)
+            .bind(still_active)
+            .bind(scheduled_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the occurrence already produced a transfer, deleted or not.
+    pub async fn executed(pool: &DbPool, scheduled_id: &str, date: chrono::NaiveDate) -> Result<bool, AppError> {
+        Ok(sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM budget_transfers WHERE scheduled_transfer_id = ? AND scheduled_date = ?"
+        )
+        .bind(scheduled_id)
+        .bind(date)
+        .fetch_one(pool)
+        .await? > 0)
+    }
+}
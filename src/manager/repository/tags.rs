@@ -0,0 +1,127 @@
+use sqlx::MySqlConnection;
+use crate::manager::models::tag::{CreateTagReq, EntryTag, Tag, TagSummaryRow};
+use crate::utils::{database::database::DbPool, error::error::AppError};
+
+pub struct TagRepo;
+
+impl TagRepo {
+    pub async fn list(pool: &DbPool, budget_id: &str) -> Result<Vec<Tag>, AppError> {
+        Ok(sqlx::query_as::<_, Tag>("SELECT id, budget_id, name, color, created_at FROM tags WHERE budget_id = ? ORDER BY name ASC")
+            .bind(budget_id)
+            .fetch_all(pool)
+            .await?)
+    }
+
+    pub async fn get_by_id(pool: &DbPool, budget_id: &str, tag_id: &str) -> Result<Tag, AppError> {
+        sqlx::query_as::<_, Tag>("SELECT id, budget_id, name, color, created_at FROM tags WHERE id = ? AND budget_id = ?")
+            .bind(tag_id)
+            .bind(budget_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    pub async fn find_by_name(pool: &DbPool, budget_id: &str, name: &str) -> Result<Option<Tag>, AppError> {
+        Ok(sqlx::query_as::<_, Tag>("SELECT id, budget_id, name, color, created_at FROM tags WHERE budget_id = ? AND name = ?")
+            .bind(budget_id)
+            .bind(name)
+            .fetch_optional(pool)
+            .await?)
+    }
+
+    pub async fn create(pool: &DbPool, budget_id: &str, req: &CreateTagReq) -> Result<Tag, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query("INSERT INTO tags (id, budget_id, name, color) VALUES (?, ?, ?, ?)")
+            .bind(&id)
+            .bind(budget_id)
+            .bind(&req.name)
+            .bind(&req.color)
+            .execute(pool)
+            .await?;
+        Self::get_by_id(pool, budget_id, &id).await
+    }
+
+    pub async fn update(pool: &DbPool, tag: &Tag) -> Result<Tag, AppError> {
+        sqlx::query("UPDATE tags SET name = ?, color = ? WHERE id = ? AND budget_id = ?")
+            .bind(&tag.name)
+            .bind(&tag.color)
+            .bind(&tag.id)
+            .bind(&tag.budget_id)
+            .execute(pool)
+            .await?;
+        Self::get_by_id(pool, &tag.budget_id, &tag.id).await
+    }
+
+    /// Deleting a tag removes it from every entry through the entry_tags cascade.
+    pub async fn delete(pool: &DbPool, budget_id: &str, tag_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM tags WHERE id = ? AND budget_id = ?")
+            .bind(tag_id)
+            .bind(budget_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn list_for_entries(pool: &DbPool, entry_ids: &[String]) -> Result<Vec<EntryTag>, AppError> {
+        if entry_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; entry_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT et.entry_id, t.id, t.name, t.color FROM entry_tags et \
+             INNER JOIN tags t ON t.id = et.tag_id \
+             WHERE et.entry_id IN ({}) ORDER BY et.entry_id, t.name ASC",
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, EntryTag>(&sql);
+        for id in entry_ids {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(pool).await?)
+    }
+
+    /// Replace the tags of an entry; an empty slice removes them all.
+    pub async fn replace_for_entry(conn: &mut MySqlConnection, entry_id: &str, tag_ids: &[String]) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM entry_tags WHERE entry_id = ?")
+            .bind(entry_id)
+            .execute(&mut *conn)
+            .await?;
+
+        for tag_id in tag_ids {
+            sqlx::query("INSERT INTO entry_tags (entry_id, tag_id) VALUES (?, ?)")
+                .bind(entry_id)
+                .bind(tag_id)
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Per-tag totals of non-deleted entries dated within `from..=to`. Tags
+    /// without entries in the range are included with zero totals.
+    pub async fn summary(pool: &DbPool, budget_id: &str, from: chrono::NaiveDate, to: chrono::NaiveDate) -> Result<Vec<TagSummaryRow>, AppError> {
+        Ok(sqlx::query_as::<_, TagSummaryRow>(r#"
+            SELECT t.id AS tag_id, t.name AS tag_name, t.color,
+                   COUNT(e.id) AS entry_count,
+                   CAST(COALESCE(SUM(CASE WHEN e.kind='income'  THEN e.base_amount_minor ELSE 0 END), 0) AS SIGNED) AS income_minor,
+                   CAST(COALESCE(SUM(CASE WHEN e.kind='expense' THEN e.base_amount_minor ELSE 0 END), 0) AS SIGNED) AS expense_minor,
+                   CAST(COALESCE(SUM(CASE WHEN e.kind='income'  THEN e.base_amount_minor ELSE -e.base_amount_minor END), 0) AS SIGNED) AS net_minor
+            FROM tags t
+            LEFT JOIN entry_tags et ON et.tag_id = t.id
+            LEFT JOIN entries e ON e.id = et.entry_id
+                AND e.deleted_at IS NULL
+                AND e.entry_date BETWEEN ? AND ?
+            WHERE t.budget_id = ?
+            GROUP BY t.id, t.name, t.color
+            ORDER BY expense_minor DESC, t.name ASC
+        "#)
+        .bind(from)
+        .bind(to)
+        .bind(budget_id)
+        .fetch_all(pool)
+        .await?)
+    }
+}
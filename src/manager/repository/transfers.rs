@@ -1,4 +1,4 @@
-use crate::manager::models::transfer::{BudgetTransfer, CreateTransferReq};
+use crate::manager::models::transfer::{BudgetTransfer, CreateTransferReq, TransferLeg};
 use crate::utils::{database::database::DbPool, error::error::AppError};
 
 pub struct TransferRepo;
@@ -12,8 +12,8 @@ impl TransferRepo {
     ) -> Result<BudgetTransfer, AppError> {
         sqlx::query_as::<_, BudgetTransfer>(
             "INSERT INTO budget_transfers 
-             (id, from_budget_id, to_budget_id, amount_minor, currency_code, transfer_date, note, created_by) 
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             (id, from_budget_id, to_budget_id, amount_minor, currency_code, to_amount_minor, to_currency_code, transfer_date, note, created_by) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
              RETURNING *"
         )
         .bind(transfer_id)
@@ -21,6 +21,8 @@ impl TransferRepo {
         .bind(&req.to_budget_id)
         .bind(req.amount_minor)
         .bind(&req.currency_code)
+        .bind(req.amount_minor)
+        .bind(&req.currency_code)
         .bind(req.transfer_date)
         .bind(&req.note)
         .bind(user_id)
@@ -37,7 +39,7 @@ impl TransferRepo {
         transfer_id: &str,
     ) -> Result<BudgetTransfer, AppError> {
         sqlx::query_as::<_, BudgetTransfer>(
-            "SELECT * FROM budget_transfers WHERE id = ?"
+            "SELECT * FROM budget_transfers WHERE id = ? AND deleted_at IS NULL"
         )
         .bind(transfer_id)
         .fetch_optional(pool)
@@ -51,7 +53,7 @@ impl TransferRepo {
     ) -> Result<Vec<BudgetTransfer>, AppError> {
         sqlx::query_as::<_, BudgetTransfer>(
             "SELECT * FROM budget_transfers 
-             WHERE from_budget_id = ? OR to_budget_id = ?
+             WHERE (from_budget_id = ? OR to_budget_id = ?) AND deleted_at IS NULL
              ORDER BY transfer_date DESC, created_at DESC"
         )
         .bind(budget_id)
@@ -63,4 +65,63 @@ impl TransferRepo {
             AppError::Internal
         })
     }
+
+    /// Transfers touching a budget whose entries have not been deleted.
+    pub async fn list_for_journal(
+        pool: &DbPool,
+        budget_id: &str,
+        from: Option<chrono::NaiveDate>,
+        to: Option<chrono::NaiveDate>,
+    ) -> Result<Vec<BudgetTransfer>, AppError> {
+        Ok(sqlx::query_as::<_, BudgetTransfer>(
+            "SELECT t.* FROM budget_transfers t
+             WHERE (t.from_budget_id = ? OR t.to_budget_id = ?)
+               AND t.deleted_at IS NULL
+               AND (? IS NULL OR t.transfer_date >= ?) AND (? IS NULL OR t.transfer_date <= ?)
+               AND EXISTS (SELECT 1 FROM entries e WHERE e.transfer_id = t.id AND e.deleted_at IS NULL)
+             ORDER BY t.transfer_date ASC, t.created_at ASC"
+        )
+        .bind(budget_id)
+        .bind(budget_id)
+        .bind(from)
+        .bind(from)
+        .bind(to)
+        .bind(to)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn legs(pool: &DbPool, transfer_id: &str) -> Result<Vec<TransferLeg>, AppError> {
+        Ok(sqlx::query_as::<_, TransferLeg>(
+            "SELECT id AS entry_id, budget_id, category_id, transfer_leg FROM entries
+             WHERE transfer_id = ? AND deleted_at IS NULL"
+        )
+        .bind(transfer_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    /// Soft-delete the transfer together with every entry it generated.
+    pub async fn delete(pool: &DbPool, transfer_id: &str, user_id: &str) -> Result<(), AppError> {
+        let now = chrono::Utc::now().naive_utc();
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query("UPDATE budget_transfers SET deleted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL")
+            .bind(now)
+            .bind(now)
+            .bind(transfer_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        sqlx::query("UPDATE entries SET deleted_at = ?, updated_by = ?, updated_at = ? WHERE transfer_id = ? AND deleted_at IS NULL")
+            .bind(now)
+            .bind(user_id)
+            .bind(now)
+            .bind(transfer_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
 }
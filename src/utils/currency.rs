@@ -0,0 +1,77 @@
+/// Number of minor units (decimal places) for an ISO 4217 currency code.
+pub fn minor_units(currency_code: &str) -> u32 {
+    match currency_code.to_uppercase().as_str() {
+        "VND" | "JPY" | "KRW" | "CLP" | "ISK" | "PYG" | "UGX" | "RWF" | "XAF" | "XOF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// Parse a human formatted amount ("1.234,56", "-12.50", "(30)", "1 000 000 ₫")
+/// into minor units. Anything that is not a digit, sign or the decimal
+/// separator is treated as a grouping character or symbol and ignored.
+pub fn parse_amount(raw: &str, decimal_separator: char, minor_units: u32) -> Result<i64, String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err("Amount is empty".to_string());
+    }
+
+    let negative = trimmed.starts_with('-') || trimmed.ends_with('-') || (trimmed.starts_with('(') && trimmed.ends_with(')'));
+    let cleaned: String = trimmed.chars().filter(|c| c.is_ascii_digit() || *c == decimal_separator).collect();
+    let (whole, fraction) = match cleaned.split_once(decimal_separator) {
+        Some((w, f)) => (w, f),
+        None => (cleaned.as_str(), ""),
+    };
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(format!("Invalid amount: {}", raw));
+    }
+    if fraction.contains(decimal_separator) {
+        return Err(format!("Invalid amount: {}", raw));
+    }
+
+    let units = minor_units as usize;
+    let significant = fraction.trim_end_matches('0');
+    if significant.len() > units {
+        return Err(format!("Amount {} has more than {} decimal places", raw, units));
+    }
+    let padded = format!("{:0<width$}", significant, width = units);
+
+    let digits = format!("{}{}", if whole.is_empty() { "0" } else { whole }, padded);
+    let value: i64 = digits.parse().map_err(|_| format!("Invalid amount: {}", raw))?;
+    Ok(if negative { -value } else { value })
+}
+
+/// Render minor units as a plain decimal string, e.g. (-123456, 2) -> "-1234.56".
+pub fn format_minor(amount_minor: i64, minor_units: u32) -> String {
+    if minor_units == 0 {
+        return amount_minor.to_string();
+    }
+    let divisor = 10i64.pow(minor_units);
+    let sign = if amount_minor < 0 { "-" } else { "" };
+    let abs = amount_minor.unsigned_abs();
+    format!("{}{}.{:0width$}", sign, abs / divisor as u64, abs % divisor as u64, width = minor_units as usize)
+}
+
+/// Convert minor units of `from` into minor units of `to`, where one unit of
+/// `from` is worth `rate` units of `to`. Rounds half away from zero.
+pub fn convert_minor(amount_minor: i64, from: &str, to: &str, rate: f64) -> i64 {
+    let scale = minor_units(to) as i32 - minor_units(from) as i32;
+    (amount_minor as f64 * rate * 10f64.powi(scale)).round() as i64
+}
+
+/// Split `total` across `parts` in proportion to their size. The shares always
+/// add up to `total`; rounding leftovers go to the largest remainders.
+pub fn allocate(total: i64, parts: &[i64]) -> Vec<i64> {
+    let sum: i64 = parts.iter().sum();
+    if sum == 0 {
+        return vec![0; parts.len()];
+    }
+    let mut shares: Vec<i64> = parts.iter().map(|p| (total as i128 * *p as i128 / sum as i128) as i64).collect();
+    let mut order: Vec<usize> = (0..parts.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse((total as i128 * parts[i] as i128 % sum as i128).abs()));
+    let leftover = total - shares.iter().sum::<i64>();
+    for &i in order.iter().cycle().take(leftover.unsigned_abs() as usize) {
+        shares[i] += leftover.signum();
+    }
+    shares
+}
@@ -1,12 +1,28 @@
 use axum::{http::StatusCode, response::{IntoResponse, Response}, Json};
+use serde::Serialize;
 use thiserror::Error;
 
+/// One invalid request field, e.g. `category_id` or `splits[1].amount_minor`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { field: field.into(), message: message.into() }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("not found")]
     NotFound,
     #[error("bad request: {0}")]
     BadRequest(String),
+    #[error("validation failed")]
+    Validation(Vec<FieldError>),
     #[error("unauthorized")]
     Unauthorized,
     #[error("forbidden")]
@@ -28,12 +44,16 @@ impl IntoResponse for AppError {
         let status = match self {
             AppError::NotFound => StatusCode::NOT_FOUND,
             AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
             AppError::Unauthorized => StatusCode::UNAUTHORIZED,
             AppError::Forbidden => StatusCode::FORBIDDEN,
             AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
             AppError::Db(_) | AppError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
         };
-        let body = Json(serde_json::json!({ "error": self.to_string() }));
+        let body = match &self {
+            AppError::Validation(fields) => Json(serde_json::json!({ "error": self.to_string(), "fields": fields })),
+            _ => Json(serde_json::json!({ "error": self.to_string() })),
+        };
         (status, body).into_response()
     }
 }
\ No newline at end of file
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use crate::manager::models::import::{ImportRowError, ParsedTransaction};
+use crate::utils::currency::{minor_units, parse_amount};
+use super::hashed_key;
+
+/// Parse an ISO 20022 camt.053 bank-to-customer statement. Every `Ntry` becomes
+/// one transaction: `CdtDbtInd` gives the kind, `BookgDt` the date, unstructured
+/// remittance info the description and the related party the counterparty.
+/// Entries must be in the budget's currency; unbooked entries are rejected.
+pub fn parse(data: &[u8], budget_currency: &str) -> Result<Vec<Result<ParsedTransaction, ImportRowError>>, String> {
+    let mut reader = Reader::from_reader(data);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut account = String::new();
+    let mut entry: Option<RawEntry> = None;
+    let mut results = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut found_statement = false;
+
+    loop {
+        let event = reader.read_event_into(&mut buf)
+            .map_err(|e| format!("Invalid XML at byte {}: {}", reader.buffer_position(), e))?;
+        match event {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                match name.as_str() {
+                    "Stmt" => {
+                        found_statement = true;
+                        account.clear();
+                    }
+                    "Ntry" if entry.is_none() => entry = Some(RawEntry::default()),
+                    "Amt" if path.last().map(|p| p.as_str()) == Some("Ntry") => {
+                        let ccy = e.try_get_attribute("Ccy").ok().flatten()
+                            .and_then(|a| a.unescape_value().ok().map(|v| v.to_string()));
+                        if let Some(raw) = entry.as_mut() {
+                            raw.currency = ccy;
+                        }
+                    }
+                    _ => {}
+                }
+                path.push(name);
+            }
+            Event::Text(t) => {
+                let text = t.unescape().map_err(|e| format!("Invalid XML text: {}", e))?.trim().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                match entry.as_mut() {
+                    Some(raw) => {
+                        let start = path.iter().rposition(|p| p == "Ntry").map(|i| i + 1).unwrap_or(path.len());
+                        raw.set(&path[start..].join("/"), text);
+                    }
+                    None => {
+                        let joined = path.join("/");
+                        if joined.ends_with("Stmt/Acct/Id/IBAN") || joined.ends_with("Stmt/Acct/Id/Othr/Id") {
+                            account = text;
+                        }
+                    }
+                }
+            }
+            Event::End(_) => {
+                let closed = path.pop();
+                if closed.as_deref() == Some("Ntry") && !path.iter().any(|p| p == "Ntry") {
+                    let raw = entry.take().unwrap_or_default();
+                    let row = results.len() + 1;
+                    results.push(raw.into_transaction(row, &account, budget_currency, &mut seen));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !found_statement {
+        return Err("Not a camt.053 statement (no Stmt element)".to_string());
+    }
+    Ok(results)
+}
+
+#[derive(Default)]
+struct RawEntry {
+    amount: Option<String>,
+    currency: Option<String>,
+    indicator: Option<String>,
+    status: Option<String>,
+    booking_date: Option<String>,
+    reference: Option<String>,
+    tx_reference: Option<String>,
+    remittance: Vec<String>,
+    additional_info: Option<String>,
+    debtor: Option<String>,
+    creditor: Option<String>,
+}
+
+impl RawEntry {
+    /// `path` is relative to the `Ntry` element
+    fn set(&mut self, path: &str, text: String) {
+        match path {
+            "Amt" => self.amount = Some(text),
+            "CdtDbtInd" => self.indicator = Some(text),
+            "Sts" | "Sts/Cd" => self.status = Some(text),
+            "BookgDt/Dt" | "BookgDt/DtTm" => self.booking_date = Some(text),
+            "AcctSvcrRef" => self.reference = Some(text),
+            "NtryRef" if self.reference.is_none() => self.reference = Some(text),
+            "AddtlNtryInf" => self.additional_info = Some(text),
+            p if p.ends_with("TxDtls/Refs/AcctSvcrRef") && self.tx_reference.is_none() => self.tx_reference = Some(text),
+            p if p.ends_with("RmtInf/Ustrd") || p.ends_with("RmtInf/Strd/CdtrRefInf/Ref") => self.remittance.push(text),
+            // camt.053.001.02 has Dbtr/Nm, later versions wrap it as Dbtr/Pty/Nm
+            p if (p.ends_with("RltdPties/Dbtr/Nm") || p.ends_with("RltdPties/Dbtr/Pty/Nm")) && self.debtor.is_none() => self.debtor = Some(text),
+            p if (p.ends_with("RltdPties/Cdtr/Nm") || p.ends_with("RltdPties/Cdtr/Pty/Nm")) && self.creditor.is_none() => self.creditor = Some(text),
+            _ => {}
+        }
+    }
+
+    fn into_transaction(self, row: usize, account: &str, budget_currency: &str, seen: &mut HashMap<String, usize>) -> Result<ParsedTransaction, ImportRowError> {
+        if let Some(status) = &self.status {
+            if !status.eq_ignore_ascii_case("BOOK") {
+                return Err(ImportRowError::new(row, Some("status"), format!("Entry is not booked (status {})", status)));
+            }
+        }
+
+        let currency = self.currency.as_deref().unwrap_or(budget_currency);
+        if !currency.eq_ignore_ascii_case(budget_currency) {
+            return Err(ImportRowError::new(row, Some("amount"), format!("Entry is in {} but the budget uses {}", currency, budget_currency)));
+        }
+        let raw_amount = self.amount.ok_or_else(|| ImportRowError::new(row, Some("amount"), "Amt is missing"))?;
+        let amount = parse_amount(&raw_amount, '.', minor_units(currency))
+            .map_err(|e| ImportRowError::new(row, Some("amount"), e))?;
+
+        let credit = match self.indicator.as_deref() {
+            Some("CRDT") => true,
+            Some("DBIT") => false,
+            Some(other) => return Err(ImportRowError::new(row, Some("kind"), format!("Unknown CdtDbtInd '{}'", other))),
+            None => return Err(ImportRowError::new(row, Some("kind"), "CdtDbtInd is missing")),
+        };
+
+        let raw_date = self.booking_date.ok_or_else(|| ImportRowError::new(row, Some("date"), "BookgDt is missing"))?;
+        let entry_date = raw_date.get(..10)
+            .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .ok_or_else(|| ImportRowError::new(row, Some("date"), format!("Invalid booking date '{}'", raw_date)))?;
+
+        let description = if self.remittance.is_empty() { self.additional_info } else { Some(self.remittance.join(" ")) };
+        let counterparty = if credit { self.debtor } else { self.creditor };
+
+        let dedupe_key = match self.reference.or(self.tx_reference) {
+            Some(reference) => format!("camt:{}:{}", account, reference),
+            None => {
+                let identity = format!(
+                    "{}|{}|{}|{}|{}",
+                    account,
+                    entry_date,
+                    if credit { amount } else { -amount },
+                    description.as_deref().unwrap_or(""),
+                    counterparty.as_deref().unwrap_or("")
+                );
+                let ordinal = seen.entry(identity.clone()).and_modify(|n| *n += 1).or_insert(1);
+                hashed_key("camt", &[&identity, &ordinal.to_string()])
+            }
+        };
+
+        Ok(ParsedTransaction {
+            row,
+            entry_date,
+            kind: if credit { "income" } else { "expense" }.to_string(),
+            amount_minor: amount.abs(),
+            description,
+            counterparty,
+            category_name: None,
+            dedupe_key: Some(dedupe_key),
+        })
+    }
+}
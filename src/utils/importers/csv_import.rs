@@ -0,0 +1,123 @@
+use crate::manager::models::import::{CsvColumnMapping, ImportRowError, ParsedTransaction};
+use crate::utils::currency::parse_amount;
+
+/// Parse CSV bytes into transactions using the given column mapping.
+/// Each data row yields either a parsed transaction or a row error; row
+/// numbers are 1-based and count the header line when present.
+pub fn parse(data: &[u8], mapping: &CsvColumnMapping, minor_units: u32) -> Result<Vec<Result<ParsedTransaction, ImportRowError>>, String> {
+    let has_header = mapping.has_header.unwrap_or(true);
+    let delimiter = mapping.delimiter.unwrap_or(',');
+    if !delimiter.is_ascii() {
+        return Err("Delimiter must be a single ASCII character".to_string());
+    }
+    if mapping.amount_column.is_none() && mapping.debit_column.is_none() && mapping.credit_column.is_none() {
+        return Err("Mapping needs an amount column or debit/credit columns".to_string());
+    }
+
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(has_header)
+        .delimiter(delimiter as u8)
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(data);
+
+    let headers: Vec<String> = if has_header {
+        reader.headers().map_err(|e| format!("Invalid CSV header: {}", e))?.iter().map(|h| h.to_string()).collect()
+    } else {
+        Vec::new()
+    };
+    let resolve = |name: &Option<String>| -> Result<Option<usize>, String> {
+        match name {
+            None => Ok(None),
+            Some(n) => headers.iter().position(|h| h.eq_ignore_ascii_case(n))
+                .or_else(|| n.parse::<usize>().ok())
+                .map(Some)
+                .ok_or_else(|| format!("Column not found: {}", n)),
+        }
+    };
+
+    let columns = Columns {
+        date: resolve(&Some(mapping.date_column.clone()))?.unwrap_or(0),
+        amount: resolve(&mapping.amount_column)?,
+        debit: resolve(&mapping.debit_column)?,
+        credit: resolve(&mapping.credit_column)?,
+        kind: resolve(&mapping.kind_column)?,
+        description: resolve(&mapping.description_column)?,
+        counterparty: resolve(&mapping.counterparty_column)?,
+        category: resolve(&mapping.category_column)?,
+        id: resolve(&mapping.id_column)?,
+        date_format: mapping.date_format.clone().unwrap_or_else(|| "%Y-%m-%d".to_string()),
+        decimal_separator: mapping.decimal_separator.unwrap_or('.'),
+        minor_units,
+    };
+
+    let first_row = if has_header { 2 } else { 1 };
+    let mut results = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let row = first_row + i;
+        match record {
+            Ok(r) if r.iter().all(|v| v.is_empty()) => continue,
+            Ok(r) => results.push(parse_row(row, &r, &columns)),
+            Err(e) => results.push(Err(ImportRowError::new(row, None, format!("Unreadable row: {}", e)))),
+        }
+    }
+
+    Ok(results)
+}
+
+struct Columns {
+    date: usize,
+    amount: Option<usize>,
+    debit: Option<usize>,
+    credit: Option<usize>,
+    kind: Option<usize>,
+    description: Option<usize>,
+    counterparty: Option<usize>,
+    category: Option<usize>,
+    id: Option<usize>,
+    date_format: String,
+    decimal_separator: char,
+    minor_units: u32,
+}
+
+fn parse_row(row: usize, record: &::csv::StringRecord, columns: &Columns) -> Result<ParsedTransaction, ImportRowError> {
+    let field = |col: Option<usize>| -> Option<String> {
+        col.and_then(|c| record.get(c)).map(|v| v.to_string()).filter(|v| !v.is_empty())
+    };
+    let amount = |col: Option<usize>, name: &str| -> Result<i64, ImportRowError> {
+        match field(col) {
+            Some(raw) => parse_amount(&raw, columns.decimal_separator, columns.minor_units)
+                .map_err(|e| ImportRowError::new(row, Some(name), e)),
+            None => Ok(0),
+        }
+    };
+
+    let raw_date = field(Some(columns.date)).ok_or_else(|| ImportRowError::new(row, Some("date"), "Date is empty"))?;
+    let entry_date = chrono::NaiveDate::parse_from_str(&raw_date, &columns.date_format)
+        .map_err(|_| ImportRowError::new(row, Some("date"), format!("Date '{}' does not match format {}", raw_date, columns.date_format)))?;
+
+    let signed = if columns.amount.is_some() {
+        amount(columns.amount, "amount")?
+    } else {
+        amount(columns.credit, "credit")?.abs() - amount(columns.debit, "debit")?.abs()
+    };
+
+    let kind = match field(columns.kind).map(|k| k.to_lowercase()) {
+        Some(k) if matches!(k.as_str(), "income" | "credit" | "in" | "cr") => "income",
+        Some(k) if matches!(k.as_str(), "expense" | "debit" | "out" | "dr") => "expense",
+        Some(k) => return Err(ImportRowError::new(row, Some("kind"), format!("Unknown kind '{}'", k))),
+        None if signed < 0 => "expense",
+        None => "income",
+    };
+
+    Ok(ParsedTransaction {
+        row,
+        entry_date,
+        kind: kind.to_string(),
+        amount_minor: signed.abs(),
+        description: field(columns.description),
+        counterparty: field(columns.counterparty),
+        category_name: field(columns.category),
+        dedupe_key: field(columns.id).map(|id| format!("csv:{}", id)),
+    })
+}
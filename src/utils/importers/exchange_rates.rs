@@ -0,0 +1,81 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use crate::manager::models::exchange_rate::RateRow;
+
+/// Parse a CSV of rates with a header naming `date`, `base`, `quote` and `rate`
+/// columns in any order. Dates are ISO (YYYY-MM-DD); any bad row fails the file.
+pub fn parse_csv(data: &[u8]) -> Result<Vec<RateRow>, String> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(data);
+    let headers = reader.headers().map_err(|e| format!("Invalid CSV header: {}", e))?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("Missing '{}' column", name));
+    let (date_col, base_col, quote_col, rate_col) = (column("date")?, column("base")?, column("quote")?, column("rate")?);
+
+    let mut rows = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        let record = record.map_err(|e| format!("Line {}: {}", line, e))?;
+        let field = |col: usize| record.get(col).unwrap_or_default();
+        let rate_date = chrono::NaiveDate::parse_from_str(field(date_col), "%Y-%m-%d")
+            .map_err(|_| format!("Line {}: invalid date '{}'", line, field(date_col)))?;
+        let rate = parse_rate(field(rate_col)).ok_or_else(|| format!("Line {}: invalid rate '{}'", line, field(rate_col)))?;
+        let base_currency = currency_code(field(base_col)).ok_or_else(|| format!("Line {}: invalid base currency '{}'", line, field(base_col)))?;
+        let quote_currency = currency_code(field(quote_col)).ok_or_else(|| format!("Line {}: invalid quote currency '{}'", line, field(quote_col)))?;
+        rows.push(RateRow { rate_date, base_currency, quote_currency, rate });
+    }
+    Ok(rows)
+}
+
+/// Parse the ECB euro foreign exchange reference rates (eurofxref-daily.xml,
+/// -hist-90d.xml or -hist.xml): `<Cube time="...">` groups of
+/// `<Cube currency="USD" rate="1.0956"/>`, all with EUR as the base.
+pub fn parse_ecb(data: &[u8]) -> Result<Vec<RateRow>, String> {
+    let mut reader = Reader::from_reader(data);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut date: Option<chrono::NaiveDate> = None;
+    let mut rows = Vec::new();
+    loop {
+        let event = reader.read_event_into(&mut buf)
+            .map_err(|e| format!("Invalid XML at byte {}: {}", reader.buffer_position(), e))?;
+        match event {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Cube" => {
+                let attr = |name: &str| e.try_get_attribute(name).ok().flatten()
+                    .and_then(|a| a.unescape_value().ok().map(|v| v.to_string()));
+                if let Some(time) = attr("time") {
+                    date = Some(chrono::NaiveDate::parse_from_str(&time, "%Y-%m-%d")
+                        .map_err(|_| format!("Invalid Cube time '{}'", time))?);
+                }
+                if let (Some(currency), Some(rate)) = (attr("currency"), attr("rate")) {
+                    let rate_date = date.ok_or("Rate outside of a dated Cube")?;
+                    rows.push(RateRow {
+                        rate_date,
+                        base_currency: "EUR".to_string(),
+                        quote_currency: currency_code(&currency).ok_or_else(|| format!("Invalid currency '{}'", currency))?,
+                        rate: parse_rate(&rate).ok_or_else(|| format!("Invalid rate '{}' for {}", rate, currency))?,
+                    });
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    if rows.is_empty() {
+        return Err("No rates found; expected an ECB eurofxref file".to_string());
+    }
+    Ok(rows)
+}
+
+fn parse_rate(raw: &str) -> Option<f64> {
+    raw.trim().parse::<f64>().ok().filter(|r| r.is_finite() && *r > 0.0)
+}
+
+fn currency_code(raw: &str) -> Option<String> {
+    let code = raw.trim().to_uppercase();
+    (code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic())).then_some(code)
+}
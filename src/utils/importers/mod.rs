@@ -0,0 +1,20 @@
+use sha2::{Digest, Sha256};
+
+pub mod camt053;
+pub mod csv_import;
+pub mod exchange_rates;
+pub mod ofx;
+pub mod prices;
+pub mod qif;
+
+/// Stable dedupe key for statement lines that carry no bank transaction id.
+pub fn hashed_key(source: &str, parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0x1f]);
+    }
+    let digest = hasher.finalize();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}:{}", source, hex)
+}
@@ -0,0 +1,71 @@
+use crate::manager::models::import::{ImportRowError, ParsedTransaction};
+use crate::utils::currency::parse_amount;
+
+/// Parse an OFX or QFX statement (SGML 1.x or XML 2.x). Leaf elements in
+/// SGML files have no closing tags, so values are read up to the next `<`.
+/// The dedupe key is the account id plus the bank's FITID.
+pub fn parse(data: &[u8], minor_units: u32) -> Result<Vec<Result<ParsedTransaction, ImportRowError>>, String> {
+    let text = String::from_utf8_lossy(data);
+    let upper = text.to_ascii_uppercase();
+    if !upper.contains("<OFX>") {
+        return Err("Not an OFX file".to_string());
+    }
+    let account = leaf_value(&text, &upper, "ACCTID").unwrap_or_default();
+
+    let mut results = Vec::new();
+    let mut cursor = 0;
+    let mut row = 0;
+    while let Some(start) = upper[cursor..].find("<STMTTRN>") {
+        let block_start = cursor + start;
+        let block_end = upper[block_start..].find("</STMTTRN>").map(|e| block_start + e)
+            .ok_or_else(|| "Unterminated STMTTRN block".to_string())?;
+        row += 1;
+        results.push(parse_transaction(row, &text[block_start..block_end], &upper[block_start..block_end], &account, minor_units));
+        cursor = block_end;
+    }
+
+    Ok(results)
+}
+
+fn parse_transaction(row: usize, block: &str, upper: &str, account: &str, minor_units: u32) -> Result<ParsedTransaction, ImportRowError> {
+    let raw_date = leaf_value(block, upper, "DTPOSTED")
+        .ok_or_else(|| ImportRowError::new(row, Some("date"), "DTPOSTED is missing"))?;
+    let entry_date = raw_date.get(..8)
+        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+        .ok_or_else(|| ImportRowError::new(row, Some("date"), format!("Invalid DTPOSTED '{}'", raw_date)))?;
+
+    let raw_amount = leaf_value(block, upper, "TRNAMT")
+        .ok_or_else(|| ImportRowError::new(row, Some("amount"), "TRNAMT is missing"))?;
+    // Some banks write the decimal part with a comma
+    let separator = if raw_amount.contains(',') && !raw_amount.contains('.') { ',' } else { '.' };
+    let amount = parse_amount(&raw_amount, separator, minor_units)
+        .map_err(|e| ImportRowError::new(row, Some("amount"), e))?;
+
+    let fitid = leaf_value(block, upper, "FITID")
+        .ok_or_else(|| ImportRowError::new(row, Some("fitid"), "FITID is missing"))?;
+    let name = leaf_value(block, upper, "NAME");
+    let memo = leaf_value(block, upper, "MEMO");
+
+    Ok(ParsedTransaction {
+        row,
+        entry_date,
+        kind: if amount < 0 { "expense" } else { "income" }.to_string(),
+        amount_minor: amount.abs(),
+        description: memo.or_else(|| name.clone()),
+        counterparty: name,
+        category_name: None,
+        dedupe_key: Some(format!("ofx:{}:{}", account, fitid)),
+    })
+}
+
+/// Text following `<TAG>` up to the next tag, with SGML/XML entities decoded.
+fn leaf_value(text: &str, upper: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = upper.find(&open)? + open.len();
+    let end = text[start..].find('<').map(|e| start + e).unwrap_or(text.len());
+    let value = text[start..end].trim()
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">");
+    if value.is_empty() { None } else { Some(value) }
+}
@@ -0,0 +1,33 @@
+use crate::manager::models::investment::{Holding, PriceRow};
+use crate::utils::currency::parse_amount;
+
+/// Parse a CSV of prices with a header naming `date`, `symbol` and `price`
+/// columns in any order. Dates are ISO (YYYY-MM-DD) and prices use a decimal
+/// point; any bad row fails the file.
+pub fn parse_csv(data: &[u8], minor_units: u32) -> Result<Vec<PriceRow>, String> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(data);
+    let headers = reader.headers().map_err(|e| format!("Invalid CSV header: {}", e))?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("Missing '{}' column", name));
+    let (date_col, symbol_col, price_col) = (column("date")?, column("symbol")?, column("price")?);
+
+    let mut rows = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        let record = record.map_err(|e| format!("Line {}: {}", line, e))?;
+        let field = |col: usize| record.get(col).unwrap_or_default();
+        let price_date = chrono::NaiveDate::parse_from_str(field(date_col), "%Y-%m-%d")
+            .map_err(|_| format!("Line {}: invalid date '{}'", line, field(date_col)))?;
+        let symbol = Holding::normalize_symbol(field(symbol_col))
+            .ok_or_else(|| format!("Line {}: invalid symbol '{}'", line, field(symbol_col)))?;
+        let price_minor = parse_amount(field(price_col), '.', minor_units)
+            .ok()
+            .filter(|p| *p > 0)
+            .ok_or_else(|| format!("Line {}: invalid price '{}'", line, field(price_col)))?;
+        rows.push(PriceRow { price_date, symbol, price_minor });
+    }
+    Ok(rows)
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use chrono::Datelike;
+use crate::manager::models::import::{ImportRowError, ParsedTransaction};
+use crate::utils::currency::parse_amount;
+use super::hashed_key;
+
+/// Parse a QIF bank or cash register. Records end with `^`; fields are
+/// D (date), T/U (amount), P (payee), M (memo), N (number) and L (category).
+/// QIF has no transaction ids, so the dedupe key hashes the record fields
+/// plus its position among identical records in the file.
+pub fn parse(data: &[u8], date_format: &str, decimal_separator: char, minor_units: u32) -> Result<Vec<Result<ParsedTransaction, ImportRowError>>, String> {
+    let text = String::from_utf8_lossy(data);
+    if !text.trim_start().starts_with("!Type:") {
+        return Err("Not a QIF file (missing !Type header)".to_string());
+    }
+
+    let mut results = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut fields: HashMap<char, String> = HashMap::new();
+    let mut row = 0;
+
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with('!') {
+            continue;
+        }
+        if line.starts_with('^') {
+            if !fields.is_empty() {
+                row += 1;
+                results.push(parse_record(row, &fields, date_format, decimal_separator, minor_units, &mut seen));
+                fields.clear();
+            }
+            continue;
+        }
+        let mut chars = line.chars();
+        if let Some(code) = chars.next() {
+            // Split lines (S/E/$) describe categories of a split; keep the first value of each field
+            fields.entry(code).or_insert_with(|| chars.as_str().trim().to_string());
+        }
+    }
+
+    Ok(results)
+}
+
+fn parse_record(
+    row: usize,
+    fields: &HashMap<char, String>,
+    date_format: &str,
+    decimal_separator: char,
+    minor_units: u32,
+    seen: &mut HashMap<String, usize>,
+) -> Result<ParsedTransaction, ImportRowError> {
+    let raw_date = fields.get(&'D').ok_or_else(|| ImportRowError::new(row, Some("date"), "D (date) is missing"))?;
+    // Quicken writes years after 2000 as 1/5'24 and pads days with spaces
+    let normalized = raw_date.replace('\'', "/").replace(' ', "");
+    let entry_date = chrono::NaiveDate::parse_from_str(&normalized, date_format)
+        .ok()
+        .filter(|d| d.year() >= 100)
+        .or_else(|| chrono::NaiveDate::parse_from_str(&normalized, &date_format.replace("%Y", "%y")).ok())
+        .ok_or_else(|| ImportRowError::new(row, Some("date"), format!("Date '{}' does not match format {}", raw_date, date_format)))?;
+
+    let raw_amount = fields.get(&'T').or_else(|| fields.get(&'U'))
+        .ok_or_else(|| ImportRowError::new(row, Some("amount"), "T (amount) is missing"))?;
+    let amount = parse_amount(raw_amount, decimal_separator, minor_units)
+        .map_err(|e| ImportRowError::new(row, Some("amount"), e))?;
+
+    let payee = fields.get(&'P').cloned().filter(|v| !v.is_empty());
+    let memo = fields.get(&'M').cloned().filter(|v| !v.is_empty());
+    // Transfers are written as [Account name]; only plain categories map to ours
+    let category = fields.get(&'L')
+        .filter(|l| !l.starts_with('['))
+        .map(|l| l.split(':').next().unwrap_or(l).to_string())
+        .filter(|v| !v.is_empty());
+
+    let identity = format!(
+        "{}|{}|{}|{}|{}",
+        entry_date,
+        amount,
+        payee.as_deref().unwrap_or(""),
+        memo.as_deref().unwrap_or(""),
+        fields.get(&'N').map(|n| n.as_str()).unwrap_or("")
+    );
+    let ordinal = seen.entry(identity.clone()).and_modify(|n| *n += 1).or_insert(1);
+
+    Ok(ParsedTransaction {
+        row,
+        entry_date,
+        kind: if amount < 0 { "expense" } else { "income" }.to_string(),
+        amount_minor: amount.abs(),
+        description: memo.or_else(|| payee.clone()),
+        counterparty: payee,
+        category_name: category,
+        dedupe_key: Some(hashed_key("qif", &[&identity, &ordinal.to_string()])),
+    })
+}
@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, BTreeSet};
+use chrono::NaiveDate;
+use crate::manager::models::export::JournalFormat;
+use crate::utils::currency;
+
+/// One leg of a journal transaction; the legs of a transaction sum to zero,
+/// legs with a `total_price` counting at that price.
+pub struct Posting {
+    pub account: String,
+    pub amount_minor: i64,
+    pub currency_code: String,
+    pub total_price: Option<(i64, String)>, // unsigned total cost in another commodity, rendered as `@@`
+}
+
+pub struct JournalTransaction {
+    pub date: NaiveDate,
+    pub payee: Option<String>,
+    pub narration: String,
+    pub id: String,
+    pub postings: Vec<Posting>,
+}
+
+/// Build a valid account name below `root` ("Assets", "Expenses", ...).
+/// Both tools require components to start with an uppercase letter or digit
+/// and beancount only allows letters, digits and dashes after that.
+pub fn account(root: &str, parts: &[&str]) -> String {
+    let mut name = root.to_string();
+    for part in parts {
+        name.push(':');
+        name.push_str(&account_component(part));
+    }
+    name
+}
+
+fn account_component(raw: &str) -> String {
+    let mut out = String::new();
+    for c in raw.trim().chars() {
+        if c.is_alphanumeric() {
+            out.push(c);
+        } else if !out.ends_with('-') && !out.is_empty() {
+            out.push('-');
+        }
+    }
+    let out = out.trim_end_matches('-').to_string();
+    match out.chars().next() {
+        None => "Unnamed".to_string(),
+        Some(first) if first.is_ascii_digit() || first.is_uppercase() => out,
+        Some(first) if first.to_uppercase().count() == 1 && first.to_uppercase().all(|u| u.is_uppercase()) => {
+            first.to_uppercase().chain(out.chars().skip(1)).collect()
+        }
+        Some(_) => format!("X{}", out),
+    }
+}
+
+/// Render transactions as an hledger journal or a beancount ledger, including
+/// the commodity and account declarations each tool expects.
+pub fn render(format: JournalFormat, title: &str, transactions: &mut [JournalTransaction]) -> String {
+    transactions.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.id.cmp(&b.id)));
+
+    let mut accounts: BTreeMap<&str, NaiveDate> = BTreeMap::new();
+    let mut commodities: BTreeSet<&str> = BTreeSet::new();
+    for t in transactions.iter() {
+        for p in &t.postings {
+            accounts.entry(&p.account).or_insert(t.date);
+            commodities.insert(&p.currency_code);
+        }
+    }
+
+    let mut out = String::new();
+    match format {
+        JournalFormat::Hledger => {
+            out.push_str(&format!("; {}\n\n", single_line(title)));
+            for code in &commodities {
+                out.push_str(&format!("commodity {} {}\n", currency::format_minor(100000, currency::minor_units(code)), code));
+            }
+            out.push('\n');
+            for account in accounts.keys() {
+                out.push_str(&format!("account {}\n", account));
+            }
+        }
+        JournalFormat::Beancount => {
+            out.push_str(&format!("option \"title\" \"{}\"\n", quoted(title)));
+            for code in &commodities {
+                out.push_str(&format!("option \"operating_currency\" \"{}\"\n", code));
+            }
+            out.push('\n');
+            for (account, opened) in &accounts {
+                out.push_str(&format!("{} open {}\n", opened, account));
+            }
+        }
+    }
+
+    for t in transactions.iter() {
+        out.push('\n');
+        match format {
+            JournalFormat::Hledger => {
+                // `;` starts a comment and `|` separates payee from note in hledger descriptions
+                let clean = |text: &str| single_line(text).replace(';', ",").replace('|', "/");
+                let description = match &t.payee {
+                    Some(payee) => format!("{} | {}", clean(payee), clean(&t.narration)),
+                    None => clean(&t.narration),
+                };
+                out.push_str(&format!("{} * {}  ; id:{}\n", t.date, description.trim(), t.id));
+            }
+            JournalFormat::Beancount => {
+                match &t.payee {
+                    Some(payee) => out.push_str(&format!("{} * \"{}\" \"{}\"\n", t.date, quoted(payee), quoted(&t.narration))),
+                    None => out.push_str(&format!("{} * \"{}\"\n", t.date, quoted(&t.narration))),
+                }
+                out.push_str(&format!("    id: \"{}\"\n", t.id));
+            }
+        }
+        for p in &t.postings {
+            let amount = currency::format_minor(p.amount_minor, currency::minor_units(&p.currency_code));
+            match &p.total_price {
+                Some((price_minor, price_code)) => {
+                    let price = currency::format_minor(*price_minor, currency::minor_units(price_code));
+                    out.push_str(&format!("    {}  {} {} @@ {} {}\n", p.account, amount, p.currency_code, price, price_code));
+                }
+                None => out.push_str(&format!("    {}  {} {}\n", p.account, amount, p.currency_code)),
+            }
+        }
+    }
+
+    out
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn quoted(text: &str) -> String {
+    single_line(text).replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -3,4 +3,7 @@ pub mod error;
 pub mod rate_limiter;
 pub mod image_processor;
 pub mod cleanup;
-pub mod s3_storage;
\ No newline at end of file
+pub mod s3_storage;
+pub mod currency;
+pub mod importers;
+pub mod journal;
@@ -145,6 +145,30 @@ impl S3Storage {
         Ok(format!("{}/{}", self.public_url, key))
     }
 
+    /// Download an attachment previously uploaded to this bucket
+    pub async fn download_attachment(&self, file_url: &str) -> Result<Vec<u8>, AppError> {
+        let key = file_url
+            .strip_prefix(&format!("{}/", self.public_url))
+            .ok_or_else(|| AppError::BadRequest("Invalid file URL format".to_string()))?;
+
+        let object = self.client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("S3 attachment download failed: {}", e);
+                AppError::Internal
+            })?;
+        let data = object.body.collect().await.map_err(|e| {
+            tracing::error!("S3 attachment download failed: {}", e);
+            AppError::Internal
+        })?;
+
+        Ok(data.into_bytes().to_vec())
+    }
+
     /// Delete attachment from S3
     pub async fn delete_attachment(&self, file_url: &str) -> Result<(), AppError> {
         // Extract key from URL